@@ -183,6 +183,7 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
             use_http_11: discover_opts.use_http_11,
             force,
             dry_run,
+            expected_schema_version: None,
         },
         DeploymentEndpoint::Lambda(arn) => RegisterDeploymentRequest::Lambda {
             arn: arn.to_string(),
@@ -190,6 +191,7 @@ pub async fn run_register(State(env): State<CliEnv>, discover_opts: &Register) -
             additional_headers: headers.clone().map(Into::into),
             force,
             dry_run,
+            expected_schema_version: None,
         },
     };
 
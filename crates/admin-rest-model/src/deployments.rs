@@ -132,6 +132,13 @@ pub enum RegisterDeploymentRequest {
         /// This is useful to see the impact of a new deployment before registering it.
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         dry_run: bool,
+
+        /// # Expected schema version
+        ///
+        /// If set, the deployment is only registered if the schema is still at this version,
+        /// otherwise the request fails with a conflict.
+        #[serde(default)]
+        expected_schema_version: Option<u32>,
     },
     Lambda {
         /// # ARN
@@ -166,6 +173,13 @@ pub enum RegisterDeploymentRequest {
         /// This is useful to see the impact of a new deployment before registering it.
         #[serde(default = "restate_serde_util::default::bool::<false>")]
         dry_run: bool,
+
+        /// # Expected schema version
+        ///
+        /// If set, the deployment is only registered if the schema is still at this version,
+        /// otherwise the request fails with a conflict.
+        #[serde(default)]
+        expected_schema_version: Option<u32>,
     },
 }
 
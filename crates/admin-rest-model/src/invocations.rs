@@ -0,0 +1,51 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_types::identifiers::InvocationId;
+use serde::{Deserialize, Serialize};
+
+pub use restate_types::retries::RetryPolicy;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvocationRetryStateResponse {
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub invocation_id: InvocationId,
+    /// # Retry count
+    ///
+    /// Number of attempts the invoker started for this invocation.
+    pub retry_count: u32,
+    /// # In flight
+    ///
+    /// If true, an attempt is currently running.
+    pub in_flight: bool,
+    /// # Last start at
+    ///
+    /// Time when the last attempt was started.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub last_start_at: humantime::Timestamp,
+    /// # Next retry at
+    ///
+    /// Time when the next attempt is scheduled, if the invocation is currently backing off.
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub next_retry_at: Option<humantime::Timestamp>,
+    /// # Last failure
+    ///
+    /// Error of the last failed attempt, if any.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_failure: Option<String>,
+    /// # Retry policy
+    ///
+    /// Backoff parameters in effect for this invocation.
+    pub retry_policy: RetryPolicy,
+}
@@ -10,6 +10,8 @@
 
 pub mod deployments;
 pub mod handlers;
+pub mod invocations;
+pub mod schema;
 pub mod services;
 pub mod subscriptions;
 pub mod version;
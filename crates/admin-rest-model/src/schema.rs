@@ -0,0 +1,80 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaPropagationResponse {
+    /// # Schema version
+    ///
+    /// Latest schema version stored in the metadata store, which is propagated to the nodes.
+    pub schema_version: u32,
+    /// # Complete
+    ///
+    /// If true, all nodes of the cluster reached the schema version.
+    pub complete: bool,
+    /// # Nodes
+    ///
+    /// Propagation progress of every node of the cluster.
+    pub nodes: Vec<NodeSchemaPropagation>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeSchemaPropagation {
+    /// # Node id
+    pub node_id: String,
+    /// # Node name
+    pub node_name: String,
+    pub status: SchemaPropagationStatus,
+    /// # Schema version
+    ///
+    /// Schema version last reported by the node, if it could be reached.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub schema_version: Option<u32>,
+    /// # Reason
+    ///
+    /// Why the node is still pending.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaPropagationStatus {
+    /// The node runs with the propagated schema version or a newer one.
+    UpToDate,
+    /// The node did not confirm the propagated schema version yet.
+    Pending,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    /// # Schema version
+    ///
+    /// Version of the exported schema. It is ignored when importing the snapshot.
+    pub schema_version: u32,
+    /// # Snapshot
+    ///
+    /// URL-safe base64 encoded snapshot of the deployments, services and subscriptions.
+    pub snapshot: String,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSchemaResponse {
+    /// # Schema version
+    ///
+    /// Version of the schema after importing the snapshot.
+    pub schema_version: u32,
+}
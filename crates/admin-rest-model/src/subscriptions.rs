@@ -45,6 +45,30 @@ pub struct CreateSubscriptionRequest {
     pub options: Option<HashMap<String, String>>,
 }
 
+#[serde_as]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSubscriptionRequest {
+    /// # Source
+    ///
+    /// Source uri of the subscription. The source cannot be changed, if provided it must match the current one.
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub source: Option<Uri>,
+    /// # Sink
+    ///
+    /// Sink uri of the subscription. The sink cannot be changed, if provided it must match the current one.
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+    pub sink: Option<Uri>,
+    /// # Options
+    ///
+    /// Options replacing the current options of the subscription.
+    pub options: HashMap<String, String>,
+}
+
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SubscriptionResponse {
@@ -0,0 +1,143 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use http::{HeaderName, HeaderValue, Method};
+use restate_types::config::CorsOptions;
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+
+use crate::Error;
+
+const DEFAULT_ALLOWED_METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+];
+
+pub(crate) fn cors_layer(opts: &CorsOptions) -> Result<CorsLayer, Error> {
+    let to_error = |reason: String| Error::Cors { reason };
+
+    let allow_origin = if opts.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            opts.allowed_origins
+                .iter()
+                .map(|origin| {
+                    HeaderValue::from_str(origin)
+                        .map_err(|_| to_error(format!("invalid origin '{origin}'")))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    };
+
+    let allow_methods = if opts.allowed_methods.is_empty() {
+        DEFAULT_ALLOWED_METHODS.to_vec()
+    } else {
+        opts.allowed_methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| to_error(format!("invalid method '{method}'")))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let allow_headers = if opts.allowed_headers.iter().any(|header| header == "*") {
+        AllowHeaders::any()
+    } else if opts.allowed_headers.is_empty() {
+        AllowHeaders::list([http::header::CONTENT_TYPE])
+    } else {
+        AllowHeaders::list(
+            opts.allowed_headers
+                .iter()
+                .map(|header| {
+                    HeaderName::from_bytes(header.as_bytes())
+                        .map_err(|_| to_error(format!("invalid header '{header}'")))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(allow_methods)
+        .allow_headers(allow_headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::routing::post;
+    use hyper::{Body, Request, StatusCode};
+    use restate_test_util::{assert_eq, let_assert};
+    use tower::ServiceExt;
+
+    fn preflight_request(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/query")
+            .header(http::header::ORIGIN, origin)
+            .header(http::header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(http::header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn preflight_request_returns_configured_headers() {
+        let opts = CorsOptions {
+            allowed_origins: vec!["https://example.com".to_owned()],
+            allowed_methods: vec!["post".to_owned()],
+            allowed_headers: vec!["content-type".to_owned()],
+        };
+        let router = axum::Router::new()
+            .route("/query", post(|| async {}))
+            .layer(cors_layer(&opts).unwrap());
+
+        let response = router
+            .clone()
+            .oneshot(preflight_request("https://example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers[http::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://example.com"
+        );
+        assert_eq!(headers[http::header::ACCESS_CONTROL_ALLOW_METHODS], "POST");
+        assert_eq!(
+            headers[http::header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type"
+        );
+
+        // other origins are not allowed
+        let response = router
+            .oneshot(preflight_request("https://evil.example.com"))
+            .await
+            .unwrap();
+        assert!(!response
+            .headers()
+            .contains_key(http::header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn invalid_origin_fails() {
+        let opts = CorsOptions {
+            allowed_origins: vec!["https://example.com\n".to_owned()],
+            ..CorsOptions::default()
+        };
+
+        let_assert!(Err(Error::Cors { .. }) = cors_layer(&opts));
+    }
+}
@@ -8,20 +8,17 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::net::SocketAddr;
-
 use codederror::CodedError;
 
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum Error {
-    #[error("failed binding to address '{address}' specified in 'admin.bind_address'")]
+    #[error("failed binding to the address specified in 'admin.bind_address': {0}")]
     #[code(restate_errors::RT0004)]
-    Binding {
-        address: SocketAddr,
-        #[source]
-        source: hyper::Error,
-    },
+    Binding(restate_grpc_util::Error),
+    #[error("invalid CORS configuration specified in 'admin.cors': {reason}")]
+    #[code(unknown)]
+    Cors { reason: String },
     #[error("error while running admin server: {0}")]
     #[code(unknown)]
-    Running(hyper::Error),
+    Running(restate_grpc_util::Error),
 }
@@ -22,6 +22,7 @@ use restate_admin_rest_model::deployments::*;
 use restate_service_client::Endpoint;
 use restate_service_protocol::discovery::DiscoverEndpoint;
 use restate_types::identifiers::InvalidLambdaARN;
+use restate_types::Version;
 use serde::Deserialize;
 
 /// Create deployment and return discovered services.
@@ -44,13 +45,14 @@ pub async fn create_deployment<V>(
     State(state): State<AdminServiceState<V>>,
     #[request_body(required = true)] Json(payload): Json<RegisterDeploymentRequest>,
 ) -> Result<impl IntoResponse, MetaApiError> {
-    let (discover_endpoint, force, dry_run) = match payload {
+    let (discover_endpoint, force, dry_run, expected_schema_version) = match payload {
         RegisterDeploymentRequest::Http {
             uri,
             additional_headers,
             use_http_11,
             force,
             dry_run,
+            expected_schema_version,
         } => (
             DiscoverEndpoint::new(
                 Endpoint::Http(
@@ -65,6 +67,7 @@ pub async fn create_deployment<V>(
             ),
             force,
             dry_run,
+            expected_schema_version,
         ),
         RegisterDeploymentRequest::Lambda {
             arn,
@@ -72,6 +75,7 @@ pub async fn create_deployment<V>(
             additional_headers,
             force,
             dry_run,
+            expected_schema_version,
         } => (
             DiscoverEndpoint::new(
                 Endpoint::Lambda(
@@ -84,6 +88,7 @@ pub async fn create_deployment<V>(
             ),
             force,
             dry_run,
+            expected_schema_version,
         ),
     };
 
@@ -101,7 +106,12 @@ pub async fn create_deployment<V>(
             log_error(
                 state
                     .schema_registry
-                    .register_deployment(discover_endpoint, force, apply_mode)
+                    .register_deployment(
+                        discover_endpoint,
+                        force,
+                        apply_mode,
+                        expected_schema_version.map(Version::from),
+                    )
                     .await,
             )
         })
@@ -20,7 +20,7 @@ use okapi_operation::okapi::map;
 use okapi_operation::okapi::openapi3::Responses;
 use okapi_operation::{okapi, Components, ToMediaTypes, ToResponses};
 use restate_core::ShutdownError;
-use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use restate_types::identifiers::{DeploymentId, InvocationId, SubscriptionId};
 use restate_types::invocation::ServiceType;
 use schemars::JsonSchema;
 use serde::Serialize;
@@ -42,6 +42,8 @@ pub enum MetaApiError {
     },
     #[error("The requested subscription '{0}' does not exist")]
     SubscriptionNotFound(SubscriptionId),
+    #[error("The requested invocation '{0}' does not exist or is completed")]
+    InvocationNotFound(InvocationId),
     #[error("Cannot {0} for service type {1}")]
     UnsupportedOperation(&'static str, ServiceType),
     #[error(transparent)]
@@ -70,15 +72,18 @@ impl IntoResponse for MetaApiError {
             MetaApiError::ServiceNotFound(_)
             | MetaApiError::HandlerNotFound { .. }
             | MetaApiError::DeploymentNotFound(_)
-            | MetaApiError::SubscriptionNotFound(_) => StatusCode::NOT_FOUND,
+            | MetaApiError::SubscriptionNotFound(_)
+            | MetaApiError::InvocationNotFound(_) => StatusCode::NOT_FOUND,
             MetaApiError::InvalidField(_, _) | MetaApiError::UnsupportedOperation(_, _) => {
                 StatusCode::BAD_REQUEST
             }
             MetaApiError::Schema(schema_error) => match schema_error {
                 SchemaError::NotFound(_) => StatusCode::NOT_FOUND,
                 SchemaError::Override(_)
+                | SchemaError::Conflict { .. }
                 | SchemaError::Service(ServiceError::DifferentType { .. })
                 | SchemaError::Service(ServiceError::RemovedHandlers { .. })
+                | SchemaError::Service(ServiceError::ChangedContentTypes { .. })
                 | SchemaError::Deployment(DeploymentError::IncorrectId { .. }) => {
                     StatusCode::CONFLICT
                 }
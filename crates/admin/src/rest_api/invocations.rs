@@ -14,11 +14,15 @@ use crate::rest_api::create_envelope_header;
 use crate::state::AdminServiceState;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::Json;
 use okapi_operation::*;
+use restate_admin_rest_model::invocations::{InvocationRetryStateResponse, RetryPolicy};
+use restate_node_services::node_svc::InvocationRetryStateRequest;
 use restate_types::identifiers::{InvocationId, WithPartitionKey};
 use restate_types::invocation::{InvocationTermination, PurgeInvocationRequest};
 use restate_wal_protocol::{append_envelope_to_bifrost, Command, Envelope};
 use serde::Deserialize;
+use std::time::{Duration, UNIX_EPOCH};
 use tracing::warn;
 
 #[derive(Debug, Default, Deserialize, JsonSchema)]
@@ -114,3 +118,55 @@ pub async fn delete_invocation<V>(
         Ok(StatusCode::ACCEPTED)
     }
 }
+
+/// Get the retry state of an invocation
+#[openapi(
+    summary = "Get invocation retry state",
+    description = "Get the retry state the invoker currently tracks for the given invocation, \
+    including the number of attempts, the time of the next scheduled retry and the backoff parameters in effect. \
+    Unknown or completed invocations are reported as not found.",
+    operation_id = "get_invocation_retry_state",
+    tags = "invocation",
+    parameters(path(
+        name = "invocation_id",
+        description = "Invocation identifier.",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn get_invocation_retry_state<V>(
+    State(mut state): State<AdminServiceState<V>>,
+    Path(invocation_id): Path<String>,
+) -> Result<Json<InvocationRetryStateResponse>, MetaApiError> {
+    let invocation_id = invocation_id
+        .parse::<InvocationId>()
+        .map_err(|e| MetaApiError::InvalidField("invocation_id", e.to_string()))?;
+
+    let response = state
+        .node_svc_client
+        .get_invocation_retry_state(InvocationRetryStateRequest {
+            invocation_id: invocation_id.to_string(),
+        })
+        .await
+        .map_err(|status| match status.code() {
+            tonic::Code::NotFound => MetaApiError::InvocationNotFound(invocation_id),
+            _ => MetaApiError::Internal(status.message().to_owned()),
+        })?
+        .into_inner();
+
+    let retry_policy: RetryPolicy = serde_json::from_slice(&response.retry_policy)
+        .map_err(|e| MetaApiError::Internal(format!("Cannot decode the retry policy: {e}")))?;
+
+    Ok(Json(InvocationRetryStateResponse {
+        invocation_id,
+        retry_count: response.retry_count,
+        in_flight: response.in_flight,
+        last_start_at: from_unix_millis(response.last_start_at),
+        next_retry_at: response.next_retry_at.map(from_unix_millis),
+        last_failure: response.last_failure,
+        retry_policy,
+    }))
+}
+
+fn from_unix_millis(millis: u64) -> humantime::Timestamp {
+    (UNIX_EPOCH + Duration::from_millis(millis)).into()
+}
@@ -15,12 +15,13 @@ mod error;
 mod handlers;
 mod health;
 mod invocations;
+mod schema;
 mod services;
 mod subscriptions;
 mod version;
 
 use codederror::CodedError;
-use okapi_operation::axum_integration::{delete, get, patch, post};
+use okapi_operation::axum_integration::{delete, get, patch, post, put};
 use okapi_operation::*;
 use restate_errors::warn_it;
 use restate_schema_api::subscription::SubscriptionValidator;
@@ -29,7 +30,12 @@ use restate_wal_protocol::{Destination, Header, Source};
 
 use crate::state::AdminServiceState;
 
-pub fn create_router<V>(state: AdminServiceState<V>) -> axum::Router<()>
+pub use version::BuildVersion;
+
+pub fn create_router<V>(
+    state: AdminServiceState<V>,
+    build_version: &BuildVersion,
+) -> axum::Router<()>
 where
     V: SubscriptionValidator + Send + Sync + Clone + 'static,
 {
@@ -76,6 +82,10 @@ where
             "/invocations/:invocation_id",
             delete(openapi_handler!(invocations::delete_invocation)),
         )
+        .route(
+            "/invocations/:invocation_id/retry-state",
+            get(openapi_handler!(invocations::get_invocation_retry_state)),
+        )
         .route(
             "/subscriptions",
             post(openapi_handler!(subscriptions::create_subscription)),
@@ -88,20 +98,45 @@ where
             "/subscriptions/:subscription",
             get(openapi_handler!(subscriptions::get_subscription)),
         )
+        .route(
+            "/subscriptions/:subscription",
+            patch(openapi_handler!(subscriptions::update_subscription)),
+        )
         .route(
             "/subscriptions/:subscription",
             delete(openapi_handler!(subscriptions::delete_subscription)),
         )
+        .route(
+            "/schema/propagation",
+            post(openapi_handler!(schema::propagate_schema)),
+        )
+        .route(
+            "/schema/snapshot",
+            get(openapi_handler!(schema::export_schema)),
+        )
+        .route(
+            "/schema/snapshot",
+            put(openapi_handler!(schema::import_schema)),
+        )
         .route("/health", get(openapi_handler!(health::health)))
         .route("/version", get(openapi_handler!(version::version)))
-        .route_openapi_specification(
-            "/openapi",
-            OpenApiBuilder::new("Admin API", env!("CARGO_PKG_VERSION")),
-        )
+        .route_openapi_specification("/openapi", openapi_builder(build_version, base_path))
         .expect("Error when building the OpenAPI specification")
         .with_state(state)
 }
 
+fn openapi_builder(build_version: &BuildVersion, base_path: Option<&str>) -> OpenApiBuilder {
+    let mut builder = OpenApiBuilder::new("Admin API", &build_version.openapi_version());
+    // let generated clients prefix the paths with the base path
+    if let Some(base_path) = base_path {
+        builder.server(okapi::openapi3::Server {
+            url: base_path.to_owned(),
+            ..Default::default()
+        });
+    }
+    builder
+}
+
 fn create_envelope_header(partition_key: PartitionKey) -> Header {
     Header {
         source: Source::ControlPlane {},
@@ -0,0 +1,290 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::error::*;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use base64::Engine;
+use okapi_operation::*;
+use restate_admin_rest_model::schema::{
+    ImportSchemaResponse, NodeSchemaPropagation, SchemaPropagationResponse,
+    SchemaPropagationStatus, SchemaSnapshot,
+};
+use restate_core::{metadata, MetadataKind};
+use restate_grpc_util::auth::BearerTokenInterceptor;
+use restate_grpc_util::create_grpc_channel_from_advertised_address;
+use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
+use restate_node_services::node_svc::SyncSchemaRequest;
+use restate_schema::SerializedSchema;
+use restate_types::net::AdvertisedAddress;
+use restate_types::{PlainNodeId, Version};
+use serde::Deserialize;
+use tokio::time::Instant;
+
+use crate::rest_api::log_error;
+use crate::state::AdminServiceState;
+
+const DEFAULT_PROPAGATION_TIMEOUT: Duration = Duration::from_secs(30);
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct PropagateSchemaParams {
+    pub timeout: Option<String>,
+}
+
+/// Propagate the schema to all nodes
+#[openapi(
+    summary = "Propagate schema",
+    description = "Asks every node of the cluster to fetch the latest schema version from the metadata store \
+    and waits until all nodes report it or the timeout expires. Nodes that could not be reached or did not \
+    catch up in time are reported as pending together with the reason.",
+    operation_id = "propagate_schema",
+    tags = "schema",
+    parameters(query(
+        name = "timeout",
+        description = "Maximum time to wait for the propagation, e.g. '10s'. Defaults to 30 seconds.",
+        required = false,
+        style = "simple",
+        allow_empty_value = false,
+        schema = "std::string::String",
+    ))
+)]
+pub async fn propagate_schema<V>(
+    State(state): State<AdminServiceState<V>>,
+    Query(PropagateSchemaParams { timeout }): Query<PropagateSchemaParams>,
+) -> Result<Json<SchemaPropagationResponse>, MetaApiError> {
+    let timeout = timeout
+        .map(|timeout| humantime::parse_duration(&timeout))
+        .transpose()
+        .map_err(|e| MetaApiError::InvalidField("timeout", e.to_string()))?
+        .unwrap_or(DEFAULT_PROPAGATION_TIMEOUT);
+
+    state
+        .task_center
+        .run_in_scope("propagate-schema", None, async move {
+            let metadata = metadata();
+            metadata
+                .sync(MetadataKind::Schema)
+                .await
+                .map_err(|e| MetaApiError::Internal(format!("Cannot sync the schema: {e}")))?;
+            let schema_version = metadata.schema_version();
+
+            let nodes_config = metadata.nodes_config();
+            let mut nodes = BTreeMap::new();
+            for (node_id, node) in nodes_config.iter() {
+                nodes.insert(
+                    node_id,
+                    (
+                        node.address.clone(),
+                        NodeSchemaPropagation {
+                            node_id: node_id.to_string(),
+                            node_name: node.name.clone(),
+                            status: SchemaPropagationStatus::Pending,
+                            schema_version: None,
+                            reason: Some("not contacted yet".to_owned()),
+                        },
+                    ),
+                );
+            }
+
+            propagate(
+                &mut nodes,
+                schema_version,
+                Instant::now() + timeout,
+                &state.grpc_interceptor,
+            )
+            .await;
+
+            let nodes: Vec<_> = nodes.into_values().map(|(_, node)| node).collect();
+            Ok(Json(SchemaPropagationResponse {
+                schema_version: schema_version.into(),
+                complete: nodes
+                    .iter()
+                    .all(|node| node.status == SchemaPropagationStatus::UpToDate),
+                nodes,
+            }))
+        })
+        .await
+}
+
+/// Export the schema
+#[openapi(
+    summary = "Export schema",
+    description = "Exports a snapshot of the deployments, services and subscriptions, which can be \
+    imported on another cluster.",
+    operation_id = "export_schema",
+    tags = "schema"
+)]
+pub async fn export_schema<V>(State(state): State<AdminServiceState<V>>) -> Json<SchemaSnapshot> {
+    let (schema_version, serialized_schema) =
+        state
+            .task_center
+            .run_in_scope_sync("export-schema", None, || {
+                state.schema_registry.export_schema()
+            });
+
+    Json(SchemaSnapshot {
+        schema_version: schema_version.into(),
+        snapshot: restate_base64_util::URL_SAFE.encode(bytes::Bytes::from(serialized_schema)),
+    })
+}
+
+/// Import a schema snapshot
+#[openapi(
+    summary = "Import schema",
+    description = "Replaces the deployments, services and subscriptions with the ones of a snapshot \
+    previously exported with the export schema API.",
+    operation_id = "import_schema",
+    tags = "schema"
+)]
+pub async fn import_schema<V>(
+    State(state): State<AdminServiceState<V>>,
+    #[request_body(required = true)] Json(SchemaSnapshot { snapshot, .. }): Json<SchemaSnapshot>,
+) -> Result<Json<ImportSchemaResponse>, MetaApiError> {
+    let serialized_schema = restate_base64_util::URL_SAFE
+        .decode(snapshot)
+        .map_err(|e| MetaApiError::InvalidField("snapshot", e.to_string()))?;
+
+    let schema_version = state
+        .task_center
+        .run_in_scope("import-schema", None, async {
+            log_error(
+                state
+                    .schema_registry
+                    .import_schema(SerializedSchema::from(bytes::Bytes::from(
+                        serialized_schema,
+                    )))
+                    .await,
+            )
+        })
+        .await?;
+
+    Ok(Json(ImportSchemaResponse {
+        schema_version: schema_version.into(),
+    }))
+}
+
+/// Asks the pending nodes to sync their schema until all of them reached the schema version or
+/// the deadline is hit.
+async fn propagate(
+    nodes: &mut BTreeMap<PlainNodeId, (AdvertisedAddress, NodeSchemaPropagation)>,
+    schema_version: Version,
+    deadline: Instant,
+    interceptor: &BearerTokenInterceptor,
+) {
+    loop {
+        let pending = nodes
+            .iter()
+            .filter(|(_, (_, node))| node.status == SchemaPropagationStatus::Pending)
+            .map(|(node_id, (address, _))| {
+                let node_id = *node_id;
+                let address = address.clone();
+                let interceptor = interceptor.clone();
+                async move {
+                    let result = tokio::time::timeout_at(
+                        deadline,
+                        sync_node_schema(address, schema_version, interceptor),
+                    )
+                    .await
+                    .unwrap_or_else(|_| Err("timed out waiting for the node".to_owned()));
+                    (node_id, result)
+                }
+            });
+        let results = futures::future::join_all(pending).await;
+        if results.is_empty() {
+            return;
+        }
+
+        for (node_id, result) in results {
+            let (_, node) = nodes.get_mut(&node_id).expect("node to be known");
+            match result {
+                Ok(node_version) => {
+                    node.schema_version = Some(node_version.into());
+                    if node_version >= schema_version {
+                        node.status = SchemaPropagationStatus::UpToDate;
+                        node.reason = None;
+                    } else {
+                        node.reason = Some(format!("node is at schema version {node_version}"));
+                    }
+                }
+                Err(reason) => node.reason = Some(format!("unreachable: {reason}")),
+            }
+        }
+
+        if Instant::now() + RETRY_INTERVAL >= deadline {
+            return;
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+async fn sync_node_schema(
+    address: AdvertisedAddress,
+    min_version: Version,
+    interceptor: BearerTokenInterceptor,
+) -> Result<Version, String> {
+    let channel =
+        create_grpc_channel_from_advertised_address(address).map_err(|e| e.to_string())?;
+    let response = NodeSvcClient::with_interceptor(channel, interceptor)
+        .sync_schema(SyncSchemaRequest {
+            min_version: Some(min_version.into()),
+        })
+        .await
+        .map_err(|status| status.message().to_owned())?
+        .into_inner();
+
+    Ok(response
+        .schema_version
+        .map(Version::from)
+        .unwrap_or(Version::INVALID))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_test_util::{assert, assert_eq, let_assert};
+
+    #[tokio::test]
+    async fn unreachable_nodes_stay_pending() {
+        let node_id = PlainNodeId::from(1);
+        let mut nodes = BTreeMap::from([(
+            node_id,
+            (
+                AdvertisedAddress::Uds("/does/not/exist.sock".into()),
+                NodeSchemaPropagation {
+                    node_id: node_id.to_string(),
+                    node_name: "node-1".to_owned(),
+                    status: SchemaPropagationStatus::Pending,
+                    schema_version: None,
+                    reason: None,
+                },
+            ),
+        )]);
+
+        propagate(
+            &mut nodes,
+            Version::MIN,
+            Instant::now() + Duration::from_millis(100),
+            &BearerTokenInterceptor::default(),
+        )
+        .await;
+
+        let (_, node) = &nodes[&node_id];
+        assert_eq!(node.status, SchemaPropagationStatus::Pending);
+        assert_eq!(node.schema_version, None);
+        let_assert!(Some(reason) = &node.reason);
+        assert!(reason.starts_with("unreachable"));
+    }
+}
@@ -141,6 +141,38 @@ pub async fn list_subscriptions<V>(
     .into()
 }
 
+/// Update subscription.
+#[openapi(
+    summary = "Update subscription",
+    description = "Update the options of a subscription. Source and sink cannot be changed.",
+    operation_id = "update_subscription",
+    tags = "subscription",
+    parameters(path(
+        name = "subscription",
+        description = "Subscription identifier",
+        schema = "std::string::String"
+    ))
+)]
+pub async fn update_subscription<V: SubscriptionValidator>(
+    State(state): State<AdminServiceState<V>>,
+    Path(subscription_id): Path<SubscriptionId>,
+    #[request_body(required = true)] Json(payload): Json<UpdateSubscriptionRequest>,
+) -> Result<Json<SubscriptionResponse>, MetaApiError> {
+    let subscription = log_error(
+        state
+            .schema_registry
+            .update_subscription(
+                subscription_id,
+                payload.source,
+                payload.sink,
+                payload.options,
+            )
+            .await,
+    )?;
+
+    Ok(SubscriptionResponse::from(subscription).into())
+}
+
 /// Delete subscription.
 #[openapi(
     summary = "Delete subscription",
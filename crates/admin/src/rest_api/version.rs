@@ -16,6 +16,36 @@ use restate_admin_rest_model::version::{AdminApiVersion, VersionInformation};
 pub const MIN_ADMIN_API_VERSION: AdminApiVersion = AdminApiVersion::V1;
 pub const MAX_ADMIN_API_VERSION: AdminApiVersion = AdminApiVersion::V1;
 
+/// Build version of the running node, which is reported as version of the OpenAPI specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildVersion {
+    pub version: String,
+    pub git_commit_sha: Option<String>,
+}
+
+impl BuildVersion {
+    pub fn new(version: impl Into<String>, git_commit_sha: Option<String>) -> Self {
+        Self {
+            version: version.into(),
+            git_commit_sha,
+        }
+    }
+
+    /// The version, with the git commit sha as build metadata if known.
+    pub fn openapi_version(&self) -> String {
+        match &self.git_commit_sha {
+            Some(git_commit_sha) => format!("{}+{git_commit_sha}", self.version),
+            None => self.version.clone(),
+        }
+    }
+}
+
+impl Default for BuildVersion {
+    fn default() -> Self {
+        Self::new(env!("CARGO_PKG_VERSION"), None)
+    }
+}
+
 /// Version information endpoint
 #[openapi(
     summary = "Admin version information",
@@ -30,3 +60,25 @@ pub async fn version() -> Json<VersionInformation> {
         max_admin_api_version: MAX_ADMIN_API_VERSION.as_repr(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openapi_version_defaults_to_crate_version() {
+        assert_eq!(
+            BuildVersion::default().openapi_version(),
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    #[test]
+    fn provided_build_version_overrides_default() {
+        assert_eq!(
+            BuildVersion::new("1.2.3", Some("abcdef".to_owned())).openapi_version(),
+            "1.2.3+abcdef"
+        );
+        assert_eq!(BuildVersion::new("1.2.3", None).openapi_version(), "1.2.3");
+    }
+}
@@ -16,8 +16,10 @@ use restate_core::ShutdownError;
 use restate_schema_api::invocation_target::BadInputContentType;
 use restate_types::endpoint_manifest;
 use restate_types::errors::GenericError;
-use restate_types::identifiers::DeploymentId;
+use restate_types::identifiers::{DeploymentId, SubscriptionId};
 use restate_types::invocation::ServiceType;
+use restate_types::Version;
+use std::num::NonZeroUsize;
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
 pub enum SchemaRegistryError {
@@ -50,6 +52,12 @@ pub enum SchemaError {
     #[error("already exists in the schema registry: {0}")]
     #[code(unknown)]
     Override(String),
+    #[error("the schema has been concurrently modified: expected version {expected}, current version {actual}")]
+    #[code(unknown)]
+    Conflict { expected: Version, actual: Version },
+    #[error("invalid schema snapshot: {0}")]
+    #[code(unknown)]
+    InvalidSnapshot(String),
 
     // Specific resources errors
     #[error(transparent)]
@@ -95,6 +103,19 @@ pub enum ServiceError {
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
+    #[error("the service '{0}' already exists but the new revision changed the input/output content-type of the handlers {1:?}, which might break existing callers")]
+    #[code(restate_errors::META0006)]
+    ChangedContentTypes(ServiceName, Vec<String>),
+    #[error("the service '{0}' advertises the handler '{1}' more than once")]
+    #[code(unknown)]
+    DuplicateHandler(ServiceName, String),
+    #[error("the service '{service}' has {handlers} handlers, but at most {max} handlers per service are allowed")]
+    #[code(unknown)]
+    TooManyHandlers {
+        service: ServiceName,
+        handlers: usize,
+        max: NonZeroUsize,
+    },
 }
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
@@ -106,6 +127,8 @@ pub enum SubscriptionError {
     InvalidSourceScheme(Uri),
     #[error("invalid source URI '{0}': source URI of Kafka type must have a authority segment containing the cluster name.")]
     InvalidKafkaSourceAuthority(Uri),
+    #[error("invalid source URI '{0}': the Kafka topic name must be non-empty, at most 249 characters long and contain only ASCII alphanumerics, '.', '_' and '-'.")]
+    InvalidKafkaTopic(Uri),
 
     #[error(
         "invalid sink URI '{0}': must have a scheme segment, with supported schemes: [service]."
@@ -118,6 +141,11 @@ pub enum SubscriptionError {
     #[error("invalid sink URI '{0}': shared handlers cannot be used as sinks.")]
     InvalidSinkSharedHandler(Uri),
 
+    #[error("cannot change the source of subscription '{0}' to '{1}': delete the subscription and create a new one instead.")]
+    SourceChanged(SubscriptionId, Uri),
+    #[error("cannot change the sink of subscription '{0}' to '{1}': delete the subscription and create a new one instead.")]
+    SinkChanged(SubscriptionId, Uri),
+
     #[error(transparent)]
     #[code(unknown)]
     Validation(GenericError),
@@ -131,6 +159,9 @@ pub enum DeploymentError {
         requested: DeploymentId,
         existing: DeploymentId,
     },
+    #[error("the deployment does not expose any service. Make sure that the deployment binds at least one service")]
+    #[code(unknown)]
+    NoServices,
 }
 
 impl From<ReadModifyWriteError<SchemaError>> for SchemaRegistryError {
@@ -16,7 +16,7 @@ use crate::schema_registry::updater::SchemaUpdater;
 use http::Uri;
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::{metadata, MetadataWriter};
-use restate_schema::Schema;
+use restate_schema::{Schema, SerializedSchema};
 use restate_schema_api::deployment::{
     DeliveryOptions, Deployment, DeploymentMetadata, DeploymentResolver,
 };
@@ -26,8 +26,10 @@ use restate_schema_api::subscription::{
 };
 use restate_service_client::Endpoint;
 use restate_service_protocol::discovery::{DiscoverEndpoint, ServiceDiscovery};
+use restate_types::config::Configuration;
 use restate_types::identifiers::{DeploymentId, ServiceRevision, SubscriptionId};
 use restate_types::metadata_store::keys::SCHEMA_INFORMATION_KEY;
+use restate_types::{Version, Versioned};
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -98,6 +100,7 @@ impl<V> SchemaRegistry<V> {
         discover_endpoint: DiscoverEndpoint,
         force: Force,
         apply_mode: ApplyMode,
+        expected_version: Option<Version>,
     ) -> Result<(DeploymentId, Vec<ServiceMetadata>), SchemaRegistryError> {
         // The number of concurrent discovery calls is bound by the number of concurrent
         // register_deployment calls. If it should become a problem that a user tries to register
@@ -120,8 +123,17 @@ impl<V> SchemaRegistry<V> {
             ),
         };
 
+        let default_idempotency_retention: Duration = Configuration::pinned()
+            .admin
+            .default_idempotency_retention
+            .into();
+        let max_handlers_per_service = Configuration::pinned().admin.max_handlers_per_service;
+
         let (id, services) = if !apply_mode.should_apply() {
-            let mut updater = SchemaUpdater::from(metadata().schema().deref().clone());
+            let mut updater = SchemaUpdater::from(metadata().schema().deref().clone())
+                .with_default_idempotency_retention(default_idempotency_retention)
+                .with_max_handlers_per_service(max_handlers_per_service)
+                .with_expected_version(expected_version);
 
             // suppress logging output in case of a dry run
             let id = tracing::subscriber::with_default(NoSubscriber::new(), || {
@@ -147,7 +159,10 @@ impl<V> SchemaRegistry<V> {
                     SCHEMA_INFORMATION_KEY.clone(),
                     |schema_information: Option<Schema>| {
                         let mut updater =
-                            SchemaUpdater::from(schema_information.unwrap_or_default());
+                            SchemaUpdater::from(schema_information.unwrap_or_default())
+                                .with_default_idempotency_retention(default_idempotency_retention)
+                                .with_max_handlers_per_service(max_handlers_per_service)
+                                .with_expected_version(expected_version);
 
                         new_deployment_id = Some(updater.add_deployment(
                             None,
@@ -270,6 +285,34 @@ impl<V> SchemaRegistry<V> {
         Ok(())
     }
 
+    /// Replaces the whole schema with the given snapshot and returns the new schema version.
+    pub async fn import_schema(
+        &self,
+        serialized_schema: SerializedSchema,
+    ) -> Result<Version, SchemaRegistryError> {
+        let schema_information = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::from(schema_information.unwrap_or_default());
+                    updater.import(serialized_schema.clone(), &self.subscription_validator)?;
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+
+        let version = schema_information.version();
+        self.metadata_writer.update(schema_information).await?;
+
+        Ok(version)
+    }
+
+    pub fn export_schema(&self) -> (Version, SerializedSchema) {
+        let schema = metadata().schema();
+        (schema.version(), schema.export())
+    }
+
     pub fn list_services(&self) -> Vec<ServiceMetadata> {
         metadata().schema().list_services()
     }
@@ -363,6 +406,40 @@ where
 
         Ok(subscription)
     }
+
+    pub async fn update_subscription(
+        &self,
+        subscription_id: SubscriptionId,
+        source: Option<Uri>,
+        sink: Option<Uri>,
+        options: HashMap<String, String>,
+    ) -> Result<Subscription, SchemaRegistryError> {
+        let schema_information = self
+            .metadata_store_client
+            .read_modify_write(
+                SCHEMA_INFORMATION_KEY.clone(),
+                |schema_information: Option<Schema>| {
+                    let mut updater = SchemaUpdater::from(schema_information.unwrap_or_default());
+                    updater.update_subscription(
+                        subscription_id,
+                        source.clone(),
+                        sink.clone(),
+                        options.clone(),
+                        &self.subscription_validator,
+                    )?;
+
+                    Ok::<_, SchemaError>(updater.into_inner())
+                },
+            )
+            .await?;
+
+        let subscription = schema_information
+            .get_subscription(subscription_id)
+            .expect("subscription was just updated");
+        self.metadata_writer.update(schema_information).await?;
+
+        Ok(subscription)
+    }
 }
 
 /// Newtype for service names
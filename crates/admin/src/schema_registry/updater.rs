@@ -15,7 +15,7 @@ use crate::schema_registry::{ModifyServiceChange, ServiceName};
 use http::{HeaderValue, Uri};
 use restate_schema::deployment::DeploymentSchemas;
 use restate_schema::service::{HandlerSchemas, ServiceLocation, ServiceSchemas};
-use restate_schema::Schema;
+use restate_schema::{Schema, SerializedSchema};
 use restate_schema_api::deployment::DeploymentMetadata;
 use restate_schema_api::invocation_target::{
     InputRules, InputValidationRule, InvocationTargetMetadata, OutputContentTypeRule, OutputRules,
@@ -29,18 +29,30 @@ use restate_types::identifiers::{DeploymentId, SubscriptionId};
 use restate_types::invocation::{
     InvocationTargetType, ServiceType, VirtualObjectHandlerType, WorkflowHandlerType,
 };
+use restate_types::{Version, Versioned};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::time::Duration;
 use tracing::{info, warn};
 
 /// Responsible for updating the provided [`Schema`] with new
 /// schema information. It makes sure that the version of schema information
 /// is incremented on changes.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SchemaUpdater {
     schema_information: Schema,
     modified: bool,
+    default_idempotency_retention: Duration,
+    expected_version: Option<Version>,
+    max_handlers_per_service: Option<NonZeroUsize>,
+}
+
+impl Default for SchemaUpdater {
+    fn default() -> Self {
+        Self::from(Schema::default())
+    }
 }
 
 impl From<Schema> for SchemaUpdater {
@@ -48,11 +60,48 @@ impl From<Schema> for SchemaUpdater {
         Self {
             schema_information,
             modified: false,
+            default_idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
+            expected_version: None,
+            max_handlers_per_service: None,
         }
     }
 }
 
 impl SchemaUpdater {
+    /// Sets the idempotency retention of services and handlers which are added.
+    pub fn with_default_idempotency_retention(
+        mut self,
+        default_idempotency_retention: Duration,
+    ) -> Self {
+        self.default_idempotency_retention = default_idempotency_retention;
+        self
+    }
+
+    /// Rejects deployments with services exposing more than the given number of handlers.
+    pub fn with_max_handlers_per_service(mut self, max_handlers_per_service: NonZeroUsize) -> Self {
+        self.max_handlers_per_service = Some(max_handlers_per_service);
+        self
+    }
+
+    /// Only adds deployments if the schema is at the expected version, to support
+    /// compare-and-swap updates by concurrent clients.
+    pub fn with_expected_version(mut self, expected_version: Option<Version>) -> Self {
+        self.expected_version = expected_version;
+        self
+    }
+
+    fn check_expected_version(&self) -> Result<(), SchemaError> {
+        match self.expected_version {
+            Some(expected) if expected != self.schema_information.version() => {
+                Err(SchemaError::Conflict {
+                    expected,
+                    actual: self.schema_information.version(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn into_inner(mut self) -> Schema {
         if self.modified {
             self.schema_information.increment_version()
@@ -64,10 +113,16 @@ impl SchemaUpdater {
     pub fn add_deployment(
         &mut self,
         requested_deployment_id: Option<DeploymentId>,
-        deployment_metadata: DeploymentMetadata,
+        mut deployment_metadata: DeploymentMetadata,
         services: Vec<endpoint_manifest::Service>,
         force: bool,
     ) -> Result<DeploymentId, SchemaError> {
+        self.check_expected_version()?;
+
+        if services.is_empty() {
+            return Err(SchemaError::Deployment(DeploymentError::NoServices));
+        }
+
         let deployment_id: Option<DeploymentId>;
 
         let proposed_services: HashMap<_, _> = services
@@ -98,6 +153,23 @@ impl SchemaUpdater {
             if force {
                 deployment_id = Some(*existing_deployment_id);
 
+                // Keep the previously configured headers unless new ones are provided
+                if deployment_metadata
+                    .delivery_options
+                    .additional_headers
+                    .is_empty()
+                {
+                    deployment_metadata
+                        .delivery_options
+                        .additional_headers
+                        .clone_from(
+                            &existing_deployment
+                                .metadata
+                                .delivery_options
+                                .additional_headers,
+                        );
+                }
+
                 for service in &existing_deployment.services {
                     // If a service is not available anymore in the new deployment, we need to remove it
                     if !proposed_services.contains_key(&service.name) {
@@ -127,6 +199,28 @@ impl SchemaUpdater {
 
         // Compute service schemas
         for (service_name, service) in proposed_services {
+            if let Some(max) = self.max_handlers_per_service {
+                if service.handlers.len() > max.get() {
+                    return Err(SchemaError::Service(ServiceError::TooManyHandlers {
+                        service: service_name,
+                        handlers: service.handlers.len(),
+                        max,
+                    }));
+                }
+            }
+
+            let mut handler_names = HashSet::with_capacity(service.handlers.len());
+            if let Some(duplicate) = service
+                .handlers
+                .iter()
+                .find(|handler| !handler_names.insert(handler.name.as_str()))
+            {
+                return Err(SchemaError::Service(ServiceError::DuplicateHandler(
+                    service_name,
+                    duplicate.name.to_string(),
+                )));
+            }
+
             let service_type = ServiceType::from(service.ty);
             let handlers = DiscoveredHandlerMetadata::compute_handlers(
                 service
@@ -134,6 +228,7 @@ impl SchemaUpdater {
                     .into_iter()
                     .map(|h| DiscoveredHandlerMetadata::from_schema(service_type, h))
                     .collect::<Result<Vec<_>, _>>()?,
+                self.default_idempotency_retention,
             );
 
             // For the time being when updating we overwrite existing data
@@ -181,6 +276,41 @@ impl SchemaUpdater {
                     }
                 }
 
+                let mut changed_content_types: Vec<String> =
+                    existing_service
+                        .handlers
+                        .iter()
+                        .filter(|(name, existing_handler)| {
+                            // only changes which existing callers might not be compatible with
+                            // count, adding a JSON schema or widening the input content-type is fine
+                            handlers.get(*name).is_some_and(|handler| {
+                                !handler.target_meta.input_rules.accepts_content_types_of(
+                                    &existing_handler.target_meta.input_rules,
+                                ) || handler.target_meta.output_rules.content_type()
+                                    != existing_handler.target_meta.output_rules.content_type()
+                            })
+                        })
+                        .map(|(name, _)| name.to_string())
+                        .collect();
+                changed_content_types.sort();
+
+                if !changed_content_types.is_empty() {
+                    if force {
+                        warn!(
+                            restate.deployment.id = %deployment_id,
+                            restate.deployment.address = %deployment_metadata.address_display(),
+                            "Going to change the input/output content-type of the following handlers of service {} due to a forced deployment update: {:?}. Existing callers might not be compatible anymore.",
+                            service_name,
+                            changed_content_types
+                        );
+                    } else {
+                        return Err(SchemaError::Service(ServiceError::ChangedContentTypes(
+                            service_name,
+                            changed_content_types,
+                        )));
+                    }
+                }
+
                 info!(
                     rpc.service = %service_name,
                     "Overwriting existing service schemas"
@@ -201,7 +331,7 @@ impl SchemaUpdater {
                         latest_deployment: deployment_id,
                         public: true,
                     },
-                    idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
+                    idempotency_retention: self.default_idempotency_retention,
                     workflow_completion_retention: if service_type == ServiceType::Workflow {
                         Some(DEFAULT_WORKFLOW_COMPLETION_RETENTION)
                     } else {
@@ -249,9 +379,13 @@ impl SchemaUpdater {
                     .services
                     .entry(service_metadata.name)
                 {
-                    // we need to check for the right revision in the service has been overwritten
-                    // by a different deployment.
-                    Entry::Occupied(entry) if entry.get().revision == service_metadata.revision => {
+                    // we need to check for the right revision and deployment in case the
+                    // service has been overwritten by a different deployment. Checking the
+                    // revision alone is not enough since it can wrap around.
+                    Entry::Occupied(entry)
+                        if entry.get().revision == service_metadata.revision
+                            && entry.get().location.latest_deployment == deployment_id =>
+                    {
                         entry.remove();
                     }
                     _ => {}
@@ -292,7 +426,12 @@ impl SchemaUpdater {
                         ))
                     })?
                     .as_str();
-                let topic_name = &source.path()[1..];
+                let topic_name = source.path().strip_prefix('/').unwrap_or_default();
+                if !is_valid_kafka_topic_name(topic_name) {
+                    return Err(SchemaError::Subscription(
+                        SubscriptionError::InvalidKafkaTopic(source),
+                    ));
+                }
                 Source::Kafka {
                     cluster: cluster_name.to_string(),
                     topic: topic_name.to_string(),
@@ -380,6 +519,98 @@ impl SchemaUpdater {
         Ok(id)
     }
 
+    /// Replaces the metadata of an existing subscription, keeping its id. The source and sink
+    /// cannot be changed, if provided they must match the ones of the existing subscription.
+    pub fn update_subscription<V: SubscriptionValidator>(
+        &mut self,
+        id: SubscriptionId,
+        source: Option<Uri>,
+        sink: Option<Uri>,
+        metadata: HashMap<String, String>,
+        validator: &V,
+    ) -> Result<(), SchemaError> {
+        let existing = self
+            .schema_information
+            .subscriptions
+            .get(&id)
+            .ok_or_else(|| SchemaError::NotFound(format!("subscription with id '{id}'")))?;
+
+        if let Some(source) = source {
+            if *existing.source() != source.to_string().as_str() {
+                return Err(SchemaError::Subscription(SubscriptionError::SourceChanged(
+                    id, source,
+                )));
+            }
+        }
+        if let Some(sink) = sink {
+            if *existing.sink() != sink.to_string().as_str() {
+                return Err(SchemaError::Subscription(SubscriptionError::SinkChanged(
+                    id, sink,
+                )));
+            }
+        }
+
+        let subscription = validator
+            .validate(Subscription::new(
+                id,
+                existing.source().clone(),
+                existing.sink().clone(),
+                metadata,
+            ))
+            .map_err(|e| SchemaError::Subscription(SubscriptionError::Validation(e.into())))?;
+
+        self.schema_information
+            .subscriptions
+            .insert(id, subscription);
+        self.modified = true;
+
+        Ok(())
+    }
+
+    /// Replaces the deployments, services and subscriptions with the ones of the snapshot. The
+    /// version of the snapshot is ignored, the current version is incremented once instead. The
+    /// subscriptions are validated like newly added ones.
+    pub fn import<V: SubscriptionValidator>(
+        &mut self,
+        serialized_schema: SerializedSchema,
+        validator: &V,
+    ) -> Result<(), SchemaError> {
+        let mut imported = serialized_schema
+            .deserialize()
+            .map_err(|e| SchemaError::InvalidSnapshot(e.to_string()))?;
+
+        for (service_name, service) in &imported.services {
+            if !imported
+                .deployments
+                .contains_key(&service.location.latest_deployment)
+            {
+                return Err(SchemaError::InvalidSnapshot(format!(
+                    "service '{service_name}' references the unknown deployment '{}'",
+                    service.location.latest_deployment
+                )));
+            }
+        }
+        for subscription in imported.subscriptions.values_mut() {
+            let Sink::Service { name, .. } = subscription.sink();
+            if !imported.services.contains_key(name) {
+                return Err(SchemaError::InvalidSnapshot(format!(
+                    "subscription '{}' references the unknown service '{name}'",
+                    subscription.id()
+                )));
+            }
+            *subscription = validator
+                .validate(subscription.clone())
+                .map_err(|e| SchemaError::Subscription(SubscriptionError::Validation(e.into())))?;
+        }
+
+        self.schema_information.services = imported.services;
+        self.schema_information.deployments = imported.deployments;
+        self.schema_information.subscriptions = imported.subscriptions;
+        self.modified = true;
+
+        Ok(())
+    }
+
     pub fn remove_subscription(&mut self, subscription_id: SubscriptionId) {
         if self
             .schema_information
@@ -445,6 +676,8 @@ struct DiscoveredHandlerMetadata {
     ty: InvocationTargetType,
     input: InputRules,
     output: OutputRules,
+    idempotency_retention: Option<Duration>,
+    journal_retention: Option<Duration>,
 }
 
 impl DiscoveredHandlerMetadata {
@@ -490,6 +723,8 @@ impl DiscoveredHandlerMetadata {
                 .map(DiscoveredHandlerMetadata::output_rules_from_schema)
                 .transpose()?
                 .unwrap_or_default(),
+            idempotency_retention: handler.idempotency_retention.map(Duration::from_millis),
+            journal_retention: handler.journal_retention.map(Duration::from_millis),
         })
     }
 
@@ -548,6 +783,7 @@ impl DiscoveredHandlerMetadata {
 
     fn compute_handlers(
         handlers: Vec<DiscoveredHandlerMetadata>,
+        idempotency_retention: Duration,
     ) -> HashMap<String, HandlerSchemas> {
         handlers
             .into_iter()
@@ -557,7 +793,10 @@ impl DiscoveredHandlerMetadata {
                     HandlerSchemas {
                         target_meta: InvocationTargetMetadata {
                             public: true,
-                            idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
+                            idempotency_retention: handler
+                                .idempotency_retention
+                                .unwrap_or(idempotency_retention),
+                            journal_retention: handler.journal_retention,
                             completion_retention: if handler.ty
                                 == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
                             {
@@ -576,12 +815,27 @@ impl DiscoveredHandlerMetadata {
     }
 }
 
+/// Kafka only accepts topic names of at most 249 ASCII alphanumerics, '.', '_' and '-', except
+/// for '.' and '..'.
+fn is_valid_kafka_topic_name(topic_name: &str) -> bool {
+    !topic_name.is_empty()
+        && topic_name.len() <= 249
+        && topic_name != "."
+        && topic_name != ".."
+        && topic_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use restate_schema::diff::ServiceDiff;
     use restate_schema_api::deployment::{Deployment, DeploymentResolver};
+    use restate_schema_api::invocation_target::InvocationTargetResolver;
     use restate_schema_api::service::ServiceMetadataResolver;
+    use restate_schema_api::subscription::SubscriptionResolver;
     use restate_test_util::{assert, assert_eq, let_assert};
 
     use restate_types::Versioned;
@@ -599,6 +853,8 @@ mod tests {
                 ty: None,
                 input: None,
                 output: None,
+                idempotency_retention: None,
+                journal_retention: None,
             }],
         }
     }
@@ -612,6 +868,8 @@ mod tests {
                 ty: None,
                 input: None,
                 output: None,
+                idempotency_retention: None,
+                journal_retention: None,
             }],
         }
     }
@@ -625,10 +883,411 @@ mod tests {
                 ty: None,
                 input: None,
                 output: None,
+                idempotency_retention: None,
+                journal_retention: None,
             }],
         }
     }
 
+    struct AcceptAllSubscriptions;
+
+    impl SubscriptionValidator for AcceptAllSubscriptions {
+        type Error = std::convert::Infallible;
+
+        fn validate(&self, subscription: Subscription) -> Result<Subscription, Self::Error> {
+            Ok(subscription)
+        }
+    }
+
+    #[test]
+    fn list_added_subscriptions_sorted_by_id() {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment = Deployment::mock();
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+
+        let subscription_ids: Vec<_> = ["orders", "payments"]
+            .into_iter()
+            .map(|topic| {
+                updater
+                    .add_subscription(
+                        None,
+                        format!("kafka://my-cluster/{topic}").parse().unwrap(),
+                        format!("service://{GREETER_SERVICE_NAME}/greet")
+                            .parse()
+                            .unwrap(),
+                        Some(HashMap::from([("group.id".to_owned(), topic.to_owned())])),
+                        &AcceptAllSubscriptions,
+                    )
+                    .unwrap()
+            })
+            .collect();
+
+        let schema = updater.into_inner();
+        let subscriptions = schema.list_subscriptions(&[]);
+
+        let mut expected_ids = subscription_ids.clone();
+        expected_ids.sort();
+        assert_eq!(
+            subscriptions.iter().map(|sub| sub.id()).collect::<Vec<_>>(),
+            expected_ids
+        );
+
+        let orders = schema.get_subscription(subscription_ids[0]).unwrap();
+        let_assert!(Source::Kafka { cluster, topic } = orders.source());
+        assert_eq!(cluster, "my-cluster");
+        assert_eq!(topic, "orders");
+        assert_eq!(orders.metadata().get("group.id").unwrap(), "orders");
+    }
+
+    fn updater_with_subscription() -> (SchemaUpdater, SubscriptionId) {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment = Deployment::mock();
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let subscription_id = updater
+            .add_subscription(
+                None,
+                "kafka://my-cluster/orders".parse().unwrap(),
+                format!("service://{GREETER_SERVICE_NAME}/greet")
+                    .parse()
+                    .unwrap(),
+                Some(HashMap::from([("group.id".to_owned(), "v1".to_owned())])),
+                &AcceptAllSubscriptions,
+            )
+            .unwrap();
+
+        (SchemaUpdater::from(updater.into_inner()), subscription_id)
+    }
+
+    #[test]
+    fn diff_services_and_deployments() {
+        let greeter_deployment = Deployment::mock_with_uri("http://localhost:9080");
+        let another_deployment = Deployment::mock_with_uri("http://localhost:9081");
+
+        let mut updater = SchemaUpdater::default();
+        updater
+            .add_deployment(
+                Some(greeter_deployment.id),
+                greeter_deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let old_schema = updater.into_inner();
+        assert!(old_schema.diff(&old_schema).is_empty());
+
+        let mut updater = SchemaUpdater::from(old_schema.clone());
+        updater
+            .add_deployment(
+                Some(another_deployment.id),
+                another_deployment.metadata.clone(),
+                vec![greeter_service(), another_greeter_service()],
+                false,
+            )
+            .unwrap();
+        let new_schema = updater.into_inner();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.added_services, vec![ANOTHER_GREETER_SERVICE_NAME]);
+        assert!(diff.removed_services.is_empty());
+        assert_eq!(diff.added_deployments, vec![another_deployment.id]);
+        assert!(diff.removed_deployments.is_empty());
+        assert_eq!(
+            diff.changed_services,
+            vec![ServiceDiff {
+                name: GREETER_SERVICE_NAME.to_owned(),
+                deployment_changed: true,
+                ..ServiceDiff::default()
+            }]
+        );
+
+        let diff = new_schema.diff(&old_schema);
+        assert!(diff.added_services.is_empty());
+        assert_eq!(diff.removed_services, vec![ANOTHER_GREETER_SERVICE_NAME]);
+        assert_eq!(diff.removed_deployments, vec![another_deployment.id]);
+    }
+
+    #[test]
+    fn diff_subscriptions() {
+        let (updater, subscription_id) = updater_with_subscription();
+        let old_schema = updater.into_inner();
+
+        let mut updater = SchemaUpdater::from(old_schema.clone());
+
+        updater
+            .update_subscription(
+                subscription_id,
+                None,
+                None,
+                HashMap::from([("group.id".to_owned(), "v2".to_owned())]),
+                &AcceptAllSubscriptions,
+            )
+            .unwrap();
+        let new_subscription_id = updater
+            .add_subscription(
+                None,
+                "kafka://my-cluster/payments".parse().unwrap(),
+                format!("service://{GREETER_SERVICE_NAME}/greet")
+                    .parse()
+                    .unwrap(),
+                None,
+                &AcceptAllSubscriptions,
+            )
+            .unwrap();
+        let new_schema = updater.into_inner();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.added_subscriptions, vec![new_subscription_id]);
+        assert!(diff.removed_subscriptions.is_empty());
+        assert_eq!(diff.changed_subscriptions, vec![subscription_id]);
+        assert!(diff.changed_services.is_empty());
+    }
+
+    #[test]
+    fn discovered_handler_retentions() {
+        let handler = |idempotency_retention, journal_retention| endpoint_manifest::Handler {
+            name: "greet".parse().unwrap(),
+            ty: None,
+            input: None,
+            output: None,
+            idempotency_retention,
+            journal_retention,
+        };
+
+        let discovered =
+            DiscoveredHandlerMetadata::from_schema(ServiceType::Service, handler(None, None))
+                .unwrap();
+        assert_eq!(discovered.idempotency_retention, None);
+        assert_eq!(discovered.journal_retention, None);
+
+        let discovered = DiscoveredHandlerMetadata::from_schema(
+            ServiceType::Service,
+            handler(Some(60_000), Some(3_600_000)),
+        )
+        .unwrap();
+        assert_eq!(
+            discovered.idempotency_retention,
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            discovered.journal_retention,
+            Some(Duration::from_secs(60 * 60))
+        );
+
+        let handlers = DiscoveredHandlerMetadata::compute_handlers(
+            vec![discovered],
+            DEFAULT_IDEMPOTENCY_RETENTION,
+        );
+        let target_meta = &handlers["greet"].target_meta;
+        assert_eq!(target_meta.idempotency_retention, Duration::from_secs(60));
+        assert_eq!(
+            target_meta.journal_retention,
+            Some(Duration::from_secs(60 * 60))
+        );
+    }
+
+    #[test]
+    fn advertised_retentions_survive_schema_serialization() {
+        let mut service = greeter_service();
+        service.handlers[0].journal_retention = Some(3_600_000);
+
+        let mut updater = SchemaUpdater::default();
+        let deployment = Deployment::mock();
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata,
+                vec![service],
+                false,
+            )
+            .unwrap();
+        let schema = updater.into_inner().export().deserialize().unwrap();
+
+        let target_meta = schema
+            .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+            .unwrap();
+        assert_eq!(
+            target_meta.idempotency_retention,
+            DEFAULT_IDEMPOTENCY_RETENTION
+        );
+        assert_eq!(
+            target_meta.journal_retention,
+            Some(Duration::from_secs(60 * 60))
+        );
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let mut updater = SchemaUpdater::default();
+        let greeter_deployment = Deployment::mock_with_uri("http://localhost:9080");
+        updater
+            .add_deployment(
+                Some(greeter_deployment.id),
+                greeter_deployment.metadata,
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let another_deployment = Deployment::mock_with_uri("http://localhost:9081");
+        updater
+            .add_deployment(
+                Some(another_deployment.id),
+                another_deployment.metadata,
+                vec![another_greeter_service()],
+                false,
+            )
+            .unwrap();
+        for topic in ["orders", "payments"] {
+            updater
+                .add_subscription(
+                    None,
+                    format!("kafka://my-cluster/{topic}").parse().unwrap(),
+                    format!("service://{GREETER_SERVICE_NAME}/greet")
+                        .parse()
+                        .unwrap(),
+                    None,
+                    &AcceptAllSubscriptions,
+                )
+                .unwrap();
+        }
+        let exported_schema = updater.into_inner();
+
+        let mut updater = SchemaUpdater::default();
+        updater
+            .import(exported_schema.export(), &AcceptAllSubscriptions)
+            .unwrap();
+        let imported_schema = updater.into_inner();
+
+        assert_eq!(imported_schema.version(), Version::MIN);
+        assert_eq!(imported_schema, exported_schema);
+    }
+
+    #[test]
+    fn import_validates_subscriptions() {
+        struct RejectAllSubscriptions;
+
+        impl SubscriptionValidator for RejectAllSubscriptions {
+            type Error = std::io::Error;
+
+            fn validate(&self, _: Subscription) -> Result<Subscription, Self::Error> {
+                Err(std::io::Error::other("rejected"))
+            }
+        }
+
+        let (updater, _) = updater_with_subscription();
+        let schema = updater.into_inner();
+
+        let mut updater = SchemaUpdater::default();
+        let_assert!(
+            Err(SchemaError::Subscription(SubscriptionError::Validation(_))) =
+                updater.import(schema.export(), &RejectAllSubscriptions)
+        );
+        assert!(!updater.modified);
+    }
+
+    #[test]
+    fn import_rejects_services_of_unknown_deployments() {
+        let (updater, _) = updater_with_subscription();
+        let mut schema = updater.into_inner();
+        schema.deployments.clear();
+
+        let mut updater = SchemaUpdater::default();
+        let_assert!(
+            Err(SchemaError::InvalidSnapshot(_)) =
+                updater.import(schema.export(), &AcceptAllSubscriptions)
+        );
+        assert!(!updater.modified);
+    }
+
+    #[test]
+    fn add_subscription_rejects_invalid_kafka_topics() {
+        let (mut updater, _) = updater_with_subscription();
+
+        for source in [
+            "kafka://my-cluster/",
+            "kafka://my-cluster/orders/eu",
+            "kafka://my-cluster/ord%20ers",
+        ] {
+            let_assert!(
+                Err(SchemaError::Subscription(
+                    SubscriptionError::InvalidKafkaTopic(_)
+                )) = updater.add_subscription(
+                    None,
+                    source.parse().unwrap(),
+                    format!("service://{GREETER_SERVICE_NAME}/greet")
+                        .parse()
+                        .unwrap(),
+                    None,
+                    &AcceptAllSubscriptions,
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn update_subscription_metadata() {
+        let (mut updater, subscription_id) = updater_with_subscription();
+
+        updater
+            .update_subscription(
+                subscription_id,
+                Some("kafka://my-cluster/orders".parse().unwrap()),
+                None,
+                HashMap::from([("group.id".to_owned(), "v2".to_owned())]),
+                &AcceptAllSubscriptions,
+            )
+            .unwrap();
+        assert!(updater.modified);
+
+        let subscription = updater
+            .into_inner()
+            .get_subscription(subscription_id)
+            .unwrap();
+        assert_eq!(*subscription.source(), "kafka://my-cluster/orders");
+        assert_eq!(subscription.metadata().get("group.id").unwrap(), "v2");
+    }
+
+    #[test]
+    fn update_subscription_rejects_source_change() {
+        let (mut updater, subscription_id) = updater_with_subscription();
+
+        let_assert!(
+            Err(SchemaError::Subscription(SubscriptionError::SourceChanged(
+                id,
+                _
+            ))) = updater.update_subscription(
+                subscription_id,
+                Some("kafka://my-cluster/payments".parse().unwrap()),
+                None,
+                HashMap::new(),
+                &AcceptAllSubscriptions,
+            )
+        );
+        assert_eq!(id, subscription_id);
+        assert!(!updater.modified);
+
+        let subscription = updater
+            .into_inner()
+            .get_subscription(subscription_id)
+            .unwrap();
+        assert_eq!(subscription.metadata().get("group.id").unwrap(), "v1");
+    }
+
     #[test]
     fn register_new_deployment() {
         let schema_information = Schema::default();
@@ -656,6 +1315,302 @@ mod tests {
         schema.assert_service_handler(GREETER_SERVICE_NAME, "greet");
     }
 
+    #[test]
+    fn detect_changed_content_type() {
+        let greeter_with_input = |content_type: &str| {
+            let mut service = greeter_service();
+            service.handlers[0].input = Some(
+                serde_json::from_value(serde_json::json!({ "contentType": content_type })).unwrap(),
+            );
+            service
+        };
+
+        let mut updater = SchemaUpdater::default();
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        updater
+            .add_deployment(
+                Some(deployment_1.id),
+                deployment_1.metadata,
+                vec![greeter_with_input("application/json")],
+                false,
+            )
+            .unwrap();
+
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::ChangedContentTypes(
+                _,
+                handlers
+            ))) = updater.add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata.clone(),
+                vec![greeter_with_input("application/octet-stream")],
+                false,
+            )
+        );
+        assert_eq!(handlers, vec!["greet".to_owned()]);
+
+        updater
+            .add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata,
+                vec![greeter_with_input("application/octet-stream")],
+                true,
+            )
+            .unwrap();
+        let schema = updater.into_inner();
+        schema.assert_service_revision(GREETER_SERVICE_NAME, 2);
+        schema.assert_service_deployment(GREETER_SERVICE_NAME, deployment_2.id);
+    }
+
+    #[test]
+    fn accept_compatible_content_type_changes() {
+        let greeter_with_input = |input: serde_json::Value| {
+            let mut service = greeter_service();
+            service.handlers[0].input = Some(serde_json::from_value(input).unwrap());
+            service
+        };
+
+        let mut updater = SchemaUpdater::default();
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        updater
+            .add_deployment(
+                Some(deployment_1.id),
+                deployment_1.metadata,
+                vec![greeter_with_input(
+                    serde_json::json!({ "contentType": "application/json" }),
+                )],
+                false,
+            )
+            .unwrap();
+
+        // adding a JSON schema keeps the accepted content-type
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+        updater
+            .add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata,
+                vec![greeter_with_input(serde_json::json!({
+                    "contentType": "application/json",
+                    "jsonSchema": { "type": "string" }
+                }))],
+                false,
+            )
+            .unwrap();
+
+        // widening to a wildcard accepts everything accepted before
+        let deployment_3 = Deployment::mock_with_uri("http://localhost:9082");
+        updater
+            .add_deployment(
+                Some(deployment_3.id),
+                deployment_3.metadata,
+                vec![greeter_with_input(
+                    serde_json::json!({ "contentType": "application/*" }),
+                )],
+                false,
+            )
+            .unwrap();
+
+        let schema = updater.into_inner();
+        schema.assert_service_revision(GREETER_SERVICE_NAME, 3);
+        schema.assert_service_deployment(GREETER_SERVICE_NAME, deployment_3.id);
+    }
+
+    #[test]
+    fn register_service_with_duplicate_handlers() {
+        let mut service = greeter_service();
+        service.handlers.push(service.handlers[0].clone());
+
+        let deployment = Deployment::mock();
+        let mut updater = SchemaUpdater::default();
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::DuplicateHandler(
+                service_name,
+                handler
+            ))) = updater.add_deployment(
+                Some(deployment.id),
+                deployment.metadata,
+                vec![service],
+                false,
+            )
+        );
+        assert_eq!(service_name.as_ref(), GREETER_SERVICE_NAME);
+        assert_eq!(handler, "greet");
+        assert!(!updater.modified);
+    }
+
+    #[test]
+    fn register_service_with_too_many_handlers() {
+        let mut service = greeter_service();
+        service.handlers.push(endpoint_manifest::Handler {
+            name: "greetAgain".parse().unwrap(),
+            ty: None,
+            input: None,
+            output: None,
+            idempotency_retention: None,
+            journal_retention: None,
+        });
+
+        let deployment = Deployment::mock();
+        let mut updater =
+            SchemaUpdater::default().with_max_handlers_per_service(NonZeroUsize::new(1).unwrap());
+        let_assert!(
+            Err(SchemaError::Service(ServiceError::TooManyHandlers {
+                handlers,
+                max,
+                ..
+            })) = updater.add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![service.clone()],
+                false,
+            )
+        );
+        assert_eq!(handlers, 2);
+        assert_eq!(max.get(), 1);
+
+        let mut updater =
+            SchemaUpdater::default().with_max_handlers_per_service(NonZeroUsize::new(2).unwrap());
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata,
+                vec![service],
+                false,
+            )
+            .unwrap();
+        let schema = updater.into_inner();
+        schema.assert_service_handler(GREETER_SERVICE_NAME, "greet");
+        schema.assert_service_handler(GREETER_SERVICE_NAME, "greetAgain");
+    }
+
+    #[test]
+    fn register_deployment_without_services() {
+        let schema = Schema::default();
+        let current_version = schema.version();
+
+        let mut updater = SchemaUpdater::from(schema);
+        let deployment = Deployment::mock();
+        assert!(let Err(SchemaError::Deployment(DeploymentError::NoServices)) = updater.add_deployment(
+            Some(deployment.id),
+            deployment.metadata,
+            vec![],
+            false,
+        ));
+        assert_eq!(updater.into_inner().version(), current_version);
+    }
+
+    #[test]
+    fn register_new_deployment_with_expected_version() {
+        let schema = Schema::default();
+        let current_version = schema.version();
+
+        let mut updater =
+            SchemaUpdater::from(schema.clone()).with_expected_version(Some(current_version.next()));
+        let deployment = Deployment::mock();
+        let_assert!(
+            Err(SchemaError::Conflict { expected, actual }) = updater.add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+        );
+        assert_eq!(expected, current_version.next());
+        assert_eq!(actual, current_version);
+        assert_eq!(updater.into_inner().version(), current_version);
+
+        let mut updater = SchemaUpdater::from(schema).with_expected_version(Some(current_version));
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata,
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        assert_eq!(updater.into_inner().version(), current_version.next());
+    }
+
+    #[test]
+    fn register_new_deployment_with_default_idempotency_retention() {
+        let idempotency_retention = Duration::from_secs(60 * 10);
+        let mut updater =
+            SchemaUpdater::default().with_default_idempotency_retention(idempotency_retention);
+
+        let deployment = Deployment::mock();
+        updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+
+        let schema = updater.into_inner();
+
+        assert_eq!(
+            schema
+                .assert_service(GREETER_SERVICE_NAME)
+                .idempotency_retention,
+            idempotency_retention.into()
+        );
+        assert_eq!(
+            schema
+                .resolve_latest_invocation_target(GREETER_SERVICE_NAME, "greet")
+                .unwrap()
+                .idempotency_retention,
+            idempotency_retention
+        );
+    }
+
+    #[test]
+    fn force_deploy_without_headers_keeps_existing_headers() {
+        let mut updater = SchemaUpdater::default();
+
+        let mut deployment = Deployment::mock();
+        deployment.metadata.delivery_options.additional_headers = HashMap::from([(
+            http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        )]);
+        let deployment_id = updater
+            .add_deployment(
+                Some(deployment.id),
+                deployment.metadata,
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+
+        let redeployment = Deployment::mock();
+        assert!(redeployment
+            .metadata
+            .delivery_options
+            .additional_headers
+            .is_empty());
+        updater
+            .add_deployment(
+                Some(deployment_id),
+                redeployment.metadata,
+                vec![greeter_service()],
+                true,
+            )
+            .unwrap();
+
+        let schema = updater.into_inner();
+        let additional_headers = schema
+            .get_deployment(&deployment_id)
+            .unwrap()
+            .metadata
+            .delivery_options
+            .additional_headers;
+        assert_eq!(
+            additional_headers.get(http::header::AUTHORIZATION).unwrap(),
+            "Bearer secret"
+        );
+    }
+
     #[test]
     fn register_new_deployment_add_unregistered_service() {
         let mut updater = SchemaUpdater::default();
@@ -913,6 +1868,48 @@ mod tests {
         assert!(schemas.get_deployment(&deployment_1.id).is_none());
     }
 
+    #[test]
+    fn remove_deployment_with_colliding_service_revision() {
+        let mut updater = SchemaUpdater::default();
+
+        let deployment_1 = Deployment::mock_with_uri("http://localhost:9080");
+        let deployment_2 = Deployment::mock_with_uri("http://localhost:9081");
+
+        updater
+            .add_deployment(
+                Some(deployment_1.id),
+                deployment_1.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        updater
+            .add_deployment(
+                Some(deployment_2.id),
+                deployment_2.metadata.clone(),
+                vec![greeter_service()],
+                false,
+            )
+            .unwrap();
+        let mut schemas = updater.into_inner();
+
+        // simulate a wrapped around revision which collides with the one of deployment_1
+        schemas
+            .services
+            .get_mut(GREETER_SERVICE_NAME)
+            .unwrap()
+            .revision = 1;
+        schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_2.id);
+
+        updater = schemas.into();
+        updater.remove_deployment(deployment_1.id);
+        let schemas = updater.into_inner();
+
+        schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_2.id);
+        schemas.assert_service_revision(GREETER_SERVICE_NAME, 1);
+        assert!(schemas.get_deployment(&deployment_1.id).is_none());
+    }
+
     mod remove_method {
         use super::*;
 
@@ -929,12 +1926,16 @@ mod tests {
                         ty: None,
                         input: None,
                         output: None,
+                        idempotency_retention: None,
+                        journal_retention: None,
                     },
                     endpoint_manifest::Handler {
                         name: "doSomething".parse().unwrap(),
                         ty: None,
                         input: None,
                         output: None,
+                        idempotency_retention: None,
+                        journal_retention: None,
                     },
                 ],
             }
@@ -949,6 +1950,8 @@ mod tests {
                     ty: None,
                     input: None,
                     output: None,
+                    idempotency_retention: None,
+                    journal_retention: None,
                 }],
             }
         }
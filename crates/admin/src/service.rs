@@ -15,19 +15,23 @@ use http::StatusCode;
 use restate_bifrost::Bifrost;
 use restate_types::arc_util::Updateable;
 use restate_types::config::AdminOptions;
-use tonic::transport::Channel;
 use tower::ServiceBuilder;
-use tracing::info;
 
 use restate_core::metadata_store::MetadataStoreClient;
-use restate_core::{cancellation_watcher, task_center, MetadataWriter};
+use restate_core::{cancellation_watcher, metadata, task_center, MetadataWriter};
+use restate_grpc_util::auth::{
+    auth_layer, AuthenticatedChannel, Authenticator, BearerTokenInterceptor,
+};
+use restate_grpc_util::run_hyper_server;
 use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
 use restate_schema_api::subscription::SubscriptionValidator;
 use restate_service_protocol::discovery::ServiceDiscovery;
 
 use crate::schema_registry::SchemaRegistry;
 use crate::Error;
-use crate::{rest_api, state, storage_query};
+use crate::{cors, rest_api, state, storage_query};
+
+pub use crate::rest_api::BuildVersion;
 
 #[derive(Debug, thiserror::Error)]
 #[error("could not create the service client: {0}")]
@@ -35,6 +39,9 @@ pub struct BuildError(#[from] restate_service_client::BuildError);
 
 pub struct AdminService<V> {
     schema_registry: SchemaRegistry<V>,
+    build_version: BuildVersion,
+    authenticator: Option<Arc<dyn Authenticator>>,
+    grpc_interceptor: BearerTokenInterceptor,
 }
 
 impl<V> AdminService<V>
@@ -54,55 +61,161 @@ where
                 service_discovery,
                 subscription_validator,
             ),
+            build_version: BuildVersion::default(),
+            authenticator: None,
+            grpc_interceptor: BearerTokenInterceptor::default(),
         }
     }
 
+    /// Sets the build version which is reported in the OpenAPI specification. Defaults to the
+    /// version of this crate.
+    pub fn with_build_version(mut self, build_version: BuildVersion) -> Self {
+        self.build_version = build_version;
+        self
+    }
+
+    /// Rejects requests which the given authenticator doesn't accept. Default is no
+    /// authentication.
+    pub fn with_authenticator(mut self, authenticator: Arc<dyn Authenticator>) -> Self {
+        self.authenticator = Some(authenticator);
+        self
+    }
+
+    /// Attaches the credentials of the given interceptor to the calls to the node services of the
+    /// other nodes, e.g. when propagating the schema. Default is no credentials.
+    pub fn with_grpc_interceptor(mut self, grpc_interceptor: BearerTokenInterceptor) -> Self {
+        self.grpc_interceptor = grpc_interceptor;
+        self
+    }
+
     pub async fn run(
         self,
         mut updateable_config: impl Updateable<AdminOptions> + Send + 'static,
-        node_svc_client: NodeSvcClient<Channel>,
+        node_svc_client: NodeSvcClient<AuthenticatedChannel>,
         bifrost: Bifrost,
     ) -> anyhow::Result<()> {
         let opts = updateable_config.load();
 
-        let rest_state =
-            state::AdminServiceState::new(self.schema_registry, bifrost, task_center());
+        let rest_state = state::AdminServiceState::new(
+            self.schema_registry,
+            bifrost,
+            task_center(),
+            node_svc_client.clone(),
+            self.grpc_interceptor,
+        );
 
-        let query_state = Arc::new(state::QueryServiceState { node_svc_client });
+        let query_state = Arc::new(state::QueryServiceState {
+            node_svc_client,
+            metadata: metadata(),
+            query_timeout: opts.query_engine.query_timeout.map(Into::into),
+            max_rows: opts.query_engine.max_rows.map(Into::into),
+            running_queries: Default::default(),
+        });
+        let base_path = opts.base_path();
         let router = axum::Router::new().merge(storage_query::create_router(query_state));
 
         let router = router
             // Merge meta API router
-            .merge(rest_api::create_router(rest_state))
-            .layer(
-                ServiceBuilder::new()
-                    .layer(HandleErrorLayer::new(|_| async {
-                        StatusCode::TOO_MANY_REQUESTS
-                    }))
-                    .layer(tower::load_shed::LoadShedLayer::new())
-                    .layer(tower::limit::GlobalConcurrencyLimitLayer::new(
-                        opts.concurrent_api_requests_limit(),
-                    )),
-            );
-
-        // Bind and serve
-        let server = hyper::Server::try_bind(&opts.bind_address)
-            .map_err(|err| Error::Binding {
-                address: opts.bind_address,
-                source: err,
-            })?
-            .serve(router.into_make_service());
-
-        info!(
-            net.host.addr = %server.local_addr().ip(),
-            net.host.port = %server.local_addr().port(),
-            "Admin API listening"
+            .merge(rest_api::create_router(
+                rest_state,
+                &self.build_version,
+                base_path.as_deref(),
+            ));
+        let router = with_base_path(router, base_path.as_deref()).layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_| async {
+                    StatusCode::TOO_MANY_REQUESTS
+                }))
+                .layer(tower::load_shed::LoadShedLayer::new())
+                .layer(tower::limit::GlobalConcurrencyLimitLayer::new(
+                    opts.concurrent_api_requests_limit(),
+                )),
         );
 
-        // Wait server graceful shutdown
-        Ok(server
-            .with_graceful_shutdown(cancellation_watcher())
+        let router = match self.authenticator {
+            Some(authenticator) => router.layer(auth_layer(authenticator)),
+            None => router,
+        };
+
+        // Applied last, so that preflight requests are answered without counting towards the
+        // concurrency limit
+        let router = match &opts.cors {
+            Some(cors_opts) => router.layer(cors::cors_layer(cors_opts)?),
+            None => router,
+        };
+
+        // Bind and serve, waiting for the graceful shutdown
+        run_hyper_server(
+            &opts.bind_address,
+            router,
+            cancellation_watcher(),
+            "admin-api",
+        )
+        .await
+        .map_err(|err| match err {
+            restate_grpc_util::Error::Running(_) => Error::Running(err),
+            _ => Error::Binding(err),
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Serves the routes under the given base path, e.g. `/restate/admin/query` instead of `/query`.
+fn with_base_path(router: axum::Router, base_path: Option<&str>) -> axum::Router {
+    match base_path {
+        Some(base_path) => axum::Router::new().nest(base_path, router),
+        None => router,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::routing::get;
+    use hyper::{Body, Request};
+    use restate_test_util::assert_eq;
+    use tower::ServiceExt;
+
+    async fn get_status(router: &axum::Router, uri: &str) -> StatusCode {
+        router
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
             .await
-            .map_err(Error::Running)?)
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn routes_respond_under_base_path() {
+        let router = with_base_path(
+            axum::Router::new().route("/query", get(|| async {})),
+            Some("/restate/admin"),
+        );
+
+        assert_eq!(
+            get_status(&router, "/restate/admin/query").await,
+            StatusCode::OK
+        );
+        assert_eq!(get_status(&router, "/query").await, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn base_path_is_normalized() {
+        let base_path = |base_path: &str| {
+            AdminOptions {
+                base_path: Some(base_path.to_owned()),
+                ..AdminOptions::default()
+            }
+            .base_path()
+        };
+
+        assert_eq!(
+            base_path("restate/admin/"),
+            Some("/restate/admin".to_owned())
+        );
+        assert_eq!(base_path("/"), None);
+        assert_eq!(AdminOptions::default().base_path(), None);
     }
 }
@@ -10,21 +10,33 @@
 //
 
 use crate::schema_registry::SchemaRegistry;
+use crate::storage_query::RunningQueries;
 use restate_bifrost::Bifrost;
-use restate_core::TaskCenter;
+use restate_core::{Metadata, TaskCenter};
+use restate_grpc_util::auth::{AuthenticatedChannel, BearerTokenInterceptor};
 use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
-use tonic::transport::Channel;
+use std::time::Duration;
 
 #[derive(Clone, derive_builder::Builder)]
 pub struct AdminServiceState<V> {
     pub schema_registry: SchemaRegistry<V>,
     pub bifrost: Bifrost,
     pub task_center: TaskCenter,
+    pub node_svc_client: NodeSvcClient<AuthenticatedChannel>,
+    /// Credentials for calling the node services of the other nodes
+    pub grpc_interceptor: BearerTokenInterceptor,
 }
 
 #[derive(Clone)]
 pub struct QueryServiceState {
-    pub node_svc_client: NodeSvcClient<Channel>,
+    pub node_svc_client: NodeSvcClient<AuthenticatedChannel>,
+    pub metadata: Metadata,
+    /// Upper bound for the duration of a query
+    pub query_timeout: Option<Duration>,
+    /// Upper bound for the number of rows returned by a query
+    pub max_rows: Option<usize>,
+    /// Queries which can be cancelled by their request id
+    pub running_queries: RunningQueries,
 }
 
 impl<V> AdminServiceState<V> {
@@ -32,11 +44,15 @@ impl<V> AdminServiceState<V> {
         schema_registry: SchemaRegistry<V>,
         bifrost: Bifrost,
         task_center: TaskCenter,
+        node_svc_client: NodeSvcClient<AuthenticatedChannel>,
+        grpc_interceptor: BearerTokenInterceptor,
     ) -> Self {
         Self {
             schema_registry,
             bifrost,
             task_center,
+            node_svc_client,
+            grpc_interceptor,
         }
     }
 }
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use arrow_flight::error::FlightError;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -23,8 +24,59 @@ use serde::Serialize;
 /// and later converted to a response through the IntoResponse implementation
 #[derive(Debug, thiserror::Error)]
 pub enum StorageQueryError {
+    #[error("query exceeded the memory limit: {0}")]
+    MemoryLimitExceeded(String),
+    /// Returned as status code if the deadline elapses before the response is started, afterwards
+    /// it terminates the response body.
+    #[error("query did not complete within {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("invalid cursor: {0}")]
+    InvalidCursor(String),
     #[error("failed grpc: {0}")]
-    Tonic(#[from] tonic::Status),
+    Tonic(tonic::Status),
+    #[error("failed reading the query result: {0}")]
+    Flight(FlightError),
+    #[error("the partition table is not known yet, try again later")]
+    PartitionTableUnavailable,
+    #[error("a query with request id '{0}' is already running")]
+    DuplicateRequestId(String),
+    #[error("no query with request id '{0}' is running")]
+    UnknownRequestId(String),
+    /// Returned as status code if a paginated query is cancelled while its page is read, otherwise
+    /// it terminates the response body.
+    #[error("query with request id '{0}' was cancelled")]
+    Cancelled(String),
+}
+
+/// Terminates the result stream of a query which returns more rows than allowed. This is only
+/// detected while streaming the rows, so it has no status code since the response has already
+/// been started. Paginated queries never exceed it, since the page size is capped instead.
+#[derive(Debug, thiserror::Error)]
+#[error("query returned more than {0} rows")]
+pub struct TooManyRowsError(pub usize);
+
+impl From<tonic::Status> for StorageQueryError {
+    fn from(status: tonic::Status) -> Self {
+        match status.code() {
+            tonic::Code::ResourceExhausted => {
+                StorageQueryError::MemoryLimitExceeded(status.message().to_owned())
+            }
+            _ => StorageQueryError::Tonic(status),
+        }
+    }
+}
+
+impl From<FlightError> for StorageQueryError {
+    fn from(err: FlightError) -> Self {
+        match err {
+            FlightError::ExternalError(err) => match err.downcast::<StorageQueryError>() {
+                Ok(err) => *err,
+                Err(err) => StorageQueryError::Flight(FlightError::ExternalError(err)),
+            },
+            FlightError::Tonic(status) => status.into(),
+            err => StorageQueryError::Flight(err),
+        }
+    }
 }
 
 /// # Error description response
@@ -37,7 +89,19 @@ struct ErrorDescriptionResponse {
 
 impl IntoResponse for StorageQueryError {
     fn into_response(self) -> Response {
-        let status_code = StatusCode::INTERNAL_SERVER_ERROR;
+        let status_code = match &self {
+            StorageQueryError::MemoryLimitExceeded(_)
+            | StorageQueryError::PartitionTableUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            StorageQueryError::Timeout(_) => StatusCode::REQUEST_TIMEOUT,
+            StorageQueryError::InvalidCursor(_) => StatusCode::BAD_REQUEST,
+            StorageQueryError::DuplicateRequestId(_) | StorageQueryError::Cancelled(_) => {
+                StatusCode::CONFLICT
+            }
+            StorageQueryError::UnknownRequestId(_) => StatusCode::NOT_FOUND,
+            StorageQueryError::Tonic(_) | StorageQueryError::Flight(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
 
         (
             status_code,
@@ -64,6 +128,9 @@ impl ToResponses for StorageQueryError {
                 "404".into() => okapi::openapi3::RefOr::Object(
                     okapi::openapi3::Response { content: error_media_type.clone(), ..Default::default() }
                 ),
+                "408".into() => okapi::openapi3::RefOr::Object(
+                    okapi::openapi3::Response { content: error_media_type.clone(), ..Default::default() }
+                ),
                 "409".into() => okapi::openapi3::RefOr::Object(
                     okapi::openapi3::Response { content: error_media_type.clone(), ..Default::default() }
                 ),
@@ -78,3 +145,38 @@ impl ToResponses for StorageQueryError {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_test_util::{assert, let_assert};
+
+    #[test]
+    fn exhausted_resources_are_reported_as_memory_limit_exceeded() {
+        let_assert!(
+            StorageQueryError::MemoryLimitExceeded(message) =
+                StorageQueryError::from(tonic::Status::resource_exhausted("sort exceeded 1 MiB"))
+        );
+        assert!(message == "sort exceeded 1 MiB");
+
+        // the error can also terminate the flight stream of a running query
+        let_assert!(
+            StorageQueryError::MemoryLimitExceeded(_) = StorageQueryError::from(
+                FlightError::Tonic(tonic::Status::resource_exhausted("sort exceeded 1 MiB"))
+            )
+        );
+
+        let_assert!(
+            StorageQueryError::Tonic(_) =
+                StorageQueryError::from(tonic::Status::internal("failed executing the query"))
+        );
+    }
+
+    #[test]
+    fn memory_limit_exceeded_is_unavailable() {
+        let response = StorageQueryError::MemoryLimitExceeded("sort exceeded 1 MiB".to_owned())
+            .into_response();
+        assert!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -9,16 +9,30 @@
 // by the Apache License, Version 2.0.
 
 mod error;
+mod partition_stats;
 mod query;
+mod running_queries;
+mod schema;
 
-use axum::{routing::post, Router};
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
 use std::sync::Arc;
 
 use crate::state::QueryServiceState;
 
+pub(crate) use running_queries::RunningQueries;
+
 pub fn create_router(state: Arc<QueryServiceState>) -> Router<()> {
     // Setup the router
     axum::Router::new()
         .route("/query", post(query::query))
+        .route("/query/:request_id", delete(query::cancel_query))
+        .route(
+            "/query/partition-stats",
+            get(partition_stats::partition_stats),
+        )
+        .route("/query/schema", get(schema::table_schemas))
         .with_state(state)
 }
@@ -0,0 +1,117 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::FlightData;
+use axum::extract::State;
+use axum::Json;
+use futures::TryStreamExt;
+use okapi_operation::*;
+use restate_node_services::node_svc::StorageQueryRequest;
+use restate_storage_query_datafusion::partition_stats::{
+    collect_partition_stats, partition_stats_query, PartitionRowCounts,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::state::QueryServiceState;
+
+use super::error::StorageQueryError;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PartitionStatsResponse {
+    pub partitions: Vec<PartitionStats>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PartitionStats {
+    pub partition_id: u64,
+    /// # Rows
+    ///
+    /// Number of rows per table. Tables without rows are omitted.
+    pub rows: BTreeMap<String, u64>,
+    /// # Total rows
+    ///
+    /// Number of rows across all tables.
+    pub total_rows: u64,
+}
+
+/// Partition stats
+#[openapi(
+    summary = "Partition stats",
+    description = "Number of rows of the tables stored in the partitions, per partition.",
+    operation_id = "partition_stats",
+    tags = "storage",
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn partition_stats(
+    State(state): State<Arc<QueryServiceState>>,
+) -> Result<Json<PartitionStatsResponse>, StorageQueryError> {
+    let partition_table = state
+        .metadata
+        .partition_table()
+        .ok_or(StorageQueryError::PartitionTableUnavailable)?;
+
+    // report all partitions, including the empty ones
+    let mut row_counts: PartitionRowCounts = partition_table
+        .partitioner()
+        .map(|(partition_id, _)| (partition_id, BTreeMap::new()))
+        .collect();
+
+    let collect_row_counts = async {
+        let response_stream = state
+            .node_svc_client
+            .clone()
+            .query_storage(StorageQueryRequest {
+                query: partition_stats_query(&partition_table),
+                params: Vec::new(),
+            })
+            .await?
+            .into_inner();
+
+        let mut record_batches = FlightRecordBatchStream::new_from_flight_data(
+            response_stream
+                .map_ok(|response| FlightData {
+                    data_header: response.header,
+                    data_body: response.data,
+                    ..FlightData::default()
+                })
+                .map_err(FlightError::from),
+        );
+        while let Some(record_batch) = record_batches.try_next().await? {
+            collect_partition_stats(&record_batch, &mut row_counts).map_err(FlightError::from)?;
+        }
+
+        Ok::<_, StorageQueryError>(())
+    };
+
+    if let Some(timeout) = state.query_timeout {
+        tokio::time::timeout(timeout, collect_row_counts)
+            .await
+            .map_err(|_| StorageQueryError::Timeout(timeout))??;
+    } else {
+        collect_row_counts.await?;
+    }
+
+    Ok(Json(PartitionStatsResponse {
+        partitions: row_counts
+            .into_iter()
+            .map(|(partition_id, rows)| PartitionStats {
+                partition_id: partition_id.into(),
+                total_rows: rows.values().sum(),
+                rows,
+            })
+            .collect(),
+    }))
+}
@@ -11,14 +11,19 @@
 use arrow_flight::decode::FlightRecordBatchStream;
 use arrow_flight::error::FlightError;
 use arrow_flight::FlightData;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use axum::body::StreamBody;
-use axum::extract::State;
-use axum::response::IntoResponse;
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
 use axum::{http, Json};
+use base64::Engine;
 use bytes::Bytes;
 use datafusion::arrow::array::{
     Array, ArrayRef, AsArray, BinaryArray, GenericByteArray, StringArray,
@@ -27,17 +32,21 @@ use datafusion::arrow::buffer::{OffsetBuffer, ScalarBuffer};
 use datafusion::arrow::datatypes::{ByteArrayType, DataType, Field, FieldRef, Schema, SchemaRef};
 use datafusion::arrow::error::ArrowError;
 use datafusion::arrow::ipc::writer::StreamWriter;
+use datafusion::arrow::json::LineDelimitedWriter;
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::{ready, Stream, StreamExt, TryStreamExt};
 use okapi_operation::*;
-use restate_node_services::node_svc::StorageQueryRequest;
+use restate_node_services::node_svc;
+use restate_node_services::node_svc::{query_parameter, StorageQueryRequest};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_with::serde_as;
+use tokio::time::{Instant, Sleep};
 
 use crate::state::QueryServiceState;
 
-use super::error::StorageQueryError;
+use super::error::{StorageQueryError, TooManyRowsError};
+use super::running_queries::RunningQuery;
 
 #[serde_as]
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -48,50 +57,527 @@ pub struct QueryRequest {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     #[schemars(with = "String")]
     pub query: String,
+
+    /// # Parameters
+    ///
+    /// Values of the positional placeholders `$1`, `$2`, ... of the query. Prefer them over
+    /// concatenating values into the query, since they don't need to be escaped.
+    #[serde(default)]
+    pub params: Vec<QueryParameter>,
+
+    /// # Explain
+    ///
+    /// If set, the logical and physical plan of the query are returned instead of its result.
+    /// Equivalent to prefixing the query with `EXPLAIN`.
+    #[serde(default)]
+    pub explain: bool,
+}
+
+/// # Query parameter
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum QueryParameter {
+    Bool(bool),
+    Int64(i64),
+    UInt64(u64),
+    Float64(f64),
+    String(String),
+}
+
+impl From<QueryParameter> for query_parameter::Value {
+    fn from(value: QueryParameter) -> Self {
+        match value {
+            QueryParameter::Bool(value) => query_parameter::Value::Bool(value),
+            QueryParameter::Int64(value) => query_parameter::Value::Int64(value),
+            QueryParameter::UInt64(value) => query_parameter::Value::Uint64(value),
+            QueryParameter::Float64(value) => query_parameter::Value::Float64(value),
+            QueryParameter::String(value) => query_parameter::Value::String(value),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct QueryParams {
+    /// # Timeout
+    ///
+    /// Maximum duration of the query, e.g. `10s`. Capped by the configured query timeout.
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[schemars(with = "Option<String>")]
+    pub timeout: Option<humantime::Duration>,
+
+    /// # Max rows
+    ///
+    /// Maximum number of rows to return. Capped by the configured max rows.
+    pub max_rows: Option<usize>,
+
+    /// # Limit
+    ///
+    /// Number of rows per page. Enables pagination of the result.
+    pub limit: Option<NonZeroUsize>,
+
+    /// # Cursor
+    ///
+    /// Opaque token returned in the `x-restate-next-cursor` header of the previous page.
+    pub cursor: Option<String>,
+
+    /// # Request id
+    ///
+    /// Client chosen id of the query, which can be used to cancel it while it is running.
+    pub request_id: Option<String>,
 }
 
 /// Query storage
 #[openapi(
     summary = "Query storage",
-    description = "Query the storage API",
+    description = "Query the storage API. The result is streamed as Arrow IPC ('application/vnd.apache.arrow.stream') by default, or as newline delimited JSON when requesting 'application/x-ndjson' via the Accept header. \
+    The result can be paginated by setting 'limit'. If there are more rows, the response contains an 'x-restate-next-cursor' header, \
+    whose value can be passed as 'cursor' to fetch the next page. Pages are only deterministic for queries with a total ORDER BY. \
+    If a query which is not paginated exceeds its timeout or max rows, or is cancelled, after the response has been started, the response body is terminated with an error instead.",
     operation_id = "query",
     tags = "storage",
+    parameters(
+        query(
+            name = "timeout",
+            description = "Maximum duration of the query, e.g. '10s'. Cannot exceed the configured query timeout.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "max_rows",
+            description = "Maximum number of rows to return. Cannot exceed the configured max rows.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "usize",
+        ),
+        query(
+            name = "limit",
+            description = "Number of rows per page. Cannot exceed the configured max rows.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "usize",
+        ),
+        query(
+            name = "cursor",
+            description = "Cursor returned by the previous page.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        ),
+        query(
+            name = "request_id",
+            description = "Id of the query, which can be passed to 'DELETE /query/{request_id}' to cancel it. Must be unique among the running queries.",
+            required = false,
+            style = "simple",
+            allow_empty_value = false,
+            schema = "std::string::String",
+        )
+    ),
     responses(ignore_return_type = true, from_type = "StorageQueryError")
 )]
 pub async fn query(
     State(state): State<Arc<QueryServiceState>>,
+    Query(params): Query<QueryParams>,
+    headers: http::HeaderMap,
     #[request_body(required = true)] Json(payload): Json<QueryRequest>,
-) -> Result<impl IntoResponse, StorageQueryError> {
+) -> Result<Response, StorageQueryError> {
     let mut worker_grpc_client = state.node_svc_client.clone();
 
-    let response_stream = worker_grpc_client
-        .query_storage(StorageQueryRequest {
-            query: payload.query,
-        })
-        .await?
-        .into_inner();
-
-    let record_batch_stream = FlightRecordBatchStream::new_from_flight_data(
-        response_stream
-            .map_ok(|response| FlightData {
-                data_header: response.header,
-                data_body: response.data,
-                ..FlightData::default()
+    let timeout = lower_limit(state.query_timeout, params.timeout.map(Into::into));
+    let max_rows = lower_limit(state.max_rows, params.max_rows);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let format = ResultFormat::from_headers(&headers);
+    let running_query = params
+        .request_id
+        .map(|request_id| state.running_queries.register(request_id))
+        .transpose()?;
+
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(PageCursor::decode)
+        .transpose()?;
+    let explain = payload.explain || is_explain(&payload.query);
+    let page = match (params.limit, cursor) {
+        // the plan cannot be paginated since EXPLAIN cannot be used as a subquery
+        _ if explain => None,
+        (None, None) => None,
+        (limit, cursor) => {
+            let offset = cursor.map(|cursor| cursor.offset).unwrap_or_default();
+            let page_size = limit
+                .map(NonZeroUsize::get)
+                .or(cursor.map(|cursor| cursor.page_size))
+                .expect("limit or cursor to be set");
+            Some(PageCursor {
+                offset,
+                page_size: lower_limit(max_rows, Some(page_size)).expect("page size to be set"),
             })
-            .map_err(FlightError::from),
+        }
+    };
+
+    let query = if let Some(page) = page {
+        page.paginate(&payload.query)
+    } else if explain && !is_explain(&payload.query) {
+        format!("EXPLAIN {}", payload.query)
+    } else {
+        payload.query
+    };
+
+    let params = payload
+        .params
+        .into_iter()
+        .map(|param| node_svc::QueryParameter {
+            value: Some(param.into()),
+        })
+        .collect();
+    // If the client disconnects, the response body and with it the gRPC response stream are
+    // dropped, which makes the node abort the execution of the query.
+    let query_storage = worker_grpc_client.query_storage(StorageQueryRequest { query, params });
+    let response_stream = if let Some(deadline) = deadline {
+        tokio::time::timeout_at(deadline, query_storage)
+            .await
+            .map_err(|_| StorageQueryError::Timeout(timeout.expect("timeout to be set")))??
+    } else {
+        query_storage.await?
+    }
+    .into_inner();
+
+    let record_batch_stream = QueryLimitsStream::new(
+        FlightRecordBatchStream::new_from_flight_data(
+            response_stream
+                .map_ok(|response| FlightData {
+                    data_header: response.header,
+                    data_body: response.data,
+                    ..FlightData::default()
+                })
+                .map_err(FlightError::from),
+        ),
+        timeout.zip(deadline),
+        // the page size is already enforced by the paginated query
+        if page.is_some() { None } else { max_rows },
+        running_query,
     );
 
+    if let Some(page) = page {
+        let page = Page::read(record_batch_stream, page).await?;
+        let next_cursor = page.next_cursor;
+        let mut response = result_response(format, page);
+        if let Some(next_cursor) = next_cursor {
+            response.headers_mut().insert(
+                NEXT_CURSOR_HEADER,
+                http::HeaderValue::from_str(&next_cursor.encode())
+                    .expect("base64 to be a valid header value"),
+            );
+        }
+        Ok(response)
+    } else {
+        Ok(result_response(format, record_batch_stream))
+    }
+}
+
+/// Cancel query
+#[openapi(
+    summary = "Cancel query",
+    description = "Cancel the running query which was started with the given request id.",
+    operation_id = "cancel_query",
+    tags = "storage",
+    parameters(path(
+        name = "request_id",
+        description = "Request id of the query to cancel.",
+        schema = "std::string::String"
+    )),
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn cancel_query(
+    State(state): State<Arc<QueryServiceState>>,
+    Path(request_id): Path<String>,
+) -> Result<http::StatusCode, StorageQueryError> {
+    if state.running_queries.cancel(&request_id) {
+        Ok(http::StatusCode::ACCEPTED)
+    } else {
+        Err(StorageQueryError::UnknownRequestId(request_id))
+    }
+}
+
+fn result_response<S>(format: ResultFormat, record_batch_stream: S) -> Response
+where
+    S: RecordBatchSource + Send + 'static,
+{
+    if format == ResultFormat::NdJson {
+        // every record batch is encoded as soon as it is received, so the response body
+        // is only polled as fast as the client consumes it
+        let result_stream = record_batch_stream.map(|record_batch| {
+            let record_batch = record_batch?;
+            let mut writer = LineDelimitedWriter::new(Vec::new());
+            writer.write(&record_batch)?;
+            writer.finish()?;
+            Ok::<_, FlightError>(Bytes::from(writer.into_inner()))
+        });
+
+        return (
+            [(http::header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+            StreamBody::new(result_stream),
+        )
+            .into_response();
+    }
+
     // create a stream without LargeUtf8 or LargeBinary columns as JS doesn't support these yet
     let result_stream = ConvertRecordBatchStream::new(record_batch_stream);
 
     let body = StreamBody::new(result_stream);
-    Ok((
-        [(
-            http::header::CONTENT_TYPE,
-            "application/vnd.apache.arrow.stream",
-        )],
-        body,
-    ))
+    ([(http::header::CONTENT_TYPE, ARROW_IPC_CONTENT_TYPE)], body).into_response()
+}
+
+/// Returns true if the query is an `EXPLAIN` statement.
+fn is_explain(query: &str) -> bool {
+    query
+        .split_whitespace()
+        .next()
+        .is_some_and(|keyword| keyword.eq_ignore_ascii_case("explain"))
+}
+
+/// Returns the requested limit if it is lower than the configured one.
+fn lower_limit<T: Ord>(configured: Option<T>, requested: Option<T>) -> Option<T> {
+    match (configured, requested) {
+        (Some(configured), Some(requested)) => Some(configured.min(requested)),
+        (configured, requested) => configured.or(requested),
+    }
+}
+
+/// Stream of record batches which can report the schema of the result, even if it has no rows.
+trait RecordBatchSource: Stream<Item = Result<RecordBatch, FlightError>> + Unpin {
+    fn schema(&self) -> Option<&SchemaRef>;
+}
+
+const NEXT_CURSOR_HEADER: http::HeaderName = http::HeaderName::from_static("x-restate-next-cursor");
+
+/// Position of a page within the query result. It is handed out to clients as an opaque token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageCursor {
+    offset: usize,
+    page_size: usize,
+}
+
+impl PageCursor {
+    fn encode(&self) -> String {
+        restate_base64_util::URL_SAFE.encode(format!("{}:{}", self.offset, self.page_size))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, StorageQueryError> {
+        let invalid_cursor = || StorageQueryError::InvalidCursor(cursor.to_owned());
+
+        let decoded = restate_base64_util::URL_SAFE
+            .decode(cursor)
+            .map_err(|_| invalid_cursor())?;
+        let (offset, page_size) = std::str::from_utf8(&decoded)
+            .ok()
+            .and_then(|decoded| decoded.split_once(':'))
+            .ok_or_else(invalid_cursor)?;
+
+        match (offset.parse::<usize>(), page_size.parse::<usize>()) {
+            (Ok(offset), Ok(page_size)) if page_size > 0 => Ok(PageCursor { offset, page_size }),
+            _ => Err(invalid_cursor()),
+        }
+    }
+
+    /// Restricts the query to this page. One row more than the page size is requested to find
+    /// out whether there is a next page. The query is closed on a new line, since it may end
+    /// with a line comment.
+    fn paginate(&self, query: &str) -> String {
+        format!(
+            "SELECT * FROM ({}\n) LIMIT {} OFFSET {}",
+            query.trim().trim_end_matches(';'),
+            self.page_size + 1,
+            self.offset
+        )
+    }
+
+    fn next(&self) -> Self {
+        PageCursor {
+            offset: self.offset + self.page_size,
+            page_size: self.page_size,
+        }
+    }
+}
+
+/// A single page of the query result. Since the cursor of the next page is returned as a
+/// response header, the page is read completely before the response is started.
+struct Page {
+    schema: Option<SchemaRef>,
+    record_batches: VecDeque<RecordBatch>,
+    next_cursor: Option<PageCursor>,
+}
+
+impl Page {
+    async fn read(
+        mut record_batch_stream: QueryLimitsStream,
+        cursor: PageCursor,
+    ) -> Result<Self, StorageQueryError> {
+        let mut record_batches = VecDeque::new();
+        let mut num_rows = 0;
+        let mut has_more = false;
+
+        while let Some(record_batch) = record_batch_stream.next().await {
+            let record_batch = record_batch?;
+            let remaining = cursor.page_size - num_rows;
+
+            if record_batch.num_rows() > remaining {
+                has_more = true;
+                if remaining > 0 {
+                    record_batches.push_back(record_batch.slice(0, remaining));
+                    num_rows += remaining;
+                }
+            } else {
+                num_rows += record_batch.num_rows();
+                record_batches.push_back(record_batch);
+            }
+        }
+
+        Ok(Page {
+            schema: record_batch_stream.schema().cloned(),
+            record_batches,
+            next_cursor: has_more.then(|| cursor.next()),
+        })
+    }
+}
+
+impl Stream for Page {
+    type Item = Result<RecordBatch, FlightError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.record_batches.pop_front().map(Ok))
+    }
+}
+
+impl RecordBatchSource for Page {
+    fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+}
+
+/// Aborts the query once it exceeds its deadline, returns more than the allowed number of
+/// rows or is cancelled. Unless the stream is read before the response is started, as for pages,
+/// the limit violation is reported as a stream error which terminates the response body.
+struct QueryLimitsStream {
+    done: bool,
+    record_batch_stream: FlightRecordBatchStream,
+    deadline: Option<(Duration, Pin<Box<Sleep>>)>,
+    max_rows: Option<usize>,
+    num_rows: usize,
+    running_query: Option<RunningQuery>,
+}
+
+impl QueryLimitsStream {
+    fn new(
+        record_batch_stream: FlightRecordBatchStream,
+        deadline: Option<(Duration, Instant)>,
+        max_rows: Option<usize>,
+        running_query: Option<RunningQuery>,
+    ) -> Self {
+        Self {
+            done: false,
+            record_batch_stream,
+            deadline: deadline
+                .map(|(timeout, deadline)| (timeout, Box::pin(tokio::time::sleep_until(deadline)))),
+            max_rows,
+            num_rows: 0,
+            running_query,
+        }
+    }
+
+    fn abort(
+        &mut self,
+        err: impl std::error::Error + Send + Sync + 'static,
+    ) -> Poll<Option<Result<RecordBatch, FlightError>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(FlightError::ExternalError(Box::new(err)))))
+    }
+}
+
+impl RecordBatchSource for QueryLimitsStream {
+    fn schema(&self) -> Option<&SchemaRef> {
+        self.record_batch_stream.schema()
+    }
+}
+
+impl Stream for QueryLimitsStream {
+    type Item = Result<RecordBatch, FlightError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        if let Some((timeout, sleep)) = &mut self.deadline {
+            if sleep.as_mut().poll(cx).is_ready() {
+                let timeout = *timeout;
+                return self.abort(StorageQueryError::Timeout(timeout));
+            }
+        }
+
+        if let Some(running_query) = &mut self.running_query {
+            if running_query.poll_cancelled(cx).is_ready() {
+                let request_id = running_query.request_id().to_owned();
+                return self.abort(StorageQueryError::Cancelled(request_id));
+            }
+        }
+
+        let record_batch = ready!(self.record_batch_stream.poll_next_unpin(cx));
+        match record_batch {
+            Some(Ok(record_batch)) => {
+                self.num_rows += record_batch.num_rows();
+                match self.max_rows {
+                    Some(max_rows) if self.num_rows > max_rows => {
+                        self.abort(TooManyRowsError(max_rows))
+                    }
+                    _ => Poll::Ready(Some(Ok(record_batch))),
+                }
+            }
+            Some(Err(err)) => {
+                self.done = true;
+                Poll::Ready(Some(Err(err)))
+            }
+            None => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+const ARROW_IPC_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Format of the query result, negotiated via the `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultFormat {
+    /// Arrow IPC stream, which preserves the column types exactly. This is the default.
+    ArrowIpc,
+    /// Newline delimited JSON, one row per line.
+    NdJson,
+}
+
+impl ResultFormat {
+    fn from_headers(headers: &http::HeaderMap) -> Self {
+        // pick the first supported media type listed by the client
+        headers
+            .get_all(http::header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_type| media_type.split(';').next())
+            .find_map(|media_type| match media_type.trim() {
+                ARROW_IPC_CONTENT_TYPE => Some(ResultFormat::ArrowIpc),
+                NDJSON_CONTENT_TYPE => Some(ResultFormat::NdJson),
+                _ => None,
+            })
+            .unwrap_or(ResultFormat::ArrowIpc)
+    }
 }
 
 fn convert_schema(schema: SchemaRef) -> SchemaRef {
@@ -162,15 +648,15 @@ enum ConversionState {
 
 /// Convert the record batches so that they don't contain LargeUtf8 or LargeBinary columns as JS doesn't
 /// support these yet.
-struct ConvertRecordBatchStream {
+struct ConvertRecordBatchStream<S> {
     done: bool,
     state: ConversionState,
 
-    record_batch_stream: FlightRecordBatchStream,
+    record_batch_stream: S,
 }
 
-impl ConvertRecordBatchStream {
-    fn new(record_batch_stream: FlightRecordBatchStream) -> Self {
+impl<S> ConvertRecordBatchStream<S> {
+    fn new(record_batch_stream: S) -> Self {
         ConvertRecordBatchStream {
             done: false,
             state: ConversionState::WaitForSchema,
@@ -179,7 +665,7 @@ impl ConvertRecordBatchStream {
     }
 }
 
-impl ConvertRecordBatchStream {
+impl<S: Unpin> ConvertRecordBatchStream<S> {
     fn create_stream_writer(
         record_batch: &RecordBatch,
     ) -> Result<(SchemaRef, StreamWriter<Vec<u8>>), ArrowError> {
@@ -223,7 +709,7 @@ impl ConvertRecordBatchStream {
     }
 }
 
-impl Stream for ConvertRecordBatchStream {
+impl<S: RecordBatchSource> Stream for ConvertRecordBatchStream<S> {
     type Item = Result<Bytes, FlightError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
@@ -268,3 +754,202 @@ impl Stream for ConvertRecordBatchStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_flight::encode::FlightDataEncoderBuilder;
+    use datafusion::arrow::array::Int32Array;
+    use datafusion::arrow::datatypes::Int32Type;
+    use futures::SinkExt;
+    use restate_test_util::{assert, assert_eq, let_assert};
+
+    use crate::storage_query::RunningQueries;
+
+    fn flight_record_batch_stream(batches: Vec<RecordBatch>) -> FlightRecordBatchStream {
+        FlightRecordBatchStream::new_from_flight_data(
+            FlightDataEncoderBuilder::new()
+                .build(futures::stream::iter(batches.into_iter().map(Ok))),
+        )
+    }
+
+    fn record_batch(num_rows: i32) -> RecordBatch {
+        RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int32Array::from_iter_values(0..num_rows)) as ArrayRef,
+        )])
+        .unwrap()
+    }
+
+    #[test]
+    fn explain_statements_are_detected() {
+        assert!(is_explain("EXPLAIN SELECT * FROM state"));
+        assert!(is_explain("  explain analyze SELECT * FROM state"));
+        assert!(!is_explain("SELECT 'EXPLAIN' FROM state"));
+        assert!(!is_explain("EXPLAINED"));
+        assert!(!is_explain(""));
+    }
+
+    #[test]
+    fn requested_limit_cannot_exceed_configured_limit() {
+        assert_eq!(lower_limit(Some(10), Some(100)), Some(10));
+        assert_eq!(lower_limit(Some(10), Some(5)), Some(5));
+        assert_eq!(lower_limit(Some(10), None), Some(10));
+        assert_eq!(lower_limit(None, Some(5)), Some(5));
+        assert_eq!(lower_limit::<usize>(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn oversized_query_is_aborted() {
+        let mut stream = QueryLimitsStream::new(
+            flight_record_batch_stream(vec![record_batch(5), record_batch(5)]),
+            None,
+            Some(7),
+            None,
+        );
+
+        assert!(let Some(Ok(_)) = stream.next().await);
+        let_assert!(Some(Err(FlightError::ExternalError(err))) = stream.next().await);
+        let_assert!(Some(TooManyRowsError(7)) = err.downcast_ref::<TooManyRowsError>());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn long_running_query_is_aborted() {
+        let timeout = Duration::from_millis(10);
+        let mut stream = QueryLimitsStream::new(
+            FlightRecordBatchStream::new_from_flight_data(futures::stream::pending()),
+            Some((timeout, Instant::now() + timeout)),
+            None,
+            None,
+        );
+
+        let_assert!(Some(Err(FlightError::ExternalError(err))) = stream.next().await);
+        let_assert!(Some(StorageQueryError::Timeout(_)) = err.downcast_ref::<StorageQueryError>());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelled_query_is_aborted() {
+        let running_queries = RunningQueries::default();
+        let mut stream = QueryLimitsStream::new(
+            FlightRecordBatchStream::new_from_flight_data(futures::stream::pending()),
+            None,
+            None,
+            Some(running_queries.register("my-query".to_owned()).unwrap()),
+        );
+
+        let_assert!(
+            Err(StorageQueryError::DuplicateRequestId(_)) =
+                running_queries.register("my-query".to_owned())
+        );
+        assert!(running_queries.cancel("my-query"));
+        assert!(!running_queries.cancel("my-query"));
+
+        let_assert!(Some(Err(FlightError::ExternalError(err))) = stream.next().await);
+        let_assert!(
+            Some(StorageQueryError::Cancelled(request_id)) =
+                err.downcast_ref::<StorageQueryError>()
+        );
+        assert_eq!(request_id, "my-query");
+        assert!(stream.next().await.is_none());
+
+        // the request id can be reused once the query was cancelled
+        assert!(running_queries.register("my-query".to_owned()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_response_stops_the_query() {
+        let running_queries = RunningQueries::default();
+        let (mut batches_tx, batches_rx) = futures::channel::mpsc::channel(1);
+        let mut body = ConvertRecordBatchStream::new(QueryLimitsStream::new(
+            FlightRecordBatchStream::new_from_flight_data(
+                FlightDataEncoderBuilder::new().build(batches_rx),
+            ),
+            None,
+            None,
+            Some(running_queries.register("my-query".to_owned()).unwrap()),
+        ));
+
+        batches_tx.send(Ok(record_batch(5))).await.unwrap();
+        assert!(let Some(Ok(_)) = body.next().await);
+
+        // emulates the client disconnecting while the query is still producing results
+        drop(body);
+        assert!(batches_tx.is_closed());
+        assert!(!running_queries.cancel("my-query"));
+    }
+
+    #[tokio::test]
+    async fn paginate_through_result() {
+        let result = record_batch(10);
+
+        let mut cursor = Some(PageCursor {
+            offset: 0,
+            page_size: 4,
+        });
+        let mut pages = vec![];
+        while let Some(current) = cursor {
+            // emulate the LIMIT and OFFSET of the paginated query, split into batches of 3 rows
+            let offset = current.offset.min(result.num_rows());
+            let length = (current.page_size + 1).min(result.num_rows() - offset);
+            let batches = (0..length)
+                .step_by(3)
+                .map(|start| result.slice(offset + start, 3.min(length - start)))
+                .collect();
+
+            let page = Page::read(
+                QueryLimitsStream::new(flight_record_batch_stream(batches), None, None, None),
+                current,
+            )
+            .await
+            .unwrap();
+            pages.push(
+                page.record_batches
+                    .iter()
+                    .flat_map(|batch| {
+                        batch
+                            .column(0)
+                            .as_primitive::<Int32Type>()
+                            .values()
+                            .to_vec()
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            // the cursor is handed out to the client as an opaque token
+            cursor = page
+                .next_cursor
+                .map(|next_cursor| PageCursor::decode(&next_cursor.encode()).unwrap());
+        }
+
+        assert_eq!(pages, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+    }
+
+    #[test]
+    fn paginated_query_keeps_trailing_comment_inside() {
+        let cursor = PageCursor {
+            offset: 8,
+            page_size: 4,
+        };
+
+        assert_eq!(
+            cursor.paginate("SELECT * FROM sys_service;"),
+            "SELECT * FROM (SELECT * FROM sys_service\n) LIMIT 5 OFFSET 8"
+        );
+        assert_eq!(
+            cursor.paginate("SELECT * FROM sys_service -- all services\n"),
+            "SELECT * FROM (SELECT * FROM sys_service -- all services\n) LIMIT 5 OFFSET 8"
+        );
+    }
+
+    #[test]
+    fn invalid_cursor_is_rejected() {
+        let_assert!(Err(StorageQueryError::InvalidCursor(_)) = PageCursor::decode("not a cursor"));
+        let_assert!(
+            Err(StorageQueryError::InvalidCursor(_)) =
+                PageCursor::decode(&restate_base64_util::URL_SAFE.encode("10:0"))
+        );
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures::FutureExt;
+use tokio::sync::oneshot;
+
+use super::error::StorageQueryError;
+
+/// Queries which were started with a request id, so that they can be cancelled by it.
+#[derive(Debug, Default, Clone)]
+pub struct RunningQueries(Arc<Mutex<Inner>>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    next_registration: u64,
+    queries: HashMap<String, (u64, oneshot::Sender<()>)>,
+}
+
+impl RunningQueries {
+    /// Registers a query under the given request id until the returned [`RunningQuery`] is
+    /// dropped.
+    pub fn register(&self, request_id: String) -> Result<RunningQuery, StorageQueryError> {
+        let mut inner = self.0.lock().unwrap();
+        if inner.queries.contains_key(&request_id) {
+            return Err(StorageQueryError::DuplicateRequestId(request_id));
+        }
+
+        let registration = inner.next_registration;
+        inner.next_registration += 1;
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        inner
+            .queries
+            .insert(request_id.clone(), (registration, cancel_tx));
+
+        Ok(RunningQuery {
+            request_id,
+            registration,
+            cancelled: Some(cancel_rx),
+            running_queries: self.clone(),
+        })
+    }
+
+    /// Cancels the query with the given request id. Returns false if there is no such query.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        let registered = self.0.lock().unwrap().queries.remove(request_id);
+        registered.is_some_and(|(_, cancel_tx)| {
+            // the query might complete concurrently
+            let _ = cancel_tx.send(());
+            true
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RunningQuery {
+    request_id: String,
+    registration: u64,
+    cancelled: Option<oneshot::Receiver<()>>,
+    running_queries: RunningQueries,
+}
+
+impl RunningQuery {
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// Resolves once the query has been cancelled.
+    pub fn poll_cancelled(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(cancelled) = &mut self.cancelled else {
+            return Poll::Pending;
+        };
+
+        match cancelled.poll_unpin(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            Poll::Ready(Err(_)) => {
+                // unregistered without being cancelled
+                self.cancelled = None;
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        let mut inner = self.running_queries.0.lock().unwrap();
+        // only remove our own registration, the request id might have been reused after a cancel
+        if inner
+            .queries
+            .get(&self.request_id)
+            .is_some_and(|(registration, _)| *registration == self.registration)
+        {
+            inner.queries.remove(&self.request_id);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::FlightData;
+use axum::extract::State;
+use axum::Json;
+use futures::TryStreamExt;
+use okapi_operation::*;
+use restate_node_services::node_svc::StorageQueryRequest;
+use restate_storage_query_datafusion::table_schema::{
+    collect_table_schemas, TableSchemas, TABLE_SCHEMA_QUERY,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::state::QueryServiceState;
+
+use super::error::StorageQueryError;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TableSchemasResponse {
+    pub tables: Vec<TableSchema>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TableSchema {
+    pub name: String,
+    /// # Columns
+    ///
+    /// Columns of the table, in the order of their definition.
+    pub columns: Vec<ColumnSchema>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// # Data type
+    ///
+    /// Arrow data type of the column, e.g. `LargeUtf8`.
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// Table schemas
+#[openapi(
+    summary = "Table schemas",
+    description = "List the tables which can be queried, together with their columns.",
+    operation_id = "table_schemas",
+    tags = "storage",
+    responses(ignore_return_type = true, from_type = "StorageQueryError")
+)]
+pub async fn table_schemas(
+    State(state): State<Arc<QueryServiceState>>,
+) -> Result<Json<TableSchemasResponse>, StorageQueryError> {
+    let mut table_schemas = TableSchemas::new();
+
+    let collect_table_schemas = async {
+        let response_stream = state
+            .node_svc_client
+            .clone()
+            .query_storage(StorageQueryRequest {
+                query: TABLE_SCHEMA_QUERY.to_owned(),
+                params: Vec::new(),
+            })
+            .await?
+            .into_inner();
+
+        let mut record_batches = FlightRecordBatchStream::new_from_flight_data(
+            response_stream
+                .map_ok(|response| FlightData {
+                    data_header: response.header,
+                    data_body: response.data,
+                    ..FlightData::default()
+                })
+                .map_err(FlightError::from),
+        );
+        while let Some(record_batch) = record_batches.try_next().await? {
+            collect_table_schemas(&record_batch, &mut table_schemas).map_err(FlightError::from)?;
+        }
+
+        Ok::<_, StorageQueryError>(())
+    };
+
+    if let Some(timeout) = state.query_timeout {
+        tokio::time::timeout(timeout, collect_table_schemas)
+            .await
+            .map_err(|_| StorageQueryError::Timeout(timeout))??;
+    } else {
+        collect_table_schemas.await?;
+    }
+
+    Ok(Json(TableSchemasResponse {
+        tables: table_schemas
+            .into_iter()
+            .map(|(name, columns)| TableSchema {
+                name,
+                columns: columns
+                    .into_iter()
+                    .map(|column| ColumnSchema {
+                        name: column.name,
+                        data_type: column.data_type,
+                        nullable: column.nullable,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }))
+}
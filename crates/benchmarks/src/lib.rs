@@ -103,10 +103,13 @@ pub fn spawn_restate(config: Configuration) -> TaskCenter {
         RocksDbManager::init(Constant::new(config.common))
     });
     tc.spawn(TaskKind::TestRunner, "benchmark", None, async move {
-        let node = Node::create(updateable_config)
+        let node = Node::create(updateable_config, None)
             .await
             .expect("Restate node must build");
-        cloned_tc.run_in_scope("startup", None, node.start()).await
+        cloned_tc
+            .run_in_scope("startup", None, node.start())
+            .await?;
+        Ok(())
     })
     .unwrap();
 
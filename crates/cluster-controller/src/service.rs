@@ -384,7 +384,7 @@ mod tests {
             .await;
 
         node_env.tc.spawn(
-            TaskKind::SystemService,
+            TaskKind::ClusterController,
             "cluster-controller",
             None,
             svc.run(bifrost.clone()),
@@ -487,7 +487,7 @@ mod tests {
             .await;
 
         node_env.tc.spawn(
-            TaskKind::SystemService,
+            TaskKind::ClusterController,
             "cluster-controller",
             None,
             svc.run(bifrost.clone()),
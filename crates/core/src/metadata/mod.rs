@@ -28,7 +28,7 @@ use restate_types::partition_table::FixedPartitionTable;
 use restate_types::{GenerationalNodeId, Version, Versioned};
 
 use crate::metadata::manager::Command;
-use crate::metadata_store::ReadError;
+use crate::metadata_store::{MetadataStoreClientError, ReadError};
 use crate::network::NetworkSender;
 use crate::{ShutdownError, TaskCenter, TaskId, TaskKind};
 
@@ -40,6 +40,15 @@ pub enum SyncError {
     Shutdown(#[from] ShutdownError),
 }
 
+impl MetadataStoreClientError for SyncError {
+    fn is_network_error(&self) -> bool {
+        match self {
+            SyncError::MetadataStore(err) => err.is_network_error(),
+            SyncError::Shutdown(_) => false,
+        }
+    }
+}
+
 /// The kind of versioned metadata that can be synchronized across nodes.
 
 #[derive(Clone)]
@@ -340,6 +340,16 @@ impl<E> MetadataStoreClientError for ReadModifyWriteError<E> {
     }
 }
 
+impl MetadataStoreClientError for ReadError {
+    fn is_network_error(&self) -> bool {
+        match self {
+            ReadError::Network(_) => true,
+            ReadError::Internal(_) => false,
+            ReadError::Codec(_) => false,
+        }
+    }
+}
+
 impl MetadataStoreClientError for ReadWriteError {
     fn is_network_error(&self) -> bool {
         match self {
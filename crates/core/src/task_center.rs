@@ -208,6 +208,11 @@ impl TaskCenter {
         self.inner.global_cancel_token.clone()
     }
 
+    /// Returns true once a shutdown has been requested, even if it is still in progress.
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.inner.shutdown_requested.load(Ordering::Relaxed)
+    }
+
     /// The exit code that the process should exit with.
     pub fn exit_code(&self) -> i32 {
         self.inner.current_exit_code.load(Ordering::Relaxed)
@@ -623,10 +628,22 @@ impl TaskCenter {
 
         let should_shutdown_on_error = kind.should_shutdown_on_error();
         let mut request_node_shutdown = false;
+        let mut shutdown_reason = "failed";
         {
             match result {
                 Ok(Ok(())) => {
-                    trace!(kind = ?kind, name = ?task.name, "Task {} exited normally", task_id);
+                    if kind.should_shutdown_on_exit()
+                        && !task.cancel.is_cancelled()
+                        && !inner.shutdown_requested.load(Ordering::Relaxed)
+                    {
+                        error!(kind = ?kind, name = ?task.name,
+                            "Shutting down: task {} terminated unexpectedly", task_id
+                        );
+                        request_node_shutdown = true;
+                        shutdown_reason = "terminated unexpectedly";
+                    } else {
+                        trace!(kind = ?kind, name = ?task.name, "Task {} exited normally", task_id);
+                    }
                     counter!(TC_FINISHED, "kind" => kind_str, "status" => TC_STATUS_COMPLETED)
                         .increment(1);
                 }
@@ -670,7 +687,10 @@ impl TaskCenter {
             // Note that the task itself has been already removed from the task map, so shutdown
             // will not wait for its completion.
             self.shutdown_node(
-                &format!("task {} failed and requested a shutdown", task.name),
+                &format!(
+                    "task {} {} and requested a shutdown",
+                    task.name, shutdown_reason
+                ),
                 EXIT_CODE_FAILURE,
             )
             .await;
@@ -865,4 +885,67 @@ mod tests {
         assert!(start.elapsed() >= Duration::from_secs(10));
         Ok(())
     }
+
+    #[tokio::test(start_paused = true)]
+    #[traced_test]
+    async fn unexpected_termination_of_control_loop_shuts_down_node() -> Result<()> {
+        let tc = TaskCenterBuilder::default()
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?;
+        tc.spawn(TaskKind::RoleRunner, "worker-role", None, async {
+            cancellation_watcher().await;
+            Ok(())
+        })
+        .unwrap();
+        tc.spawn(
+            TaskKind::PartitionProcessorManager,
+            "early-exit",
+            None,
+            async { Ok(()) },
+        )
+        .unwrap();
+
+        tc.watch_shutdown().await;
+        assert_eq!(tc.exit_code(), EXIT_CODE_FAILURE);
+        assert!(logs_contain("terminated unexpectedly"));
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn system_service_may_finish_on_its_own() -> Result<()> {
+        let tc = TaskCenterBuilder::default()
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?;
+        tc.spawn(TaskKind::SystemService, "finite-service", None, async {
+            Ok(())
+        })
+        .unwrap();
+
+        // give the task time to finish
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!tc.shutdown_token().is_cancelled());
+        assert_eq!(tc.exit_code(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_system_service_does_not_shut_down_node() -> Result<()> {
+        let tc = TaskCenterBuilder::default()
+            .default_runtime_handle(tokio::runtime::Handle::current())
+            .ingress_runtime_handle(tokio::runtime::Handle::current())
+            .build()?;
+        let task_id = tc
+            .spawn(TaskKind::ClusterController, "service", None, async {
+                cancellation_watcher().await;
+                Ok(())
+            })
+            .unwrap();
+
+        tc.cancel_task(task_id).unwrap().await?;
+        assert!(!tc.shutdown_token().is_cancelled());
+        assert_eq!(tc.exit_code(), 0);
+        Ok(())
+    }
 }
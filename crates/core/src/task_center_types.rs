@@ -39,6 +39,11 @@ pub struct TaskId(u64);
 ///   * `OnError`  - What to do if the task returned Err(_)
 ///     - `log`                   - Log an error
 ///     - `shutdown` (default)    - Shutdown the node (task center global shutdown)
+///
+///   * `OnExit`  - What to do if the task returned Ok(()) before it was cancelled
+///     - `ignore` (default)      - Nothing, the task is allowed to finish on its own
+///     - `shutdown`              - Shutdown the node with a failure exit code, because the task
+///                                 is expected to run until the node shuts down
 #[derive(
     Clone,
     Copy,
@@ -68,6 +73,12 @@ pub enum TaskKind {
     IngressServer,
     RoleRunner,
     SystemService,
+    /// The control loop of the cluster controller, which runs until the node shuts down.
+    #[strum(props(OnExit = "shutdown"))]
+    ClusterController,
+    /// The control loop of the partition processor manager, which runs until the node shuts down.
+    #[strum(props(OnExit = "shutdown"))]
+    PartitionProcessorManager,
     Ingress,
     PartitionProcessor,
     #[strum(props(OnError = "log"))]
@@ -90,6 +101,10 @@ impl TaskKind {
         self.on_error() == "shutdown"
     }
 
+    pub fn should_shutdown_on_exit(&self) -> bool {
+        self.on_exit() == "shutdown"
+    }
+
     pub fn should_wait_on_cancel(&self) -> bool {
         self.on_cancel() == "wait"
     }
@@ -106,6 +121,10 @@ impl TaskKind {
         self.get_str("OnError").unwrap_or("shutdown")
     }
 
+    fn on_exit(&self) -> &'static str {
+        self.get_str("OnExit").unwrap_or("ignore")
+    }
+
     pub fn runtime(&self) -> AsyncRuntime {
         match self.get_str("runtime").unwrap_or("default") {
             "default" => AsyncRuntime::Default,
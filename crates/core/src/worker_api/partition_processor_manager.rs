@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use restate_types::identifiers::PartitionId;
+use std::collections::BTreeMap;
+
+use restate_types::identifiers::{LeaderEpoch, PartitionId};
 use tokio::sync::{mpsc, oneshot};
 
 use crate::ShutdownError;
@@ -16,6 +18,26 @@ use crate::ShutdownError;
 #[derive(Debug)]
 pub enum ProcessorsManagerCommand {
     GetLivePartitions(oneshot::Sender<Vec<PartitionId>>),
+    /// Stops the partition processor of the given partition while keeping the other partition
+    /// processors running.
+    StopPartition(PartitionId),
+    /// Stops the partition processor of the given partition from starting new invocations while
+    /// it keeps processing timers and completions. Only has an effect on leaders.
+    PauseInvocations(PartitionId),
+    ResumeInvocations(PartitionId),
+    /// Makes the partition processor of the given partition relinquish its leadership and become
+    /// a follower, so that another node can take over. Has no effect on followers.
+    StepDown(PartitionId),
+    GetLeadershipSnapshot(oneshot::Sender<BTreeMap<PartitionId, PartitionLeadership>>),
+}
+
+/// Leadership of a partition processor running on this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionLeadership {
+    pub is_leader: bool,
+    /// Epoch of the last leader the partition processor has observed, which is its own epoch if
+    /// it is the leader.
+    pub leader_epoch: Option<LeaderEpoch>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,4 +56,44 @@ impl ProcessorsManagerHandle {
             .unwrap();
         rx.await.map_err(|_| ShutdownError)
     }
+
+    /// Returns the leadership of all partition processors running on this node.
+    pub async fn leadership_snapshot(
+        &self,
+    ) -> Result<BTreeMap<PartitionId, PartitionLeadership>, ShutdownError> {
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .send(ProcessorsManagerCommand::GetLeadershipSnapshot(tx))
+            .await
+            .map_err(|_| ShutdownError)?;
+        rx.await.map_err(|_| ShutdownError)
+    }
+
+    pub async fn stop_partition(&self, partition_id: PartitionId) -> Result<(), ShutdownError> {
+        self.0
+            .send(ProcessorsManagerCommand::StopPartition(partition_id))
+            .await
+            .map_err(|_| ShutdownError)
+    }
+
+    pub async fn pause_invocations(&self, partition_id: PartitionId) -> Result<(), ShutdownError> {
+        self.0
+            .send(ProcessorsManagerCommand::PauseInvocations(partition_id))
+            .await
+            .map_err(|_| ShutdownError)
+    }
+
+    pub async fn resume_invocations(&self, partition_id: PartitionId) -> Result<(), ShutdownError> {
+        self.0
+            .send(ProcessorsManagerCommand::ResumeInvocations(partition_id))
+            .await
+            .map_err(|_| ShutdownError)
+    }
+
+    pub async fn step_down(&self, partition_id: PartitionId) -> Result<(), ShutdownError> {
+        self.0
+            .send(ProcessorsManagerCommand::StepDown(partition_id))
+            .await
+            .map_err(|_| ShutdownError)
+    }
 }
@@ -0,0 +1,484 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Authentication of the requests to the HTTP endpoints and the gRPC services of a node, such as
+//! the Admin APIs.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::{fmt, fs, io};
+
+use http::{header, Extensions, HeaderMap, HeaderValue, Request, Response, StatusCode};
+use restate_types::config::AuthOptions;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tower_http::validate_request::{ValidateRequest, ValidateRequestHeaderLayer};
+
+/// Verifies that a request is authenticated. Implement it to plug in a custom verification.
+pub trait Authenticator: Send + Sync + 'static {
+    /// Returns true if the request with the given headers and extensions may reach the handlers.
+    /// For connections over TLS, the extensions carry the
+    /// [`PeerCertificates`](crate::tls::PeerCertificates) of clients which authenticated with a
+    /// certificate.
+    fn authenticate(&self, headers: &HeaderMap, extensions: &Extensions) -> bool;
+}
+
+/// Accepts requests with an `Authorization: Bearer <token>` header carrying the configured token.
+pub struct BearerTokenAuthenticator {
+    token: String,
+}
+
+impl BearerTokenAuthenticator {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+
+    /// Reads the token from the configured bearer token file.
+    pub fn from_options(opts: &AuthOptions) -> io::Result<Self> {
+        read_bearer_token(opts).map(Self::new)
+    }
+}
+
+fn read_bearer_token(opts: &AuthOptions) -> io::Result<String> {
+    let to_error = |kind: io::ErrorKind, reason: &dyn fmt::Display| {
+        io::Error::new(
+            kind,
+            format!(
+                "failed reading the bearer token file '{}': {reason}",
+                opts.bearer_token_file.display()
+            ),
+        )
+    };
+
+    let token =
+        fs::read_to_string(&opts.bearer_token_file).map_err(|err| to_error(err.kind(), &err))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(to_error(io::ErrorKind::InvalidData, &"file is empty"));
+    }
+
+    Ok(token.to_owned())
+}
+
+impl fmt::Debug for BearerTokenAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerTokenAuthenticator")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Authenticator for BearerTokenAuthenticator {
+    fn authenticate(&self, headers: &HeaderMap, _extensions: &Extensions) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.token.as_bytes()))
+    }
+}
+
+/// The authenticator configured via the options, if any.
+pub fn configured_authenticator(
+    opts: Option<&AuthOptions>,
+) -> io::Result<Option<Arc<dyn Authenticator>>> {
+    opts.map(|opts| {
+        BearerTokenAuthenticator::from_options(opts)
+            .map(|authenticator| Arc::new(authenticator) as Arc<dyn Authenticator>)
+    })
+    .transpose()
+}
+
+/// Compares without short-circuiting, so that the time taken doesn't reveal the matching prefix.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Layer rejecting requests which aren't authenticated with `401 Unauthorized`, before they reach
+/// the handlers.
+pub fn auth_layer<ResBody>(
+    authenticator: Arc<dyn Authenticator>,
+) -> ValidateRequestHeaderLayer<Authenticate<ResBody>> {
+    ValidateRequestHeaderLayer::custom(Authenticate {
+        authenticator,
+        _response_body: PhantomData,
+    })
+}
+
+pub struct Authenticate<ResBody> {
+    authenticator: Arc<dyn Authenticator>,
+    _response_body: PhantomData<fn() -> ResBody>,
+}
+
+impl<ResBody> Clone for Authenticate<ResBody> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: Arc::clone(&self.authenticator),
+            _response_body: PhantomData,
+        }
+    }
+}
+
+impl<B, ResBody: Default> ValidateRequest<B> for Authenticate<ResBody> {
+    type ResponseBody = ResBody;
+
+    fn validate(&mut self, request: &mut Request<B>) -> Result<(), Response<ResBody>> {
+        if self
+            .authenticator
+            .authenticate(request.headers(), request.extensions())
+        {
+            return Ok(());
+        }
+
+        let mut response = Response::new(ResBody::default());
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        Err(response)
+    }
+}
+
+/// Layer rejecting gRPC calls which aren't authenticated with the `UNAUTHENTICATED` status, before
+/// they reach the services. This includes the calls which the nodes issue among each other, so the
+/// nodes attach their credentials with a [`BearerTokenInterceptor`].
+pub fn grpc_auth_layer<ResBody>(
+    authenticator: Arc<dyn Authenticator>,
+) -> ValidateRequestHeaderLayer<AuthenticateGrpc<ResBody>> {
+    ValidateRequestHeaderLayer::custom(AuthenticateGrpc {
+        authenticator,
+        _response_body: PhantomData,
+    })
+}
+
+pub struct AuthenticateGrpc<ResBody> {
+    authenticator: Arc<dyn Authenticator>,
+    _response_body: PhantomData<fn() -> ResBody>,
+}
+
+impl<ResBody> Clone for AuthenticateGrpc<ResBody> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: Arc::clone(&self.authenticator),
+            _response_body: PhantomData,
+        }
+    }
+}
+
+impl<B, ResBody: Default> ValidateRequest<B> for AuthenticateGrpc<ResBody> {
+    type ResponseBody = ResBody;
+
+    fn validate(&mut self, request: &mut Request<B>) -> Result<(), Response<ResBody>> {
+        if self
+            .authenticator
+            .authenticate(request.headers(), request.extensions())
+        {
+            return Ok(());
+        }
+
+        // trailers-only response, carrying the status in the headers
+        let mut response = Response::new(ResBody::default());
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/grpc"),
+        );
+        headers.insert(
+            "grpc-status",
+            HeaderValue::from(tonic::Code::Unauthenticated as i32),
+        );
+        headers.insert(
+            "grpc-message",
+            HeaderValue::from_static("missing%20or%20invalid%20credentials"),
+        );
+        headers.insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        Err(response)
+    }
+}
+
+/// Channel whose requests carry the credentials of a [`BearerTokenInterceptor`].
+pub type AuthenticatedChannel = InterceptedService<Channel, BearerTokenInterceptor>;
+
+/// Attaches the configured bearer token to outgoing gRPC requests, so that a node can call the
+/// authenticated services of the other nodes. Without a token, requests are sent unchanged.
+#[derive(Clone, Default)]
+pub struct BearerTokenInterceptor {
+    authorization: Option<MetadataValue<Ascii>>,
+}
+
+impl BearerTokenInterceptor {
+    pub fn from_options(opts: Option<&AuthOptions>) -> io::Result<Self> {
+        let authorization = opts
+            .map(|opts| {
+                let token = read_bearer_token(opts)?;
+                format!("Bearer {token}").parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "the bearer token file '{}' contains invalid characters",
+                            opts.bearer_token_file.display()
+                        ),
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(Self { authorization })
+    }
+}
+
+impl fmt::Debug for BearerTokenInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerTokenInterceptor")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Interceptor for BearerTokenInterceptor {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        if let Some(authorization) = &self.authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", authorization.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+    use std::io::Write;
+
+    use hyper::Body;
+    use rustls::Certificate;
+    use tower::{service_fn, ServiceBuilder, ServiceExt};
+
+    use crate::tls::PeerCertificates;
+
+    async fn request_status(
+        authenticator: Arc<dyn Authenticator>,
+        authorization: Option<&str>,
+    ) -> StatusCode {
+        let service = ServiceBuilder::new()
+            .layer(auth_layer(authenticator))
+            .service(service_fn(|_: Request<Body>| async {
+                Ok::<_, Infallible>(Response::new(Body::empty()))
+            }));
+
+        let mut request = Request::get("/metrics");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+
+        service
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn requests_with_token_are_allowed() {
+        let authenticator = Arc::new(BearerTokenAuthenticator::new("secret"));
+
+        assert_eq!(
+            request_status(authenticator, Some("Bearer secret")).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_without_valid_token_are_rejected() {
+        let authenticator = Arc::new(BearerTokenAuthenticator::new("secret"));
+
+        for authorization in [None, Some("Bearer wrong"), Some("Basic secret")] {
+            assert_eq!(
+                request_status(authenticator.clone(), authorization).await,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_authenticator_is_used() {
+        struct ApiKey;
+
+        impl Authenticator for ApiKey {
+            fn authenticate(&self, headers: &HeaderMap, _extensions: &Extensions) -> bool {
+                headers
+                    .get(header::AUTHORIZATION)
+                    .is_some_and(|value| value == "ApiKey my-key")
+            }
+        }
+
+        assert_eq!(
+            request_status(Arc::new(ApiKey), Some("ApiKey my-key")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request_status(Arc::new(ApiKey), Some("Bearer my-key")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn authenticator_can_check_the_peer_certificate() {
+        struct ClientCertificate;
+
+        impl Authenticator for ClientCertificate {
+            fn authenticate(&self, _headers: &HeaderMap, extensions: &Extensions) -> bool {
+                extensions
+                    .get::<PeerCertificates>()
+                    .is_some_and(|certs| certs.0.first() == Some(&Certificate(b"client".to_vec())))
+            }
+        }
+
+        let request_status = |peer_certificates: Option<PeerCertificates>| {
+            let service = ServiceBuilder::new()
+                .layer(auth_layer(Arc::new(ClientCertificate)))
+                .service(service_fn(|_: Request<Body>| async {
+                    Ok::<_, Infallible>(Response::new(Body::empty()))
+                }));
+            let mut request = Request::get("/metrics").body(Body::empty()).unwrap();
+            if let Some(peer_certificates) = peer_certificates {
+                request.extensions_mut().insert(peer_certificates);
+            }
+            async move { service.oneshot(request).await.unwrap().status() }
+        };
+
+        assert_eq!(
+            request_status(Some(PeerCertificates(
+                vec![Certificate(b"client".to_vec())].into()
+            )))
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request_status(Some(PeerCertificates(
+                vec![Certificate(b"other".to_vec())].into()
+            )))
+            .await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(request_status(None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    async fn grpc_request_status(
+        authenticator: Arc<dyn Authenticator>,
+        path: &str,
+        authorization: Option<&str>,
+    ) -> Option<String> {
+        let service = ServiceBuilder::new()
+            .layer(grpc_auth_layer(authenticator))
+            .service(service_fn(|_: Request<Body>| async {
+                let mut response = Response::new(Body::empty());
+                response
+                    .headers_mut()
+                    .insert("grpc-status", HeaderValue::from(0));
+                Ok::<_, Infallible>(response)
+            }));
+
+        let mut request = Request::post(path);
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+
+        let response = service
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response
+            .headers()
+            .get("grpc-status")
+            .map(|status| status.to_str().unwrap().to_owned())
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_grpc_calls_are_rejected() {
+        let authenticator = Arc::new(BearerTokenAuthenticator::new("secret"));
+        let unauthenticated = (tonic::Code::Unauthenticated as i32).to_string();
+
+        assert_eq!(
+            grpc_request_status(
+                authenticator.clone(),
+                "/dev.restate.node_svc.NodeSvc/SetLogFilter",
+                None
+            )
+            .await,
+            Some(unauthenticated.clone())
+        );
+        assert_eq!(
+            grpc_request_status(
+                authenticator.clone(),
+                "/dev.restate.node_svc.NodeSvc/SetLogFilter",
+                Some("Bearer secret")
+            )
+            .await,
+            Some("0".to_owned())
+        );
+        // the calls among the nodes need the credentials as well
+        assert_eq!(
+            grpc_request_status(
+                authenticator,
+                "/dev.restate.node_svc.NodeSvc/GetIdent",
+                None
+            )
+            .await,
+            Some(unauthenticated)
+        );
+    }
+
+    #[test]
+    fn interceptor_attaches_the_token() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(token_file, "secret").unwrap();
+        let opts = AuthOptions {
+            bearer_token_file: token_file.path().to_owned(),
+        };
+
+        let mut interceptor = BearerTokenInterceptor::from_options(Some(&opts)).unwrap();
+        let request = interceptor.call(tonic::Request::new(())).unwrap();
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer secret"
+        );
+
+        let mut interceptor = BearerTokenInterceptor::from_options(None).unwrap();
+        let request = interceptor.call(tonic::Request::new(())).unwrap();
+        assert!(request.metadata().get("authorization").is_none());
+    }
+
+    #[test]
+    fn token_is_read_from_file() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(token_file, "secret").unwrap();
+        let opts = AuthOptions {
+            bearer_token_file: token_file.path().to_owned(),
+        };
+
+        let authenticator = BearerTokenAuthenticator::from_options(&opts).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(authenticator.authenticate(&headers, &Extensions::new()));
+    }
+}
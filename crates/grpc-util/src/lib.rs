@@ -8,6 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod auth;
+pub mod tls;
+
+use std::convert::Infallible;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -17,18 +21,24 @@ use http::Uri;
 use hyper::body::HttpBody;
 use hyper::server::accept::Accept;
 use hyper::server::conn::AddrIncoming;
+use restate_types::config::Configuration;
 use restate_types::net::{AdvertisedAddress, BindAddress};
 use tokio::io;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{UnixListener, UnixStream};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
 use tokio_stream::wrappers::UnixListenerStream;
 use tonic::transport::{Channel, Endpoint};
-use tower::service_fn;
+use tower::{service_fn, ServiceExt};
 use tracing::{debug, info};
 
+/// Creates a lazily connecting channel to the given address. Addresses with the `https` scheme are
+/// connected to over TLS, verifying the server certificate against the system's root certificates
+/// and the CA configured in `tls.ca-path`.
 pub fn create_grpc_channel_from_advertised_address(
     address: AdvertisedAddress,
-) -> Result<Channel, http::Error> {
+) -> Result<Channel, ChannelError> {
     let channel = match address {
         AdvertisedAddress::Uds(uds_path) => {
             // dummy endpoint required to specify an uds connector, it is not used anywhere
@@ -39,17 +49,35 @@ pub fn create_grpc_channel_from_advertised_address(
                 }))
         }
         AdvertisedAddress::Http(uri) => {
+            let use_tls = uri.scheme() == Some(&http::uri::Scheme::HTTPS);
             // todo: Make the channel settings configurable
-            Channel::builder(uri)
+            let mut endpoint = Channel::builder(uri)
                 .connect_timeout(Duration::from_secs(5))
                 // todo: configure the channel from configuration file
-                .http2_adaptive_window(true)
-                .connect_lazy()
+                .http2_adaptive_window(true);
+            if use_tls {
+                let tls_config =
+                    tls::client_tls_config(Configuration::pinned().common.tls.as_ref())?;
+                endpoint = endpoint.tls_config(tls_config)?;
+            }
+            endpoint.connect_lazy()
         }
     };
     Ok(channel)
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum ChannelError {
+    #[error("failed loading the CA certificate '{}' specified in 'tls': {source}", ca_path.display())]
+    Ca {
+        ca_path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error(transparent)]
+    Transport(#[from] tonic::transport::Error),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("failed binding to address '{address}': {source}")]
@@ -64,6 +92,12 @@ pub enum Error {
         #[source]
         source: io::Error,
     },
+    #[error("failed loading the TLS certificate '{}' or private key '{}' specified in 'tls': {reason}", cert_path.display(), key_path.display())]
+    Tls {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        reason: String,
+    },
     #[error("failed running grpc server: {0}")]
     Running(#[from] hyper::Error),
 }
@@ -74,6 +108,30 @@ pub async fn run_hyper_server<S, B, F>(
     shutdown_signal: F,
     server_name: &str,
 ) -> Result<(), Error>
+where
+    S: hyper::service::Service<http::Request<hyper::Body>, Response = hyper::Response<B>>
+        + Send
+        + Clone
+        + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    F: Future<Output = ()>,
+{
+    run_hyper_server_with_tls(bind_address, None, service, shutdown_signal, server_name).await
+}
+
+/// Like [`run_hyper_server`], but terminates TLS on the accepted connections if a TLS acceptor is
+/// given.
+pub async fn run_hyper_server_with_tls<S, B, F>(
+    bind_address: &BindAddress,
+    tls_acceptor: Option<TlsAcceptor>,
+    service: S,
+    shutdown_signal: F,
+    server_name: &str,
+) -> Result<(), Error>
 where
     S: hyper::service::Service<http::Request<hyper::Body>, Response = hyper::Response<B>>
         + Send
@@ -95,12 +153,23 @@ where
             let acceptor =
                 hyper::server::accept::from_stream(UnixListenerStream::new(unix_listener));
 
-            info!(uds.path = %uds_path.display(), "Server '{}' listening", server_name);
+            info!(
+                uds.path = %uds_path.display(),
+                tls = tls_acceptor.is_some(),
+                "Server '{}' listening", server_name
+            );
 
-            run_server(acceptor, service, shutdown_signal).await?
+            run_server(acceptor, tls_acceptor, service, shutdown_signal).await?
         }
         BindAddress::Socket(socket_addr) => {
-            run_tcp_server(socket_addr, service, shutdown_signal, server_name).await?
+            run_tcp_server(
+                socket_addr,
+                tls_acceptor,
+                service,
+                shutdown_signal,
+                server_name,
+            )
+            .await?
         }
     }
 
@@ -111,6 +180,7 @@ where
 
 async fn run_tcp_server<S, B, F>(
     socket_addr: &SocketAddr,
+    tls_acceptor: Option<TlsAcceptor>,
     service: S,
     shutdown_signal: F,
     server_name: &str,
@@ -135,14 +205,16 @@ where
     info!(
         net.host.addr = %acceptor.local_addr().ip(),
         net.host.port = %acceptor.local_addr().port(),
+        tls = tls_acceptor.is_some(),
         "Server '{}' listening", server_name
     );
 
-    run_server(acceptor, service, shutdown_signal).await
+    run_server(acceptor, tls_acceptor, service, shutdown_signal).await
 }
 
-async fn run_server<S, B, Conn, Err, F>(
-    acceptor: impl Accept<Conn = Conn, Error = Err>,
+async fn run_server<S, B, I, F>(
+    acceptor: I,
+    tls_acceptor: Option<TlsAcceptor>,
     service: S,
     shutdown_signal: F,
 ) -> Result<(), Error>
@@ -156,14 +228,39 @@ where
     B: HttpBody + Send + 'static,
     B::Data: Send,
     B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
-    Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
-    Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+    I: Accept + Send + 'static,
+    I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    I::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
     F: Future<Output = ()>,
 {
-    let server = hyper::Server::builder(acceptor).serve(tower::make::Shared::new(service));
-
-    server
-        .with_graceful_shutdown(shutdown_signal)
-        .await
-        .map_err(Error::Running)
+    match tls_acceptor {
+        Some(tls_acceptor) => {
+            // the client certificate of a connection is passed on to the authentication of its
+            // requests
+            let make_service =
+                hyper::service::make_service_fn(move |connection: &TlsStream<I::Conn>| {
+                    let peer_certificates = tls::PeerCertificates::of(connection);
+                    let service = service.clone().map_request(
+                        move |mut request: http::Request<hyper::Body>| {
+                            if let Some(peer_certificates) = &peer_certificates {
+                                request.extensions_mut().insert(peer_certificates.clone());
+                            }
+                            request
+                        },
+                    );
+                    futures::future::ready(Ok::<_, Infallible>(service))
+                });
+            hyper::Server::builder(tls::accept(acceptor, tls_acceptor))
+                .serve(make_service)
+                .with_graceful_shutdown(shutdown_signal)
+                .await
+        }
+        None => {
+            hyper::Server::builder(acceptor)
+                .serve(tower::make::Shared::new(service))
+                .with_graceful_shutdown(shutdown_signal)
+                .await
+        }
+    }
+    .map_err(Error::Running)
 }
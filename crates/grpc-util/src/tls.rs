@@ -0,0 +1,245 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Termination of TLS on the connections accepted by the servers of a node, and TLS configuration
+//! of the channels to other nodes.
+
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{future, StreamExt};
+use hyper::server::accept::Accept;
+use restate_types::config::TlsOptions;
+use rustls::server::AllowAnyAnonymousOrAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use rustls_pemfile::Item;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tonic::transport::ClientTlsConfig;
+use tracing::debug;
+
+use crate::{ChannelError, Error};
+
+/// Upper bound for the number of TLS handshakes that are performed concurrently.
+const MAX_CONCURRENT_HANDSHAKES: usize = 64;
+/// Connections that don't complete the TLS handshake within this time are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Certificate chain which the client presented during the TLS handshake, starting with the
+/// client certificate. It is added to the extensions of the requests of a connection if the client
+/// authenticated with a certificate signed by one of the configured client CAs, so that an
+/// [`Authenticator`](crate::auth::Authenticator) can check its subject.
+#[derive(Debug, Clone)]
+pub struct PeerCertificates(pub Arc<[Certificate]>);
+
+impl PeerCertificates {
+    pub(crate) fn of<IO>(connection: &TlsStream<IO>) -> Option<Self> {
+        connection
+            .get_ref()
+            .1
+            .peer_certificates()
+            .map(|certs| PeerCertificates(certs.into()))
+    }
+}
+
+/// Loads the certificate and private key of the given options, failing if either is unusable.
+pub fn load_tls_acceptor(opts: &TlsOptions) -> Result<TlsAcceptor, Error> {
+    let to_error = |reason: String| Error::Tls {
+        cert_path: opts.cert_path.clone(),
+        key_path: opts.key_path.clone(),
+        reason,
+    };
+
+    let certs = load_certs(&opts.cert_path).map_err(|e| to_error(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(to_error("no certificate found".to_owned()));
+    }
+    let key = load_private_key(&opts.key_path)
+        .map_err(|e| to_error(e.to_string()))?
+        .ok_or_else(|| to_error("no private key found".to_owned()))?;
+
+    let config_builder = ServerConfig::builder().with_safe_defaults();
+    let config_builder = match &opts.client_ca_path {
+        Some(client_ca_path) => {
+            let client_ca_certs = load_certs(client_ca_path).map_err(|e| {
+                to_error(format!(
+                    "failed loading the client CA '{}': {e}",
+                    client_ca_path.display()
+                ))
+            })?;
+            let mut roots = RootCertStore::empty();
+            for cert in &client_ca_certs {
+                roots.add(cert).map_err(|e| to_error(e.to_string()))?;
+            }
+            if roots.is_empty() {
+                return Err(to_error(format!(
+                    "no client CA certificate found in '{}'",
+                    client_ca_path.display()
+                )));
+            }
+            config_builder.with_client_cert_verifier(
+                AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed(),
+            )
+        }
+        None => config_builder.with_no_client_auth(),
+    };
+    let mut config = config_builder
+        .with_single_cert(certs, key)
+        .map_err(|e| to_error(e.to_string()))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// TLS configuration of the channels to other nodes. Their server certificates are verified
+/// against the system's root certificates and the configured CA, if any.
+pub(crate) fn client_tls_config(
+    opts: Option<&TlsOptions>,
+) -> Result<ClientTlsConfig, ChannelError> {
+    let mut config = ClientTlsConfig::new();
+    if let Some(ca_path) = opts.and_then(|opts| opts.ca_path.as_ref()) {
+        let ca_pem = std::fs::read(ca_path).map_err(|source| ChannelError::Ca {
+            ca_path: ca_path.clone(),
+            source,
+        })?;
+        config = config.ca_certificate(tonic::transport::Certificate::from_pem(ca_pem));
+    }
+    Ok(config)
+}
+
+fn load_certs(path: &Path) -> io::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect())
+}
+
+fn load_private_key(path: &Path) -> io::Result<Option<PrivateKey>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    for item in rustls_pemfile::read_all(&mut reader)? {
+        match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => {
+                return Ok(Some(PrivateKey(key)))
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Terminates TLS on the connections accepted by `incoming`. Connections that fail the handshake
+/// are dropped without affecting the other connections.
+pub(crate) fn accept<I>(
+    incoming: I,
+    tls_acceptor: TlsAcceptor,
+) -> impl Accept<Conn = TlsStream<I::Conn>, Error = I::Error>
+where
+    I: Accept + Send + 'static,
+    I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    I::Error: Send + 'static,
+{
+    let mut incoming = Box::pin(incoming);
+    let connections = futures::stream::poll_fn(move |cx| incoming.as_mut().poll_accept(cx));
+
+    let tls_connections = connections
+        .map(move |connection| {
+            let tls_acceptor = tls_acceptor.clone();
+            async move {
+                let connection = match connection {
+                    Ok(connection) => connection,
+                    Err(err) => return Some(Err(err)),
+                };
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, tls_acceptor.accept(connection)).await
+                {
+                    Ok(Ok(tls_connection)) => Some(Ok(tls_connection)),
+                    Ok(Err(err)) => {
+                        debug!(%err, "TLS handshake failed");
+                        None
+                    }
+                    Err(_) => {
+                        debug!("TLS handshake timed out");
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+        .filter_map(future::ready);
+
+    hyper::server::accept::from_stream(tls_connections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_test_util::{assert, let_assert};
+    use std::io::Write;
+
+    #[test]
+    fn missing_certificate_fails() {
+        let opts = TlsOptions {
+            cert_path: "/does/not/exist/cert.pem".into(),
+            key_path: "/does/not/exist/key.pem".into(),
+            client_ca_path: None,
+            ca_path: None,
+        };
+
+        let_assert!(Err(Error::Tls { cert_path, .. }) = load_tls_acceptor(&opts).map(|_| ()));
+        assert!(cert_path == opts.cert_path);
+    }
+
+    #[test]
+    fn file_without_certificate_fails() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "not a pem file").unwrap();
+        let opts = TlsOptions {
+            cert_path: file.path().to_owned(),
+            key_path: file.path().to_owned(),
+            client_ca_path: None,
+            ca_path: None,
+        };
+
+        let_assert!(Err(Error::Tls { reason, .. }) = load_tls_acceptor(&opts).map(|_| ()));
+        assert!(reason == "no certificate found");
+    }
+
+    #[test]
+    fn client_tls_config_without_ca() {
+        let_assert!(Ok(_) = client_tls_config(None));
+
+        let opts = TlsOptions {
+            cert_path: "/does/not/exist/cert.pem".into(),
+            key_path: "/does/not/exist/key.pem".into(),
+            client_ca_path: None,
+            ca_path: None,
+        };
+        let_assert!(Ok(_) = client_tls_config(Some(&opts)));
+    }
+
+    #[test]
+    fn missing_ca_fails() {
+        let opts = TlsOptions {
+            cert_path: "/does/not/exist/cert.pem".into(),
+            key_path: "/does/not/exist/key.pem".into(),
+            client_ca_path: None,
+            ca_path: Some("/does/not/exist/ca.pem".into()),
+        };
+
+        let_assert!(Err(ChannelError::Ca { ca_path, .. }) = client_tls_config(Some(&opts)));
+        assert!(Some(ca_path) == opts.ca_path);
+    }
+}
@@ -27,7 +27,7 @@ use restate_types::GenerationalNodeId;
 use restate_wal_protocol::{
     append_envelope_to_bifrost, Command, Destination, Envelope, Header, Source,
 };
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tracing::{debug, trace};
 
@@ -45,6 +45,10 @@ pub trait DispatchIngressRequest {
 struct IngressDispatcherState {
     msg_index: AtomicU64,
 
+    // While quiesced, new invocations are rejected. Responses and attach requests for
+    // in-flight invocations are still accepted.
+    quiesced: AtomicBool,
+
     // TODO those two maps below can be replaced with the ResponseTracker from the network module
 
     // This map can be unbounded, because we enforce concurrency limits in the ingress
@@ -59,8 +63,7 @@ struct IngressDispatcherState {
 
 impl IngressDispatcherState {
     fn get_and_increment_msg_index(&self) -> MessageIndex {
-        self.msg_index
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        self.msg_index.fetch_add(1, Ordering::Relaxed)
     }
 }
 
@@ -76,6 +79,15 @@ impl IngressDispatcher {
             state: Arc::new(IngressDispatcherState::default()),
         }
     }
+
+    /// Stops (or resumes) accepting new invocations.
+    pub fn set_quiesced(&self, quiesced: bool) {
+        self.state.quiesced.store(quiesced, Ordering::Relaxed);
+    }
+
+    pub fn is_quiesced(&self) -> bool {
+        self.state.quiesced.load(Ordering::Relaxed)
+    }
 }
 
 impl DispatchIngressRequest for IngressDispatcher {
@@ -97,6 +109,16 @@ impl DispatchIngressRequest for IngressDispatcher {
             request_mode,
         } = ingress_request;
 
+        if self.is_quiesced()
+            && matches!(
+                inner,
+                IngressDispatcherRequestInner::Invoke(_)
+                    | IngressDispatcherRequestInner::ProxyThrough(_)
+            )
+        {
+            return Err(IngressDispatchError::Quiesced);
+        }
+
         let (dedup_source, msg_index, proxying_partition_key) = match request_mode {
             IngressRequestMode::RequestResponse(ingress_response_key, response_sender) => {
                 self.state
@@ -457,4 +479,54 @@ mod tests {
             .await
             .unwrap()
     }
+
+    #[test(tokio::test)]
+    async fn quiesced_dispatcher_rejects_new_invocations() {
+        let mut env_builder = TestCoreEnvBuilder::new_with_mock_network()
+            .add_mock_nodes_config()
+            .with_partition_table(FixedPartitionTable::new(Version::MIN, 1));
+
+        let bifrost_svc = restate_bifrost::BifrostService::new(env_builder.metadata.clone());
+        let bifrost = bifrost_svc.handle();
+        let dispatcher = IngressDispatcher::new(bifrost.clone());
+
+        env_builder = env_builder.add_message_handler(dispatcher.clone());
+        let node_env = env_builder.build().await;
+
+        node_env
+            .tc
+            .run_in_scope("test", None, async {
+                bifrost_svc.start().await?;
+
+                let new_invocation = || {
+                    IngressDispatcherRequest::invocation(ServiceInvocation::initialize(
+                        InvocationId::mock_random(),
+                        InvocationTarget::mock_service(),
+                        restate_types::invocation::Source::Ingress,
+                    ))
+                    .0
+                };
+
+                dispatcher.set_quiesced(true);
+                let_assert!(
+                    Err(IngressDispatchError::Quiesced) =
+                        dispatcher.dispatch_ingress_request(new_invocation()).await
+                );
+
+                // attaching to existing invocations is still possible
+                let (attach_req, _, _) = IngressDispatcherRequest::attach(
+                    InvocationQuery::Invocation(InvocationId::mock_random()),
+                );
+                dispatcher.dispatch_ingress_request(attach_req).await?;
+
+                dispatcher.set_quiesced(false);
+                dispatcher
+                    .dispatch_ingress_request(new_invocation())
+                    .await?;
+
+                Ok::<(), anyhow::Error>(())
+            })
+            .await
+            .unwrap()
+    }
 }
@@ -16,4 +16,6 @@ pub enum IngressDispatchError {
     WalProtocol(#[from] restate_wal_protocol::Error),
     #[error("partition routing error: {0}")]
     PartitionRoutingError(#[from] PartitionTableError),
+    #[error("node is quiesced and does not accept new invocations")]
+    Quiesced,
 }
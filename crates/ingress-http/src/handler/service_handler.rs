@@ -14,7 +14,10 @@ use super::HandlerError;
 use super::{Handler, APPLICATION_JSON};
 
 use crate::handler::responses::{IDEMPOTENCY_EXPIRES, X_RESTATE_ID};
-use crate::metric_definitions::{INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, REQUEST_COMPLETED};
+use crate::metric_definitions::{
+    INGRESS_REQUESTS, INGRESS_REQUEST_DURATION, INGRESS_REQUEST_FAILED, INGRESS_REQUEST_SIZE,
+    INGRESS_REQUEST_SUCCEEDED, INGRESS_RESPONSE_SIZE, REQUEST_COMPLETED,
+};
 use bytes::Bytes;
 use bytestring::ByteString;
 use http::{header, HeaderMap, HeaderName, Method, Request, Response, StatusCode};
@@ -134,6 +137,12 @@ where
         let (ingress_span, ingress_span_context) =
             prepare_tracing_span(&invocation_id, &invocation_target, &req);
 
+        let request_size_histogram = histogram!(
+            INGRESS_REQUEST_SIZE,
+            "rpc.service" => service_name.clone(),
+            "rpc.method" => handler_name.clone(),
+        );
+
         let result = async move {
             info!("Processing ingress request");
 
@@ -151,6 +160,7 @@ where
                 .map_err(|e| HandlerError::Body(e.into()))?
                 .to_bytes();
             trace!(rpc.request = ?body);
+            request_size_histogram.record(body.len() as f64);
 
             // Validate content-type and body
             invocation_target_meta.input_rules.validate(
@@ -215,13 +225,27 @@ where
         )
         .record(start_time.elapsed());
 
+        if let Some(response_size) = result
+            .as_ref()
+            .ok()
+            .and_then(|response| http_body::Body::size_hint(response.body()).exact())
+        {
+            histogram!(
+                INGRESS_RESPONSE_SIZE,
+                "rpc.service" => service_name.clone(),
+                "rpc.method" => handler_name.clone(),
+            )
+            .record(response_size as f64);
+        }
+
         counter!(
             INGRESS_REQUESTS,
             "status" => REQUEST_COMPLETED,
-            "rpc.service" => service_name,
-            "rpc.method" => handler_name,
+            "rpc.service" => service_name.clone(),
+            "rpc.method" => handler_name.clone(),
         )
         .increment(1);
+        record_request_outcome(service_name, handler_name, &result);
         result
     }
 
@@ -313,6 +337,28 @@ where
     }
 }
 
+pub(crate) fn record_request_outcome<B>(
+    service_name: String,
+    handler_name: String,
+    result: &Result<Response<B>, HandlerError>,
+) {
+    match result {
+        Ok(_) => counter!(
+            INGRESS_REQUEST_SUCCEEDED,
+            "rpc.service" => service_name,
+            "rpc.method" => handler_name,
+        )
+        .increment(1),
+        Err(err) => counter!(
+            INGRESS_REQUEST_FAILED,
+            "rpc.service" => service_name,
+            "rpc.method" => handler_name,
+            "status" => err.status_code().as_str().to_owned(),
+        )
+        .increment(1),
+    }
+}
+
 fn parse_headers(headers: HeaderMap) -> Result<Vec<Header>, HandlerError> {
     headers
         .into_iter()
@@ -12,7 +12,7 @@ use super::health::HealthResponse;
 use super::mocks::*;
 use super::service_handler::*;
 use super::ConnectInfo;
-use super::Handler;
+use super::{Handler, HandlerError};
 use restate_ingress_dispatcher::{IngressInvocationResponse, SubmittedInvocationNotification};
 use std::collections::HashMap;
 
@@ -1038,6 +1038,85 @@ async fn health() {
     let _: HealthResponse = serde_json::from_slice(&response_bytes).unwrap();
 }
 
+#[test]
+fn failed_request_increments_failure_counter() {
+    use crate::metric_definitions::{INGRESS_REQUEST_FAILED, INGRESS_REQUEST_SUCCEEDED};
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct CountingRecorder {
+        counters: Mutex<HashMap<Key, Arc<AtomicU64>>>,
+    }
+
+    impl CountingRecorder {
+        fn value(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+            self.counters
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(key, _)| {
+                    key.name() == name
+                        && labels.iter().all(|(k, v)| {
+                            key.labels()
+                                .any(|label| label.key() == *k && label.value() == *v)
+                        })
+                })
+                .map(|(_, counter)| counter.load(Ordering::Relaxed))
+                .sum()
+        }
+    }
+
+    impl Recorder for CountingRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(Arc::clone(
+                self.counters
+                    .lock()
+                    .unwrap()
+                    .entry(key.clone())
+                    .or_default(),
+            ))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    let recorder = CountingRecorder::default();
+    metrics::with_local_recorder(&recorder, || {
+        record_request_outcome::<Full<Bytes>>(
+            "greeter.Greeter".to_owned(),
+            "greet".to_owned(),
+            &Err(HandlerError::NotFound),
+        );
+    });
+
+    assert_eq!(
+        recorder.value(
+            INGRESS_REQUEST_FAILED,
+            &[
+                ("rpc.service", "greeter.Greeter"),
+                ("rpc.method", "greet"),
+                ("status", "404")
+            ]
+        ),
+        1
+    );
+    assert_eq!(recorder.value(INGRESS_REQUEST_SUCCEEDED, &[]), 0);
+}
+
 fn request_handler_not_reached(_req: IngressDispatcherRequest) {
     panic!("This code should not be reached in this test");
 }
@@ -18,7 +18,12 @@ pub const REQUEST_ADMITTED: &str = "admitted";
 pub const REQUEST_COMPLETED: &str = "completed";
 pub const REQUEST_DENIED_THROTTLE: &str = "throttled";
 
+pub const INGRESS_REQUEST_SUCCEEDED: &str = "restate.ingress.requests_succeeded.total";
+pub const INGRESS_REQUEST_FAILED: &str = "restate.ingress.requests_failed.total";
+
 pub const INGRESS_REQUEST_DURATION: &str = "restate.ingress.request_duration.seconds";
+pub const INGRESS_REQUEST_SIZE: &str = "restate.ingress.request_size.bytes";
+pub const INGRESS_RESPONSE_SIZE: &str = "restate.ingress.response_size.bytes";
 
 pub(crate) fn describe_metrics() {
     describe_counter!(
@@ -26,9 +31,29 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of ingress requests in different states, see label state to classify"
     );
+    describe_counter!(
+        INGRESS_REQUEST_SUCCEEDED,
+        Unit::Count,
+        "Number of ingress requests to a service handler which succeeded, by service and handler"
+    );
+    describe_counter!(
+        INGRESS_REQUEST_FAILED,
+        Unit::Count,
+        "Number of ingress requests to a service handler which failed, by service, handler and response status code"
+    );
     describe_histogram!(
         INGRESS_REQUEST_DURATION,
         Unit::Seconds,
         "Total latency of Ingress request processing in seconds"
     );
+    describe_histogram!(
+        INGRESS_REQUEST_SIZE,
+        Unit::Bytes,
+        "Size of the ingress request bodies in bytes"
+    );
+    describe_histogram!(
+        INGRESS_RESPONSE_SIZE,
+        Unit::Bytes,
+        "Size of the ingress response bodies in bytes"
+    );
 }
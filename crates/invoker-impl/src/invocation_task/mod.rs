@@ -28,7 +28,7 @@ use restate_schema_api::deployment::DeploymentResolver;
 use restate_service_client::{Request, ServiceClient, ServiceClientError};
 use restate_service_protocol::message::{EncodingError, MessageType};
 use restate_types::deployment::PinnedDeployment;
-use restate_types::errors::InvocationError;
+use restate_types::errors::{codes, InvocationError};
 use restate_types::identifiers::{DeploymentId, EntryIndex, InvocationId, PartitionLeaderEpoch};
 use restate_types::invocation::InvocationTarget;
 use restate_types::journal::enriched::EnrichedRawEntry;
@@ -54,6 +54,9 @@ use tracing::instrument;
 const SERVICE_PROTOCOL_VERSION_V1: HeaderValue =
     HeaderValue::from_static("application/vnd.restate.invocation.v1");
 
+/// Prefix shared by the content types of all service protocol versions.
+const SERVICE_PROTOCOL_CONTENT_TYPE_PREFIX: &str = "application/vnd.restate.invocation.";
+
 #[allow(clippy::declare_interior_mutable_const)]
 const X_RESTATE_SERVER: HeaderName = HeaderName::from_static("x-restate-server");
 
@@ -130,6 +133,9 @@ pub(crate) enum InvocationTaskError {
     #[error("cannot resume invocation because it was created with an incompatible service protocol version '{}' and the server does not support upgrading versions yet", .0.as_repr())]
     #[code(restate_errors::RT0014)]
     UnsupportedServiceProtocolVersion(ServiceProtocolVersion),
+    #[error("the service endpoint responded with content type '{0:?}' of a different service protocol version than the negotiated version '{}'. This usually means that the SDK version of the service is incompatible with this Restate server", .1.as_repr())]
+    #[code(restate_errors::RT0013)]
+    ServiceProtocolVersionMismatch(HeaderValue, ServiceProtocolVersion),
 }
 
 #[derive(Debug, Default)]
@@ -141,7 +147,20 @@ pub struct InvocationErrorRelatedEntry {
 
 impl InvocationTaskError {
     pub(crate) fn is_transient(&self) -> bool {
-        true
+        // Retrying won't help as long as the service runs with an incompatible SDK
+        !matches!(
+            self,
+            InvocationTaskError::ServiceProtocolVersionMismatch(_, _)
+        )
+    }
+
+    pub(crate) fn is_protocol_version_mismatch(&self) -> bool {
+        matches!(
+            self,
+            InvocationTaskError::ServiceProtocolVersionMismatch(_, _)
+                | InvocationTaskError::IncompatibleServiceEndpoint(_, _)
+                | InvocationTaskError::UnsupportedServiceProtocolVersion(_)
+        )
     }
 
     pub(crate) fn into_invocation_error(self) -> InvocationError {
@@ -160,6 +179,9 @@ impl InvocationTaskError {
                 }
                 err
             }
+            e @ InvocationTaskError::ServiceProtocolVersionMismatch(_, _) => {
+                InvocationError::new(codes::PROTOCOL_VIOLATION, e)
+            }
             e => InvocationError::internal(e),
         }
     }
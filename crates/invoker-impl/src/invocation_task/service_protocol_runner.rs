@@ -11,7 +11,7 @@
 use crate::invocation_task::{
     service_protocol_version_to_header_value, InvocationErrorRelatedEntry, InvocationTask,
     InvocationTaskError, InvocationTaskOutputInner, ResponseChunk, ResponseStreamState,
-    TerminalLoopState, X_RESTATE_SERVER,
+    TerminalLoopState, SERVICE_PROTOCOL_CONTENT_TYPE_PREFIX, X_RESTATE_SERVER,
 };
 use crate::Notification;
 use bytes::Bytes;
@@ -19,6 +19,7 @@ use futures::future::FusedFuture;
 use futures::{FutureExt, Stream, StreamExt};
 use hyper::body::Sender;
 use hyper::http::uri::PathAndQuery;
+use hyper::http::HeaderValue;
 use hyper::{http, Body, HeaderMap};
 use opentelemetry::propagation::TextMapPropagator;
 use opentelemetry_http::HeaderInjector;
@@ -412,27 +413,10 @@ where
             return Err(InvocationTaskError::UnexpectedResponse(parts.status));
         }
 
-        let content_type = parts.headers.remove(http::header::CONTENT_TYPE);
-        let expected_content_type =
-            service_protocol_version_to_header_value(self.service_protocol_version);
-        match content_type {
-            Some(ct) =>
-            {
-                #[allow(clippy::borrow_interior_mutable_const)]
-                if ct != expected_content_type {
-                    return Err(InvocationTaskError::UnexpectedContentType(
-                        Some(ct),
-                        expected_content_type,
-                    ));
-                }
-            }
-            None => {
-                return Err(InvocationTaskError::UnexpectedContentType(
-                    None,
-                    expected_content_type,
-                ))
-            }
-        }
+        check_response_content_type(
+            parts.headers.remove(http::header::CONTENT_TYPE),
+            self.service_protocol_version,
+        )?;
 
         if let Some(hv) = parts.headers.remove(X_RESTATE_SERVER) {
             self.invocation_task
@@ -536,3 +520,71 @@ where
         }
     }
 }
+
+fn check_response_content_type(
+    content_type: Option<HeaderValue>,
+    service_protocol_version: ServiceProtocolVersion,
+) -> Result<(), InvocationTaskError> {
+    let expected_content_type = service_protocol_version_to_header_value(service_protocol_version);
+    match content_type {
+        #[allow(clippy::borrow_interior_mutable_const)]
+        Some(ct) if ct == expected_content_type => Ok(()),
+        Some(ct)
+            if ct
+                .to_str()
+                .is_ok_and(|ct| ct.starts_with(SERVICE_PROTOCOL_CONTENT_TYPE_PREFIX)) =>
+        {
+            Err(InvocationTaskError::ServiceProtocolVersionMismatch(
+                ct,
+                service_protocol_version,
+            ))
+        }
+        ct => Err(InvocationTaskError::UnexpectedContentType(
+            ct,
+            expected_content_type,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_test_util::{assert, let_assert};
+    use restate_types::errors::codes;
+
+    #[test]
+    fn response_of_other_service_protocol_version_is_mismatch() {
+        assert!(let Ok(()) = check_response_content_type(
+            Some(HeaderValue::from_static("application/vnd.restate.invocation.v1")),
+            ServiceProtocolVersion::V1
+        ));
+
+        let_assert!(
+            Err(err) = check_response_content_type(
+                Some(HeaderValue::from_static(
+                    "application/vnd.restate.invocation.v2"
+                )),
+                ServiceProtocolVersion::V1
+            )
+        );
+        assert!(matches!(
+            err,
+            InvocationTaskError::ServiceProtocolVersionMismatch(_, ServiceProtocolVersion::V1)
+        ));
+        assert!(!err.is_transient());
+        assert!(err.into_invocation_error().code() == codes::PROTOCOL_VIOLATION);
+
+        let_assert!(
+            Err(err) = check_response_content_type(
+                Some(HeaderValue::from_static("application/json")),
+                ServiceProtocolVersion::V1
+            )
+        );
+        assert!(matches!(
+            err,
+            InvocationTaskError::UnexpectedContentType(_, _)
+        ));
+        assert!(err.is_transient());
+    }
+}
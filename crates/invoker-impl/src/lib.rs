@@ -60,8 +60,8 @@ use restate_types::deployment::PinnedDeployment;
 use restate_types::invocation::InvocationTarget;
 
 use crate::metric_definitions::{
-    INVOKER_ENQUEUE, INVOKER_INVOCATION_TASK, TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED,
-    TASK_OP_SUSPENDED,
+    INVOKER_ENQUEUE, INVOKER_INVOCATION_TASK, INVOKER_PROTOCOL_VERSION_MISMATCH, TASK_OP_COMPLETED,
+    TASK_OP_FAILED, TASK_OP_STARTED, TASK_OP_SUSPENDED,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -248,8 +248,20 @@ where
     }
 
     pub async fn run(
+        self,
+        updateable_options: impl Updateable<InvokerOptions> + Send + 'static,
+    ) -> anyhow::Result<()> {
+        self.run_until(updateable_options, cancellation_watcher())
+            .await
+    }
+
+    /// Runs the invoker until `shutdown` completes. In contrast to [`Service::run`], the
+    /// invoker does not react to the cancellation of its task. This allows the caller to shut
+    /// down the invoker only after the partition processors which receive its effects are gone.
+    pub async fn run_until(
         self,
         mut updateable_options: impl Updateable<InvokerOptions> + Send + 'static,
+        shutdown: impl Future<Output = ()>,
     ) -> anyhow::Result<()> {
         let Service {
             tmp_dir,
@@ -257,7 +269,6 @@ where
             ..
         } = self;
 
-        let shutdown = cancellation_watcher();
         tokio::pin!(shutdown);
 
         let in_memory_limit = updateable_options.load().in_memory_queue_length_limit();
@@ -871,6 +882,9 @@ where
         error: InvocationTaskError,
         mut ism: InvocationStateMachine,
     ) {
+        if error.is_protocol_version_mismatch() {
+            counter!(INVOKER_PROTOCOL_VERSION_MISMATCH).increment(1);
+        }
         match ism.handle_task_error() {
             Some(next_retry_timer_duration) if error.is_transient() => {
                 counter!(INVOKER_INVOCATION_TASK,
@@ -1033,10 +1047,12 @@ mod tests {
     use restate_invoker_api::{entry_enricher, ServiceHandle};
     use restate_schema_api::deployment::test_util::MockDeploymentMetadataRegistry;
     use restate_test_util::{check, let_assert};
+    use restate_types::errors::codes;
     use restate_types::identifiers::{LeaderEpoch, PartitionId};
     use restate_types::journal::enriched::EnrichedEntryHeader;
     use restate_types::journal::raw::RawEntry;
     use restate_types::retries::RetryPolicy;
+    use restate_types::service_protocol::ServiceProtocolVersion;
 
     use crate::invocation_task::InvocationTaskError;
     use crate::quota::InvokerConcurrencyQuota;
@@ -1209,6 +1225,46 @@ mod tests {
         tc.cancel_task(invoker_task_id).unwrap().await.unwrap();
     }
 
+    #[test(tokio::test)]
+    async fn run_until_ignores_task_cancellation() {
+        let node_env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let tc = node_env.tc;
+        let invoker_options = InvokerOptionsBuilder::default().build().unwrap();
+        let service = Service::new(
+            &invoker_options,
+            MockDeploymentMetadataRegistry::default(),
+            ServiceClient::from_options(
+                &ServiceClientOptions::default(),
+                restate_service_client::AssumeRoleCacheMode::None,
+            )
+            .unwrap(),
+            entry_enricher::test_util::MockEntryEnricher,
+        );
+
+        let (processors_alive, mut processors_stopped) = mpsc::channel::<()>(1);
+        let invoker_task_id = tc
+            .spawn(
+                TaskKind::SystemService,
+                "invoker",
+                None,
+                service.run_until(Constant::new(invoker_options), async move {
+                    let _ = processors_stopped.recv().await;
+                }),
+            )
+            .unwrap();
+
+        // cancelling the task must not stop the invoker as long as processors are alive
+        let mut join_handle = tc.cancel_task(invoker_task_id).unwrap();
+        check!(
+            tokio::time::timeout(Duration::from_millis(100), &mut join_handle)
+                .await
+                .is_err()
+        );
+
+        drop(processors_alive);
+        join_handle.await.unwrap();
+    }
+
     #[test(tokio::test)]
     async fn quota_allows_one_concurrent_invocation() {
         let invoker_options = InvokerOptionsBuilder::default()
@@ -1380,4 +1436,51 @@ mod tests {
         let_assert!(InvokerConcurrencyQuota::Limited { available_slots } = &service_inner.quota);
         assert_eq!(*available_slots, 2);
     }
+
+    #[test(tokio::test)]
+    async fn protocol_version_mismatch_fails_invocation() {
+        let invoker_options = InvokerOptionsBuilder::default()
+            .inactivity_timeout(Duration::ZERO.into())
+            .abort_timeout(Duration::ZERO.into())
+            .disable_eager_state(false)
+            .message_size_warning(NonZeroUsize::new(1024).unwrap())
+            .message_size_limit(None)
+            .build()
+            .unwrap();
+        let invocation_id = InvocationId::mock_random();
+
+        let (_, _status_tx, mut service_inner) =
+            ServiceInner::mock(|_, _, _, _, _, _, _| pending(), None);
+        let mut partition_rx = service_inner.register_mock_partition(EmptyStorageReader);
+
+        service_inner.handle_invoke(
+            &invoker_options,
+            MOCK_PARTITION,
+            invocation_id,
+            InvocationTarget::mock_virtual_object(),
+            InvokeInputJournal::NoCachedJournal,
+        );
+
+        // The endpoint answers with the content type of another service protocol version
+        service_inner
+            .handle_invocation_task_failed(
+                MOCK_PARTITION,
+                invocation_id,
+                InvocationTaskError::ServiceProtocolVersionMismatch(
+                    hyper::http::HeaderValue::from_static("application/vnd.restate.invocation.v2"),
+                    ServiceProtocolVersion::V1,
+                ),
+            )
+            .await;
+
+        // The invocation fails right away instead of being retried
+        let effect = partition_rx.recv().await.unwrap();
+        assert_eq!(effect.invocation_id, invocation_id);
+        let_assert!(EffectKind::Failed(err) = effect.kind);
+        assert_eq!(err.code(), codes::PROTOCOL_VIOLATION);
+        assert!(service_inner
+            .status_store
+            .resolve_invocation(MOCK_PARTITION, &invocation_id)
+            .is_none());
+    }
 }
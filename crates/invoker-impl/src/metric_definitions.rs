@@ -16,6 +16,8 @@ pub const INVOKER_ENQUEUE: &str = "restate.invoker.enqueue.total";
 pub const INVOKER_INVOCATION_TASK: &str = "restate.invoker.invocation_task.total";
 pub const INVOKER_AVAILABLE_SLOTS: &str = "restate.invoker.available_slots";
 pub const INVOKER_TASK_DURATION: &str = "restate.invoker.task_duration.seconds";
+pub const INVOKER_PROTOCOL_VERSION_MISMATCH: &str =
+    "restate.invoker.protocol_version_mismatch.total";
 
 pub const TASK_OP_STARTED: &str = "started";
 pub const TASK_OP_SUSPENDED: &str = "suspended";
@@ -45,5 +47,11 @@ pub(crate) fn describe_metrics() {
         INVOKER_TASK_DURATION,
         Unit::Seconds,
         "Time taken to complete an invoker task"
-    )
+    );
+
+    describe_counter!(
+        INVOKER_PROTOCOL_VERSION_MISMATCH,
+        Unit::Count,
+        "Number of invocation task failures caused by a service endpoint speaking an incompatible service protocol version"
+    );
 }
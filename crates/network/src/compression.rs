@@ -0,0 +1,166 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Instant;
+
+use bytes::Bytes;
+
+use restate_node_protocol::node::message::BinaryMessage;
+use restate_node_protocol::node::Compression;
+use restate_node_protocol::CodecError;
+use restate_types::config::{MessageCompression, NetworkingOptions};
+
+use crate::metric_definitions::{
+    MESSAGE_COMPRESSION_DURATION, MESSAGE_COMPRESSION_RATIO, MESSAGE_DECOMPRESSION_DURATION,
+};
+
+/// Compression of the messages sent over a connection, negotiated with the peer during the
+/// handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SendCompression {
+    compression: Compression,
+    threshold: usize,
+}
+
+impl SendCompression {
+    /// Returns the configured compression if the peer is able to decompress it.
+    pub fn negotiate(
+        options: &NetworkingOptions,
+        peer_accepts: impl Fn(Compression) -> bool,
+    ) -> Option<Self> {
+        let compression = match options.compression {
+            MessageCompression::None => return None,
+            MessageCompression::Zstd => Compression::Zstd,
+            MessageCompression::Lz4 => Compression::Lz4,
+        };
+
+        peer_accepts(compression).then_some(SendCompression {
+            compression,
+            threshold: options.compression_threshold.get(),
+        })
+    }
+
+    /// Compresses the payload of the message if it exceeds the compression threshold.
+    pub fn compress(&self, msg: &mut BinaryMessage) -> Result<(), CodecError> {
+        if msg.payload.len() < self.threshold {
+            return Ok(());
+        }
+
+        let compression_started = Instant::now();
+        let compressed = match self.compression {
+            Compression::None => return Ok(()),
+            Compression::Zstd => zstd::bulk::compress(&msg.payload, 0)
+                .map_err(|err| CodecError::Encode(err.into()))?,
+            Compression::Lz4 => lz4_flex::compress_prepend_size(&msg.payload),
+        };
+        MESSAGE_COMPRESSION_DURATION.record(compression_started.elapsed());
+        MESSAGE_COMPRESSION_RATIO.record(msg.payload.len() as f64 / compressed.len() as f64);
+
+        msg.payload = Bytes::from(compressed);
+        msg.set_compression(self.compression);
+        Ok(())
+    }
+}
+
+/// Decompresses the payload of the message according to the compression set by the sender.
+pub(crate) fn decompress(mut msg: BinaryMessage) -> Result<BinaryMessage, CodecError> {
+    let compression = Compression::try_from(msg.compression).map_err(|_| {
+        CodecError::Decode(format!("unknown message compression {}", msg.compression).into())
+    })?;
+
+    let decompression_started = Instant::now();
+    let decompressed = match compression {
+        Compression::None => return Ok(msg),
+        Compression::Zstd => zstd::stream::decode_all(msg.payload.as_ref())
+            .map_err(|err| CodecError::Decode(err.into()))?,
+        Compression::Lz4 => lz4_flex::decompress_size_prepended(&msg.payload)
+            .map_err(|err| CodecError::Decode(err.into()))?,
+    };
+    MESSAGE_DECOMPRESSION_DURATION.record(decompression_started.elapsed());
+
+    msg.payload = Bytes::from(decompressed);
+    msg.set_compression(Compression::None);
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroUsize;
+
+    use googletest::prelude::*;
+
+    fn options(compression: MessageCompression, threshold: usize) -> NetworkingOptions {
+        NetworkingOptions {
+            compression,
+            compression_threshold: NonZeroUsize::new(threshold).unwrap(),
+            ..NetworkingOptions::default()
+        }
+    }
+
+    fn message(payload: impl Into<Bytes>) -> BinaryMessage {
+        BinaryMessage {
+            target: 0,
+            payload: payload.into(),
+            compression: Compression::None.into(),
+        }
+    }
+
+    #[test]
+    fn compression_requires_peer_support() {
+        assert_that!(
+            SendCompression::negotiate(&options(MessageCompression::None, 1), |_| true),
+            none()
+        );
+        assert_that!(
+            SendCompression::negotiate(&options(MessageCompression::Zstd, 1), |c| c
+                == Compression::Lz4),
+            none()
+        );
+        assert_that!(
+            SendCompression::negotiate(&options(MessageCompression::Lz4, 1), |c| c
+                == Compression::Lz4),
+            some(anything())
+        );
+    }
+
+    #[test]
+    fn compressed_messages_roundtrip() -> Result<()> {
+        let payload = Bytes::from("restate".repeat(1024));
+
+        for compression in [MessageCompression::Zstd, MessageCompression::Lz4] {
+            let send_compression =
+                SendCompression::negotiate(&options(compression, 1024), |_| true).unwrap();
+
+            let mut msg = message(payload.clone());
+            send_compression.compress(&mut msg)?;
+            assert_that!(msg.compression(), not(eq(Compression::None)));
+            assert_that!(msg.payload.len(), lt(payload.len()));
+
+            let msg = decompress(msg)?;
+            assert_that!(msg.compression(), eq(Compression::None));
+            assert_that!(msg.payload, eq(payload.clone()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn small_messages_are_not_compressed() -> Result<()> {
+        let send_compression =
+            SendCompression::negotiate(&options(MessageCompression::Zstd, 1024), |_| true).unwrap();
+
+        let mut msg = message("small");
+        send_compression.compress(&mut msg)?;
+        assert_that!(msg.compression(), eq(Compression::None));
+        assert_that!(msg.payload, eq(Bytes::from("small")));
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@ use restate_node_protocol::node::Header;
 use restate_node_protocol::node::Message;
 use restate_types::GenerationalNodeId;
 
+use crate::compression::SendCompression;
 use crate::metric_definitions::CONNECTION_SEND_DURATION;
 use crate::metric_definitions::MESSAGE_SENT;
 
@@ -40,6 +41,8 @@ pub(crate) struct Connection {
     pub(crate) cid: u64,
     pub(crate) peer: GenerationalNodeId,
     pub(crate) protocol_version: ProtocolVersion,
+    /// Compression of outgoing messages, if negotiated with the peer
+    pub(crate) compression: Option<SendCompression>,
     pub(crate) sender: mpsc::Sender<Message>,
     pub(crate) created: std::time::Instant,
 }
@@ -48,12 +51,14 @@ impl Connection {
     pub fn new(
         peer: GenerationalNodeId,
         protocol_version: ProtocolVersion,
+        compression: Option<SendCompression>,
         sender: mpsc::Sender<Message>,
     ) -> Self {
         Self {
             cid: rand::random(),
             peer,
             protocol_version,
+            compression,
             sender,
             created: std::time::Instant::now(),
         }
@@ -80,6 +85,7 @@ impl Connection {
             peer: self.peer,
             connection: Arc::downgrade(self),
             protocol_version: self.protocol_version,
+            compression: self.compression,
         }
     }
 }
@@ -97,6 +103,7 @@ pub struct ConnectionSender {
     peer: GenerationalNodeId,
     connection: Weak<Connection>,
     protocol_version: ProtocolVersion,
+    compression: Option<SendCompression>,
 }
 
 impl ConnectionSender {
@@ -121,7 +128,10 @@ impl ConnectionSender {
     {
         let send_start = Instant::now();
         let header = Header::new(metadata().nodes_config_version());
-        let body = serialize_message(message, self.protocol_version)?;
+        let mut body = serialize_message(message, self.protocol_version)?;
+        if let (Some(compression), message::Body::Encoded(msg)) = (&self.compression, &mut body) {
+            compression.compress(msg)?;
+        }
         let res = self
             .connection
             .upgrade()
@@ -24,19 +24,23 @@ use tracing::{debug, info, trace, warn, Instrument, Span};
 
 use restate_core::metadata;
 use restate_core::{cancellation_watcher, current_task_id, task_center, TaskId, TaskKind};
+use restate_grpc_util::auth::BearerTokenInterceptor;
 use restate_grpc_util::create_grpc_channel_from_advertised_address;
+use restate_node_protocol::common::TargetName;
 use restate_node_protocol::node::message::{self, ConnectionControl};
 use restate_node_protocol::node::{Header, Hello, Message, Welcome};
 use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
+use restate_types::config::{Configuration, UnknownMessageHandling};
 use restate_types::net::AdvertisedAddress;
 use restate_types::{GenerationalNodeId, NodeId, PlainNodeId};
 
 use super::connection::{Connection, ConnectionSender};
 use super::handshake::{negotiate_protocol_version, wait_for_hello, wait_for_welcome};
+use crate::compression::{decompress, SendCompression};
 use crate::error::{NetworkError, ProtocolError};
 use crate::metric_definitions::{
     self, CONNECTION_DROPPED, INCOMING_CONNECTION, MESSAGE_PROCESSING_DURATION, MESSAGE_RECEIVED,
-    ONGOING_DRAIN, OUTGOING_CONNECTION,
+    ONGOING_DRAIN, OUTGOING_CONNECTION, UNKNOWN_MESSAGE_DROPPED,
 };
 
 // todo: make this configurable
@@ -209,7 +213,10 @@ impl ConnectionManager {
             .expect("channel accept Welcome message");
 
         INCOMING_CONNECTION.increment(1);
-        let connection = Connection::new(peer_node_id, selected_protocol_version, tx);
+        let compression = SendCompression::negotiate(&Configuration::pinned().networking, |c| {
+            hello.accepts_compression(c)
+        });
+        let connection = Connection::new(peer_node_id, selected_protocol_version, compression, tx);
         // Register the connection.
         let _ = self.start_connection_reactor(connection, incoming)?;
         // For uniformity with outbound connections, we map all responses to Ok, we never rely on
@@ -272,7 +279,13 @@ impl ConnectionManager {
             }
         };
 
-        self.connect_with_channel(node_id, channel).await
+        // the token is read on every new connection so that a rotated token is picked up
+        let interceptor =
+            BearerTokenInterceptor::from_options(Configuration::pinned().common.auth.as_ref())
+                .map_err(NetworkError::Credentials)?;
+
+        self.connect_with_channel(node_id, channel, interceptor)
+            .await
     }
 
     // Left here for future use. This allows the node to connect to itself and bypass the
@@ -286,6 +299,7 @@ impl ConnectionManager {
         let connection = Connection::new(
             node_id,
             restate_node_protocol::common::CURRENT_PROTOCOL_VERSION,
+            None,
             tx,
         );
 
@@ -300,10 +314,12 @@ impl ConnectionManager {
         &self,
         node_id: GenerationalNodeId,
         channel: Channel,
+        interceptor: BearerTokenInterceptor,
     ) -> Result<Arc<Connection>, NetworkError> {
         let metadata = metadata();
 
-        let mut client = NodeSvcClient::new(channel);
+        // the other node authenticates the connection like any other call to its gRPC services
+        let mut client = NodeSvcClient::with_interceptor(channel, interceptor);
         let nodes_config = metadata.nodes_config();
         let cluster_name = nodes_config.cluster_name();
 
@@ -349,12 +365,17 @@ impl ConnectionManager {
             .into());
         }
 
+        let compression = SendCompression::negotiate(&Configuration::pinned().networking, |c| {
+            welcome.accepts_compression(c)
+        });
+
         OUTGOING_CONNECTION.increment(1);
         let connection = Connection::new(
             peer_node_id
                 .as_generational()
                 .expect("must be generational id"),
             protocol_version,
+            compression,
             tx,
         );
 
@@ -448,6 +469,7 @@ where
         "task_id",
         tracing::field::display(current_task_id().unwrap()),
     );
+    let unknown_message_handling = Configuration::pinned().networking.unknown_message_handling;
     let mut cancellation = std::pin::pin!(cancellation_watcher());
     // Receive loop
     loop {
@@ -481,8 +503,17 @@ where
             // todo: if header contains newer config or metadata versions, notify metadata().
         };
 
-        // body are not allowed to be empty.
+        // body are not allowed to be empty. Note that a peer running a newer version might have
+        // sent a message type that we don't know about, which is decoded as an empty body.
         let Some(body) = msg.body else {
+            if unknown_message_handling == UnknownMessageHandling::Drop {
+                UNKNOWN_MESSAGE_DROPPED.increment(1);
+                debug!(
+                    "Dropping message of unknown type received from peer {}",
+                    connection.peer
+                );
+                continue;
+            }
             connection
                 .send_control_frame(ConnectionControl::codec_error("Body is missing on message"));
             break;
@@ -507,7 +538,23 @@ where
             break;
         }
 
-        match try_unwrap_binary_message(body, connection.protocol_version) {
+        match try_unwrap_binary_message(body, connection.protocol_version).and_then(decompress) {
+            Ok(msg) if msg.target() == TargetName::Unknown => {
+                MESSAGE_PROCESSING_DURATION.record(processing_started.elapsed());
+                if unknown_message_handling == UnknownMessageHandling::Drop {
+                    UNKNOWN_MESSAGE_DROPPED.increment(1);
+                    debug!(
+                        "Dropping message for unknown target {} received from peer {}",
+                        msg.target, connection.peer
+                    );
+                    continue;
+                }
+                connection.send_control_frame(ConnectionControl::codec_error(format!(
+                    "Unknown message target {}",
+                    msg.target
+                )));
+                break;
+            }
             Ok(msg) => {
                 if let Err(e) = router
                     .call(
@@ -549,7 +596,8 @@ where
     while let Some(Ok(msg)) = incoming.next().await {
         if let Some(body) = msg.body {
             // we ignore non-deserializable messages (serde errors, or control signals in drain)
-            if let Ok(msg) = try_unwrap_binary_message(body, protocol_version) {
+            if let Ok(msg) = try_unwrap_binary_message(body, protocol_version).and_then(decompress)
+            {
                 drain_counter += 1;
                 if let Err(e) = router
                     .call(peer_node_id, connection_id, protocol_version, msg)
@@ -610,7 +658,7 @@ mod tests {
     use restate_node_protocol::{
         common::ProtocolVersion, CURRENT_PROTOCOL_VERSION, MIN_SUPPORTED_PROTOCOL_VERSION,
     };
-    use restate_test_util::assert_eq;
+    use restate_test_util::{assert_eq, assert_ne};
     use restate_types::nodes_config::NodesConfigError;
 
     // Test handshake with a client
@@ -695,6 +743,7 @@ mod tests {
                     max_protocol_version: ProtocolVersion::Unknown.into(),
                     my_node_id: Some(my_node_id.into()),
                     cluster_name: metadata.nodes_config().cluster_name().to_owned(),
+                    accepted_compressions: vec![],
                 };
                 let hello = Message::new(Header::new(metadata.nodes_config_version()), hello);
                 tx.send(Ok(hello))
@@ -720,6 +769,7 @@ mod tests {
                     max_protocol_version: CURRENT_PROTOCOL_VERSION.into(),
                     my_node_id: Some(my_node_id.into()),
                     cluster_name: "Random-cluster".to_owned(),
+                    accepted_compressions: vec![],
                 };
                 let hello = Message::new(Header::new(metadata.nodes_config_version()), hello);
                 tx.send(Ok(hello)).await?;
@@ -742,6 +792,65 @@ mod tests {
             .await
     }
 
+    #[tokio::test]
+    async fn test_unknown_message_is_dropped() -> Result<()> {
+        let test_setup = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        test_setup
+            .tc
+            .run_in_scope("test", None, async {
+                let metadata = restate_core::metadata();
+                let (tx, rx) = mpsc::channel(3);
+                let connections = ConnectionManager::default();
+
+                let hello = Hello::new(
+                    metadata.my_node_id(),
+                    metadata.nodes_config().cluster_name().to_owned(),
+                );
+                let hello = Message::new(Header::new(metadata.nodes_config_version()), hello);
+                tx.send(Ok(hello)).await?;
+
+                let incoming = ReceiverStream::new(rx);
+                let mut output_stream = connections
+                    .accept_incoming_connection(incoming)
+                    .await
+                    .expect("handshake");
+                let msg = output_stream
+                    .next()
+                    .await
+                    .expect("welcome message")
+                    .expect("ok");
+                assert!(matches!(msg.body, Some(message::Body::Welcome(_))));
+
+                // a message type this node doesn't know is decoded with an empty body
+                tx.send(Ok(Message {
+                    header: None,
+                    body: None,
+                }))
+                .await?;
+                // a message for a target this node doesn't know
+                tx.send(Ok(Message {
+                    header: None,
+                    body: Some(message::Body::Encoded(message::BinaryMessage {
+                        target: i32::MAX,
+                        payload: Default::default(),
+                        compression: Default::default(),
+                    })),
+                }))
+                .await?;
+                drop(tx);
+
+                // the connection must not be terminated with a codec error
+                while let Some(msg) = output_stream.next().await {
+                    let msg = msg.expect("ok");
+                    if let Some(message::Body::ConnectionControl(ctrl)) = msg.body {
+                        assert_ne!(ctrl.signal(), message::Signal::CodecError);
+                    }
+                }
+                Ok(())
+            })
+            .await
+    }
+
     #[tokio::test]
     async fn test_node_generation() -> Result<()> {
         let test_setup = TestCoreEnv::create_with_mock_nodes_config(1, 2).await;
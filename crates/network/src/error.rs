@@ -20,7 +20,9 @@ pub enum NetworkError {
     #[error("operation aborted, node is shutting down")]
     Shutdown(#[from] ShutdownError),
     #[error("node {0} address is bad: {1}")]
-    BadNodeAddress(NodeId, http::Error),
+    BadNodeAddress(NodeId, restate_grpc_util::ChannelError),
+    #[error("cannot load the credentials for connecting to other nodes: {0}")]
+    Credentials(std::io::Error),
     #[error("timeout: {0}")]
     Timeout(&'static str),
     #[error("protocol error: {0}")]
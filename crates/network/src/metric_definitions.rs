@@ -19,10 +19,16 @@ const NETWORK_CONNECTION_DROPPED: &str = "restate.network.connection_dropped.tot
 const NETWORK_ONGOING_DRAINS: &str = "restate.network.ongoing_drains";
 const NETWORK_MESSAGE_SENT: &str = "restate.network.message_sent.total";
 const NETWORK_MESSAGE_RECEIVED: &str = "restate.network.message_received.total";
+const NETWORK_UNKNOWN_MESSAGE_DROPPED: &str = "restate.network.unknown_message_dropped.total";
 
 const NETWORK_CONNECTION_SEND_DURATION: &str = "restate.network.connection_send_duration.seconds";
 const NETWORK_MESSAGE_PROCESSING_DURATION: &str =
     "restate.network.message_processing_duration.seconds";
+const NETWORK_MESSAGE_COMPRESSION_RATIO: &str = "restate.network.message_compression_ratio";
+const NETWORK_MESSAGE_COMPRESSION_DURATION: &str =
+    "restate.network.message_compression_duration.seconds";
+const NETWORK_MESSAGE_DECOMPRESSION_DURATION: &str =
+    "restate.network.message_decompression_duration.seconds";
 
 pub static INCOMING_CONNECTION: Lazy<Counter> =
     Lazy::new(|| counter!(NETWORK_CONNECTION_CREATED, "direction" => "incoming"));
@@ -35,6 +41,8 @@ pub static ONGOING_DRAIN: Lazy<Gauge> = Lazy::new(|| gauge!(NETWORK_ONGOING_DRAI
 
 pub static MESSAGE_SENT: Lazy<Counter> = Lazy::new(|| counter!(NETWORK_MESSAGE_SENT));
 pub static MESSAGE_RECEIVED: Lazy<Counter> = Lazy::new(|| counter!(NETWORK_MESSAGE_RECEIVED));
+pub static UNKNOWN_MESSAGE_DROPPED: Lazy<Counter> =
+    Lazy::new(|| counter!(NETWORK_UNKNOWN_MESSAGE_DROPPED));
 
 pub static CONNECTION_SEND_DURATION: Lazy<Histogram> =
     Lazy::new(|| histogram!(NETWORK_CONNECTION_SEND_DURATION));
@@ -42,6 +50,15 @@ pub static CONNECTION_SEND_DURATION: Lazy<Histogram> =
 pub static MESSAGE_PROCESSING_DURATION: Lazy<Histogram> =
     Lazy::new(|| histogram!(NETWORK_MESSAGE_PROCESSING_DURATION));
 
+pub static MESSAGE_COMPRESSION_RATIO: Lazy<Histogram> =
+    Lazy::new(|| histogram!(NETWORK_MESSAGE_COMPRESSION_RATIO));
+
+pub static MESSAGE_COMPRESSION_DURATION: Lazy<Histogram> =
+    Lazy::new(|| histogram!(NETWORK_MESSAGE_COMPRESSION_DURATION));
+
+pub static MESSAGE_DECOMPRESSION_DURATION: Lazy<Histogram> =
+    Lazy::new(|| histogram!(NETWORK_MESSAGE_DECOMPRESSION_DURATION));
+
 pub fn describe_metrics() {
     describe_counter!(
         NETWORK_CONNECTION_CREATED,
@@ -67,6 +84,12 @@ pub fn describe_metrics() {
         "Number of messages received"
     );
 
+    describe_counter!(
+        NETWORK_UNKNOWN_MESSAGE_DROPPED,
+        Unit::Count,
+        "Number of received messages dropped because their type is unknown to this node"
+    );
+
     describe_histogram!(
         NETWORK_CONNECTION_SEND_DURATION,
         Unit::Seconds,
@@ -77,4 +100,19 @@ pub fn describe_metrics() {
         Unit::Seconds,
         "Latency of deserializing and processing incoming messages"
     );
+    describe_histogram!(
+        NETWORK_MESSAGE_COMPRESSION_RATIO,
+        Unit::Count,
+        "Ratio between the uncompressed and the compressed size of compressed outgoing messages"
+    );
+    describe_histogram!(
+        NETWORK_MESSAGE_COMPRESSION_DURATION,
+        Unit::Seconds,
+        "Time spent compressing outgoing messages"
+    );
+    describe_histogram!(
+        NETWORK_MESSAGE_DECOMPRESSION_DURATION,
+        Unit::Seconds,
+        "Time spent decompressing incoming messages"
+    );
 }
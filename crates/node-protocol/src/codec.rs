@@ -19,6 +19,7 @@ use crate::common::ProtocolVersion;
 use crate::common::TargetName;
 use crate::node::message;
 use crate::node::message::BinaryMessage;
+use crate::node::Compression;
 use crate::CodecError;
 
 pub trait Targeted {
@@ -169,6 +170,7 @@ pub fn serialize_message<M: WireEncode + Targeted>(
     Ok(message::Body::Encoded(BinaryMessage {
         target,
         payload: payload.freeze(),
+        compression: Compression::None.into(),
     }))
 }
 
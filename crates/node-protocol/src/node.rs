@@ -19,6 +19,9 @@ include!(concat!(env!("OUT_DIR"), "/dev.restate.node.rs"));
 pub const FILE_DESCRIPTOR_SET: &[u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/node_descriptor.bin"));
 
+/// Compression algorithms this node can decompress, advertised to peers during the handshake.
+pub const SUPPORTED_COMPRESSIONS: &[Compression] = &[Compression::Zstd, Compression::Lz4];
+
 impl Hello {
     pub fn new(my_node_id: GenerationalNodeId, cluster_name: String) -> Self {
         Self {
@@ -26,8 +29,14 @@ impl Hello {
             max_protocol_version: CURRENT_PROTOCOL_VERSION.into(),
             my_node_id: Some(my_node_id.into()),
             cluster_name,
+            accepted_compressions: SUPPORTED_COMPRESSIONS.iter().map(|c| *c as i32).collect(),
         }
     }
+
+    /// Whether the peer can decompress messages compressed with the given algorithm.
+    pub fn accepts_compression(&self, compression: Compression) -> bool {
+        self.accepted_compressions().any(|c| c == compression)
+    }
 }
 
 impl Header {
@@ -43,8 +52,14 @@ impl Welcome {
         Self {
             my_node_id: Some(my_node_id.into()),
             protocol_version: protocol_version.into(),
+            accepted_compressions: SUPPORTED_COMPRESSIONS.iter().map(|c| *c as i32).collect(),
         }
     }
+
+    /// Whether the peer can decompress messages compressed with the given algorithm.
+    pub fn accepts_compression(&self, compression: Compression) -> bool {
+        self.accepted_compressions().any(|c| c == compression)
+    }
 }
 
 impl Message {
@@ -0,0 +1,39 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Build information of the node, mirroring the build information of the server binary.
+
+use restate_node_services::node_svc::BuildInfoResponse;
+
+pub const RESTATE_NODE_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const RESTATE_NODE_BUILD_TIME: &str = env!("VERGEN_BUILD_TIMESTAMP");
+pub const RESTATE_NODE_COMMIT_SHA: &str = env!("VERGEN_GIT_SHA");
+
+pub fn build_info() -> BuildInfoResponse {
+    BuildInfoResponse {
+        version: RESTATE_NODE_VERSION.to_owned(),
+        git_commit_sha: RESTATE_NODE_COMMIT_SHA.to_owned(),
+        build_timestamp: RESTATE_NODE_BUILD_TIME.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_info_is_set() {
+        let build_info = build_info();
+
+        assert!(!build_info.version.is_empty());
+        assert!(!build_info.git_commit_sha.is_empty());
+        assert!(!build_info.build_timestamp.is_empty());
+    }
+}
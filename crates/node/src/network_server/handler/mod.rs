@@ -14,14 +14,17 @@ pub mod node;
 use std::fmt::Write;
 
 use axum::extract::State;
+use axum::Json;
 use metrics_exporter_prometheus::formatting;
 use rocksdb::statistics::{Histogram, Ticker};
 
 use restate_rocksdb::{CfName, RocksDbManager};
+use restate_types::config::Configuration;
+use restate_types::nodes_config::Role;
 
 use crate::network_server::prometheus_helpers::{
-    format_rocksdb_histogram_for_prometheus, format_rocksdb_property_for_prometheus,
-    format_rocksdb_stat_ticker_for_prometheus, MetricUnit,
+    convert_to_openmetrics, format_rocksdb_histogram_for_prometheus,
+    format_rocksdb_property_for_prometheus, format_rocksdb_stat_ticker_for_prometheus, MetricUnit,
 };
 use crate::network_server::state::NodeCtrlHandlerState;
 
@@ -177,8 +180,99 @@ const ROCKSDB_CF_PROPERTIES: &[(&str, MetricUnit)] = &[
     ("rocksdb.num-files-at-level6", MetricUnit::Count),
 ];
 
+const PROMETHEUS_TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const OPENMETRICS_MEDIA_TYPE: &str = "application/openmetrics-text";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Exposition format of the metrics, negotiated via the `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpositionFormat {
+    /// Prometheus text format. This is the default.
+    PrometheusText,
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    fn from_headers(headers: &http::HeaderMap) -> Self {
+        let accepts_openmetrics = headers
+            .get_all(http::header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_type| media_type.split(';').next())
+            .any(|media_type| media_type.trim() == OPENMETRICS_MEDIA_TYPE);
+
+        if accepts_openmetrics {
+            ExpositionFormat::OpenMetrics
+        } else {
+            ExpositionFormat::PrometheusText
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExpositionFormat::PrometheusText => PROMETHEUS_TEXT_CONTENT_TYPE,
+            ExpositionFormat::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+        }
+    }
+}
+
+/// Compile time features and runtime flags which determine the operational mode of the node.
+#[derive(Debug, serde::Serialize)]
+pub struct NodeFeatures {
+    build: BuildFeatures,
+    runtime: RuntimeFlags,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BuildFeatures {
+    debug_assertions: bool,
+    options_schema: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct RuntimeFlags {
+    roles: Vec<Role>,
+    allow_bootstrap: bool,
+    prometheus_enabled: bool,
+    /// Unset if the node does not run the worker role
+    quiesced: Option<bool>,
+}
+
 // -- Direct HTTP Handlers --
-pub async fn render_metrics(State(state): State<NodeCtrlHandlerState>) -> String {
+pub async fn render_features(State(state): State<NodeCtrlHandlerState>) -> Json<NodeFeatures> {
+    let config = Configuration::pinned();
+
+    Json(NodeFeatures {
+        build: BuildFeatures {
+            debug_assertions: cfg!(debug_assertions),
+            options_schema: cfg!(feature = "options_schema"),
+        },
+        runtime: RuntimeFlags {
+            roles: config.common.roles.iter().collect(),
+            allow_bootstrap: config.common.allow_bootstrap,
+            prometheus_enabled: state.prometheus_handle.is_some(),
+            quiesced: state
+                .quiesce_handle
+                .as_ref()
+                .map(|quiesce_handle| quiesce_handle.is_quiesced()),
+        },
+    })
+}
+
+pub async fn render_metrics(
+    State(state): State<NodeCtrlHandlerState>,
+    headers: http::HeaderMap,
+) -> Result<([(http::HeaderName, &'static str); 1], String), (http::StatusCode, String)> {
+    let format = ExpositionFormat::from_headers(&headers);
+
+    if let Some(err) = state.prometheus_install_error {
+        return Err((
+            http::StatusCode::SERVICE_UNAVAILABLE,
+            format!("Metrics are not available: {err}"),
+        ));
+    }
+
     let default_cf = CfName::new("default");
     let mut out = String::new();
 
@@ -302,5 +396,9 @@ pub async fn render_metrics(State(state): State<NodeCtrlHandlerState>) -> String
             }
         }
     }
-    out
+
+    if format == ExpositionFormat::OpenMetrics {
+        out = convert_to_openmetrics(&out);
+    }
+    Ok(([(http::header::CONTENT_TYPE, format.content_type())], out))
 }
@@ -8,26 +8,44 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use arrow_flight::encode::FlightDataEncoderBuilder;
 use arrow_flight::error::FlightError;
+use datafusion::common::ScalarValue;
 use futures::stream::BoxStream;
 use futures::TryStreamExt;
 use restate_core::{metadata, TaskCenter};
+use restate_invoker_api::StatusHandle;
 use restate_network::error::ProtocolError;
 use tokio_stream::StreamExt;
 use tonic::{Request, Response, Status, Streaming};
+use tracing::info;
 
+use crate::build_info;
 use crate::network_server::WorkerDependencies;
 use restate_network::ConnectionManager;
+use restate_node_protocol::metadata::MetadataKind;
 use restate_node_protocol::node::Message;
 use restate_node_services::node_svc::node_svc_server::NodeSvc;
-use restate_node_services::node_svc::{IdentResponse, NodeStatus};
-use restate_node_services::node_svc::{StorageQueryRequest, StorageQueryResponse};
+use restate_node_services::node_svc::{query_parameter, StorageQueryRequest, StorageQueryResponse};
+use restate_node_services::node_svc::{
+    BuildInfoResponse, IdentResponse, InvocationRetryStateRequest, InvocationRetryStateResponse,
+    LeadershipSnapshotResponse, NodeStatus, PartitionLeadership, SetLogFilterRequest,
+    SetLogFilterResponse, SetQuiescedRequest, StepDownRequest, SyncSchemaRequest,
+    SyncSchemaResponse,
+};
+use restate_storage_query_datafusion::context::is_memory_limit_exceeded;
+use restate_tracing_instrumentation::{LogFilterHandle, SetLogFilterError};
+use restate_types::config::Configuration;
+use restate_types::identifiers::{InvocationId, PartitionId, WithPartitionKey};
+use restate_types::Version;
 
 pub struct NodeSvcHandler {
     task_center: TaskCenter,
     worker: Option<WorkerDependencies>,
     connections: ConnectionManager,
+    log_filter_handle: Option<LogFilterHandle>,
 }
 
 impl NodeSvcHandler {
@@ -35,11 +53,13 @@ impl NodeSvcHandler {
         task_center: TaskCenter,
         worker: Option<WorkerDependencies>,
         connections: ConnectionManager,
+        log_filter_handle: Option<LogFilterHandle>,
     ) -> Self {
         Self {
             task_center,
             worker,
             connections,
+            log_filter_handle,
         }
     }
 }
@@ -48,9 +68,17 @@ impl NodeSvcHandler {
 impl NodeSvc for NodeSvcHandler {
     async fn get_ident(&self, _request: Request<()>) -> Result<Response<IdentResponse>, Status> {
         // STUB IMPLEMENTATION
+        let status = match self.worker {
+            // reported as soon as the shutdown begins, so that the node is taken out of rotation
+            // while its subsystems are still draining. Not SHUTTING_DOWN, because in-flight
+            // requests are still being served.
+            _ if self.task_center.is_shutdown_requested() => NodeStatus::Draining,
+            Some(ref worker) if worker.quiesce_handle.is_quiesced() => NodeStatus::Quiesced,
+            _ => NodeStatus::Alive,
+        };
         self.task_center.run_in_scope_sync("get_ident", None, || {
             Ok(Response::new(IdentResponse {
-                status: NodeStatus::Alive.into(),
+                status: status.into(),
                 node_id: Some(metadata().my_node_id().into()),
             }))
         })
@@ -65,31 +93,234 @@ impl NodeSvc for NodeSvcHandler {
         let Some(ref worker) = self.worker else {
             return Err(Status::failed_precondition("Not a worker node"));
         };
-        let query = request.into_inner().query;
+        let StorageQueryRequest { query, params } = request.into_inner();
+        let params = params
+            .into_iter()
+            .map(|param| match param.value {
+                Some(query_parameter::Value::Bool(value)) => Ok(ScalarValue::from(value)),
+                Some(query_parameter::Value::Int64(value)) => Ok(ScalarValue::from(value)),
+                Some(query_parameter::Value::Uint64(value)) => Ok(ScalarValue::from(value)),
+                Some(query_parameter::Value::Float64(value)) => Ok(ScalarValue::from(value)),
+                Some(query_parameter::Value::String(value)) => Ok(ScalarValue::from(value)),
+                None => Err(Status::invalid_argument("query parameter without a value")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         let record_stream = self
             .task_center
             .run_in_scope("query-storage", None, async move {
-                worker.query_context.execute(&query).await.map_err(|err| {
-                    Status::internal(format!("failed executing the query '{}': {}", query, err))
-                })
+                worker
+                    .query_context
+                    .execute_with_params(&query, params)
+                    .await
+                    .map_err(|err| {
+                        if is_memory_limit_exceeded(&err) {
+                            Status::resource_exhausted(format!(
+                                "query '{}' exceeded the memory limit: {}",
+                                query, err
+                            ))
+                        } else {
+                            Status::internal(format!(
+                                "failed executing the query '{}': {}",
+                                query, err
+                            ))
+                        }
+                    })
             })
             .await?;
 
         let schema = record_stream.schema();
-        let response_stream =
-            FlightDataEncoderBuilder::new()
-                // CLI is expecting schema information
-                .with_schema(schema)
-                .build(record_stream.map_err(|err| {
+        let response_stream = FlightDataEncoderBuilder::new()
+            // CLI is expecting schema information
+            .with_schema(schema)
+            .build(record_stream.map_err(|err| {
+                if is_memory_limit_exceeded(&err) {
+                    FlightError::Tonic(Status::resource_exhausted(format!(
+                        "query exceeded the memory limit: {}",
+                        err
+                    )))
+                } else {
                     FlightError::from(datafusion::arrow::error::ArrowError::from(err))
+                }
+            }))
+            .map_ok(|flight_data| StorageQueryResponse {
+                header: flight_data.data_header,
+                data: flight_data.data_body,
+            })
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(response_stream)))
+    }
+
+    async fn get_build_info(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<BuildInfoResponse>, Status> {
+        Ok(Response::new(build_info::build_info()))
+    }
+
+    async fn set_log_filter(
+        &self,
+        request: Request<SetLogFilterRequest>,
+    ) -> Result<Response<SetLogFilterResponse>, Status> {
+        let Some(ref log_filter_handle) = self.log_filter_handle else {
+            return Err(Status::failed_precondition(
+                "Log filter can't be changed on this node",
+            ));
+        };
+        let filter = request.into_inner().filter;
+
+        let previous_filter =
+            log_filter_handle
+                .set_log_filter(&filter)
+                .map_err(|err| match err {
+                    SetLogFilterError::InvalidFilter(err) => Status::invalid_argument(format!(
+                        "Invalid log filter '{}': {}",
+                        filter, err
+                    )),
+                    SetLogFilterError::Reload(err) => Status::internal(err.to_string()),
+                })?;
+        info!(%previous_filter, "Changed log filter to '{}'", filter);
+
+        Ok(Response::new(SetLogFilterResponse { previous_filter }))
+    }
+
+    async fn sync_schema(
+        &self,
+        request: Request<SyncSchemaRequest>,
+    ) -> Result<Response<SyncSchemaResponse>, Status> {
+        let min_version = request
+            .into_inner()
+            .min_version
+            .map(Version::from)
+            .unwrap_or(Version::INVALID);
+
+        self.task_center
+            .run_in_scope("sync-schema", None, async move {
+                let metadata = metadata();
+                if metadata.schema_version() < min_version {
+                    metadata
+                        .sync(MetadataKind::Schema)
+                        .await
+                        .map_err(|err| Status::unavailable(err.to_string()))?;
+                }
+
+                Ok(Response::new(SyncSchemaResponse {
+                    schema_version: Some(metadata.schema_version().into()),
                 }))
-                .map_ok(|flight_data| StorageQueryResponse {
-                    header: flight_data.data_header,
-                    data: flight_data.data_body,
+            })
+            .await
+    }
+
+    async fn get_leadership_snapshot(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<LeadershipSnapshotResponse>, Status> {
+        let Some(ref worker) = self.worker else {
+            return Err(Status::failed_precondition("Not a worker node"));
+        };
+
+        let snapshot = worker
+            .processors_manager_handle
+            .leadership_snapshot()
+            .await
+            .map_err(|err| Status::unavailable(err.to_string()))?;
+
+        Ok(Response::new(LeadershipSnapshotResponse {
+            partitions: snapshot
+                .into_iter()
+                .map(|(partition_id, leadership)| PartitionLeadership {
+                    partition_id: partition_id.into(),
+                    is_leader: leadership.is_leader,
+                    leader_epoch: leadership.leader_epoch.map(Into::into),
                 })
-                .map_err(Status::from);
-        Ok(Response::new(Box::pin(response_stream)))
+                .collect(),
+        }))
+    }
+
+    async fn step_down(&self, request: Request<StepDownRequest>) -> Result<Response<()>, Status> {
+        let Some(ref worker) = self.worker else {
+            return Err(Status::failed_precondition("Not a worker node"));
+        };
+        let partition_id = PartitionId::from(request.into_inner().partition_id);
+
+        let snapshot = worker
+            .processors_manager_handle
+            .leadership_snapshot()
+            .await
+            .map_err(|err| Status::unavailable(err.to_string()))?;
+        if !snapshot.contains_key(&partition_id) {
+            return Err(Status::not_found(format!(
+                "partition processor '{}' is not running on this node",
+                partition_id
+            )));
+        }
+
+        worker
+            .processors_manager_handle
+            .step_down(partition_id)
+            .await
+            .map_err(|err| Status::unavailable(err.to_string()))?;
+        info!(%partition_id, "Requested partition processor to step down");
+
+        Ok(Response::new(()))
+    }
+
+    async fn set_quiesced(
+        &self,
+        request: Request<SetQuiescedRequest>,
+    ) -> Result<Response<()>, Status> {
+        let Some(ref worker) = self.worker else {
+            return Err(Status::failed_precondition("Not a worker node"));
+        };
+
+        let result = if request.into_inner().quiesced {
+            worker.quiesce_handle.quiesce().await
+        } else {
+            worker.quiesce_handle.unquiesce().await
+        };
+        result.map_err(|err| Status::unavailable(err.to_string()))?;
+
+        Ok(Response::new(()))
+    }
+
+    async fn get_invocation_retry_state(
+        &self,
+        request: Request<InvocationRetryStateRequest>,
+    ) -> Result<Response<InvocationRetryStateResponse>, Status> {
+        let Some(ref worker) = self.worker else {
+            return Err(Status::failed_precondition("Not a worker node"));
+        };
+        let invocation_id: InvocationId =
+            request.into_inner().invocation_id.parse().map_err(|err| {
+                Status::invalid_argument(format!("invalid invocation id: {}", err))
+            })?;
+
+        let partition_key = invocation_id.partition_key();
+        let report = worker
+            .invoker_status_reader
+            .read_status(partition_key..=partition_key)
+            .await
+            .find(|report| report.invocation_id() == &invocation_id)
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "invocation '{}' is not tracked by the invoker",
+                    invocation_id
+                ))
+            })?;
+
+        let retry_policy = serde_json::to_vec(&Configuration::pinned().worker.invoker.retry_policy)
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(InvocationRetryStateResponse {
+            retry_count: u32::try_from(report.retry_count()).unwrap_or(u32::MAX),
+            in_flight: report.in_flight(),
+            last_start_at: unix_millis(report.last_start_at()),
+            next_retry_at: report.next_retry_at().map(unix_millis),
+            last_failure: report
+                .last_retry_attempt_failure()
+                .map(|failure| failure.err.to_string()),
+            retry_policy: retry_policy.into(),
+        }))
     }
 
     type CreateConnectionStream = BoxStream<'static, Result<Message, Status>>;
@@ -120,3 +351,35 @@ impl NodeSvc for NodeSvcHandler {
         Ok(Response::new(output_stream))
     }
 }
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_core::TestCoreEnv;
+
+    async fn status(handler: &NodeSvcHandler) -> NodeStatus {
+        handler
+            .get_ident(Request::new(()))
+            .await
+            .unwrap()
+            .into_inner()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn draining_node_reports_draining_status() {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let handler = NodeSvcHandler::new(env.tc.clone(), None, ConnectionManager::default(), None);
+        assert_eq!(status(&handler).await, NodeStatus::Alive);
+
+        env.tc.shutdown_node("drain requested", 0).await;
+        assert_eq!(status(&handler).await, NodeStatus::Draining);
+    }
+}
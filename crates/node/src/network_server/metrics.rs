@@ -20,7 +20,13 @@ use restate_types::config::CommonOptions;
 /// as much as possible (e.g. `restate.invocation.id`)
 static ALLOWED_LABELS: &[&str] = &["rpc.method", "rpc.service", "command", "service", "db"];
 
-pub(crate) fn install_global_prometheus_recorder(opts: &CommonOptions) -> PrometheusHandle {
+#[derive(Debug, thiserror::Error)]
+#[error("failed installing the global prometheus recorder: {0}")]
+pub(crate) struct InstallPrometheusRecorderError(String);
+
+pub(crate) fn install_global_prometheus_recorder(
+    opts: &CommonOptions,
+) -> Result<PrometheusHandle, InstallPrometheusRecorderError> {
     let builder = PrometheusBuilder::default()
         // Remove a metric from registry if it was not updated for that duration
         .idle_timeout(
@@ -31,8 +37,23 @@ pub(crate) fn install_global_prometheus_recorder(opts: &CommonOptions) -> Promet
     let prometheus_handle = recorder.handle();
     let recorder = TracingContextLayer::only_allow(ALLOWED_LABELS).layer(recorder);
 
-    // We do not expect this to fail except due to atomic CAS failure
-    // which should never happen in practice.
-    metrics::set_global_recorder(recorder).expect("no global metrics recorder should be installed");
-    prometheus_handle
+    // Fails if a global recorder has been installed already, e.g. if the node is initialized
+    // more than once within the same process.
+    metrics::set_global_recorder(recorder)
+        .map_err(|err| InstallPrometheusRecorderError(err.to_string()))?;
+    Ok(prometheus_handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_install_fails_without_panicking() {
+        let opts = CommonOptions::default();
+
+        // the first install can fail as well if another test installed a recorder already
+        let _ = install_global_prometheus_recorder(&opts);
+        assert!(install_global_prometheus_recorder(&opts).is_err());
+    }
 }
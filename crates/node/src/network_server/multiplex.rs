@@ -21,6 +21,7 @@ use futures::{future::BoxFuture, ready};
 use hyper::Request;
 use std::{
     convert::Infallible,
+    sync::Arc,
     task::{Context, Poll},
 };
 use tower::Service;
@@ -30,6 +31,8 @@ pub struct MultiplexService<A, B> {
     base_ready: bool,
     grpc: B,
     grpc_ready: bool,
+    /// Path prefixes of the gRPC services, e.g. `/package.Service/`.
+    grpc_path_prefixes: Arc<[String]>,
 }
 
 impl<A, B> MultiplexService<A, B> {
@@ -39,8 +42,22 @@ impl<A, B> MultiplexService<A, B> {
             base_ready: false,
             grpc,
             grpc_ready: false,
+            grpc_path_prefixes: Arc::new([]),
         }
     }
+
+    /// Routes requests to the given gRPC services (by their fully qualified name) to the grpc
+    /// service even if they lack a gRPC content-type.
+    pub fn with_grpc_services<'a>(
+        mut self,
+        service_names: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        self.grpc_path_prefixes = service_names
+            .into_iter()
+            .map(|service_name| format!("/{service_name}/"))
+            .collect();
+        self
+    }
 }
 
 impl<A, B> Clone for MultiplexService<A, B>
@@ -52,6 +69,7 @@ where
         Self {
             base: self.base.clone(),
             grpc: self.grpc.clone(),
+            grpc_path_prefixes: Arc::clone(&self.grpc_path_prefixes),
             // the cloned services probably wont be ready
             base_ready: false,
             grpc_ready: false,
@@ -105,7 +123,7 @@ where
 
         // if we get a grpc request call the grpc service, otherwise call the http service
         // when calling a service it becomes not-ready so we have drive readiness again
-        if is_grpc_request(&req) {
+        if is_grpc_request(&req) || is_grpc_path(&req, &self.grpc_path_prefixes) {
             self.grpc_ready = false;
             let future = self.grpc.call(req);
             Box::pin(async move {
@@ -130,3 +148,57 @@ fn is_grpc_request<B>(req: &Request<B>) -> bool {
         .filter(|content_type| content_type.starts_with(b"application/grpc"))
         .is_some()
 }
+
+fn is_grpc_path<B>(req: &Request<B>, grpc_path_prefixes: &[String]) -> bool {
+    let path = req.uri().path();
+    grpc_path_prefixes
+        .iter()
+        .any(|prefix| path.starts_with(prefix.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hyper::Body;
+    use tower::ServiceExt;
+
+    async fn route(req: Request<Body>) -> String {
+        let service = MultiplexService::new(
+            tower::service_fn(|_: Request<Body>| async { Ok::<_, Infallible>("http") }),
+            tower::service_fn(|_: Request<Body>| async { Ok::<_, Infallible>("grpc") }),
+        )
+        .with_grpc_services(["dev.restate.node_svc.NodeSvc"]);
+
+        let response = service.oneshot(req).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn grpc_web_request_is_routed_to_grpc() {
+        let req = Request::post("/dev.restate.node_svc.NodeSvc/GetIdent")
+            .header(CONTENT_TYPE, "application/grpc-web+proto")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(route(req).await, "grpc");
+
+        // known grpc services are routed by path if the content-type is missing
+        let req = Request::post("/dev.restate.node_svc.NodeSvc/GetIdent")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(route(req).await, "grpc");
+    }
+
+    #[tokio::test]
+    async fn http_request_is_routed_to_http() {
+        let req = Request::get("/metrics").body(Body::empty()).unwrap();
+        assert_eq!(route(req).await, "http");
+
+        let req = Request::post("/dev.restate.other.OtherSvc/Call")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(route(req).await, "http");
+    }
+}
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::fmt::Write;
 
 use metrics::gauge;
@@ -201,3 +202,136 @@ pub fn submit_tokio_metrics(runtime: &'static str, stats: RuntimeMetrics) {
             .set(stats.worker_mean_poll_time(idx).as_secs_f64());
     }
 }
+
+/// Converts the prometheus text exposition format to the OpenMetrics text format.
+///
+/// Samples of the same metric family are grouped, since OpenMetrics doesn't allow interleaving
+/// metric families (which happens for per-database rocksdb metrics). Counter families are named
+/// without the `_total` suffix, while their samples always carry it.
+pub fn convert_to_openmetrics(text: &str) -> String {
+    #[derive(Default)]
+    struct MetricFamily<'a> {
+        help: Option<&'a str>,
+        metric_type: Option<&'a str>,
+        samples: Vec<String>,
+    }
+
+    fn family_name<'a>(name: &'a str, metric_type: &str) -> &'a str {
+        if metric_type == "counter" {
+            name.strip_suffix("_total").unwrap_or(name)
+        } else {
+            name
+        }
+    }
+
+    fn family<'f, 'a>(
+        families: &'f mut HashMap<&'a str, MetricFamily<'a>>,
+        order: &mut Vec<&'a str>,
+        name: &'a str,
+    ) -> &'f mut MetricFamily<'a> {
+        families.entry(name).or_insert_with(|| {
+            order.push(name);
+            MetricFamily::default()
+        })
+    }
+
+    let metric_types: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("# TYPE "))
+        .filter_map(|line| line.split_once(' '))
+        .collect();
+
+    let mut families = HashMap::new();
+    let mut order = Vec::new();
+
+    for line in text.lines().filter(|line| !line.is_empty()) {
+        if let Some(help) = line.strip_prefix("# HELP ") {
+            let (name, help) = help.split_once(' ').unwrap_or((help, ""));
+            let metric_type = metric_types.get(name).copied().unwrap_or_default();
+            family(&mut families, &mut order, family_name(name, metric_type)).help = Some(help);
+        } else if let Some(metric_type) = line.strip_prefix("# TYPE ") {
+            let (name, metric_type) = metric_type.split_once(' ').unwrap_or((metric_type, ""));
+            family(&mut families, &mut order, family_name(name, metric_type)).metric_type =
+                Some(metric_type);
+        } else if line.starts_with('#') {
+            // other comments are not part of the OpenMetrics format
+        } else {
+            let sample_name = line.split(['{', ' ']).next().unwrap_or(line);
+            let (name, metric_type) = ["", "_sum", "_count", "_bucket"]
+                .iter()
+                .filter_map(|suffix| sample_name.strip_suffix(suffix))
+                .find_map(|name| metric_types.get_key_value(name))
+                .map(|(name, metric_type)| (*name, *metric_type))
+                .unwrap_or((sample_name, "unknown"));
+
+            let sample = if metric_type == "counter" && !sample_name.ends_with("_total") {
+                format!("{}_total{}", sample_name, &line[sample_name.len()..])
+            } else {
+                line.to_owned()
+            };
+            family(&mut families, &mut order, family_name(name, metric_type))
+                .samples
+                .push(sample);
+        }
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for name in order {
+        let family = families.remove(name).expect("metric family to exist");
+        let metric_type = match family.metric_type {
+            None | Some("untyped") => "unknown",
+            Some(metric_type) => metric_type,
+        };
+        let _ = writeln!(out, "# TYPE {} {}", name, metric_type);
+        if let Some(help) = family.help {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+        }
+        for sample in family.samples {
+            let _ = writeln!(out, "{}", sample);
+        }
+    }
+    let _ = writeln!(out, "# EOF");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openmetrics_conversion() {
+        let text = "\
+# HELP restate_requests_total Number of requests
+# TYPE restate_requests_total counter
+restate_requests_total{db=\"a\"} 1
+
+# TYPE restate_rocksdb_db_get_seconds summary
+restate_rocksdb_db_get_seconds{db=\"a\",quantile=\"0.5\"} 0.1
+restate_rocksdb_db_get_seconds_sum{db=\"a\"} 1
+restate_rocksdb_db_get_seconds_count{db=\"a\"} 10
+
+# TYPE restate_requests_total counter
+restate_requests_total{db=\"b\"} 2
+
+# TYPE restate_flushes counter
+restate_flushes 3
+";
+
+        assert_eq!(
+            convert_to_openmetrics(text),
+            "\
+# TYPE restate_requests counter
+# HELP restate_requests Number of requests
+restate_requests_total{db=\"a\"} 1
+restate_requests_total{db=\"b\"} 2
+# TYPE restate_rocksdb_db_get_seconds summary
+restate_rocksdb_db_get_seconds{db=\"a\",quantile=\"0.5\"} 0.1
+restate_rocksdb_db_get_seconds_sum{db=\"a\"} 1
+restate_rocksdb_db_get_seconds_count{db=\"a\"} 10
+# TYPE restate_flushes counter
+restate_flushes_total 3
+# EOF
+"
+        );
+    }
+}
@@ -8,13 +8,31 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::response::IntoResponse;
 use axum::routing::get;
+use futures::future;
 use tonic::codec::CompressionEncoding;
+use tonic::server::NamedService;
+use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
+use tonic_web::GrpcWebLayer;
+use tower::util::option_layer;
+use tower_http::compression::CompressionLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
+use tracing::{error, info, warn, Span};
 
 use restate_cluster_controller::ClusterControllerHandle;
+use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{cancellation_watcher, task_center};
-use restate_grpc_util::run_hyper_server;
+use restate_grpc_util::auth::{
+    auth_layer, configured_authenticator, grpc_auth_layer, Authenticator,
+};
+use restate_grpc_util::run_hyper_server_with_tls;
+use restate_grpc_util::tls::load_tls_acceptor;
+use restate_invoker_impl::ChannelStatusReader;
 use restate_metadata_store::MetadataStoreClient;
 use restate_network::ConnectionManager;
 use restate_node_protocol::{common, node};
@@ -22,35 +40,49 @@ use restate_node_services::cluster_ctrl;
 use restate_node_services::cluster_ctrl::cluster_ctrl_svc_server::ClusterCtrlSvcServer;
 use restate_node_services::node_svc::node_svc_server::NodeSvcServer;
 use restate_storage_query_datafusion::context::QueryContext;
+use restate_tracing_instrumentation::LogFilterHandle;
 use restate_types::config::CommonOptions;
-use restate_worker::SubscriptionControllerHandle;
+use restate_types::net::BindAddress;
+use restate_worker::{QuiesceHandle, SubscriptionControllerHandle};
 
 use crate::network_server::handler;
 use crate::network_server::handler::cluster_ctrl::ClusterCtrlSvcHandler;
 use crate::network_server::handler::node::NodeSvcHandler;
 use crate::network_server::metrics::install_global_prometheus_recorder;
 use crate::network_server::multiplex::MultiplexService;
-use crate::network_server::state::NodeCtrlHandlerStateBuilder;
+use crate::network_server::state::{NodeCtrlHandlerState, NodeCtrlHandlerStateBuilder};
 
 pub struct NetworkServer {
     connection_manager: ConnectionManager,
+    log_filter_handle: Option<LogFilterHandle>,
     worker_deps: Option<WorkerDependencies>,
     admin_deps: Option<AdminDependencies>,
+    authenticator: Option<Arc<dyn Authenticator>>,
 }
 
 impl NetworkServer {
     pub fn new(
         connection_manager: ConnectionManager,
+        log_filter_handle: Option<LogFilterHandle>,
         worker_deps: Option<WorkerDependencies>,
         admin_deps: Option<AdminDependencies>,
     ) -> Self {
         Self {
             connection_manager,
+            log_filter_handle,
             worker_deps,
             admin_deps,
+            authenticator: None,
         }
     }
 
+    /// Authenticates the requests to the HTTP endpoints and the gRPC services with the given
+    /// authenticator instead of the one configured via the options.
+    pub fn with_authenticator(mut self, authenticator: Arc<dyn Authenticator>) -> Self {
+        self.authenticator = Some(authenticator);
+        self
+    }
+
     pub async fn run(self, options: CommonOptions) -> Result<(), anyhow::Error> {
         let tc = task_center();
         // Configure Metric Exporter
@@ -58,89 +90,252 @@ impl NetworkServer {
         state_builder.task_center(tc.clone());
 
         if !options.disable_prometheus {
-            state_builder.prometheus_handle(Some(install_global_prometheus_recorder(&options)));
+            match install_global_prometheus_recorder(&options) {
+                Ok(prometheus_handle) => {
+                    state_builder.prometheus_handle(Some(prometheus_handle));
+                }
+                Err(err) => {
+                    // keep the node running, /metrics reports the failure instead
+                    error!(%err, "Metrics won't be exported");
+                    state_builder.prometheus_install_error(Some(err.to_string()));
+                }
+            }
         }
 
+        state_builder.quiesce_handle(
+            self.worker_deps
+                .as_ref()
+                .map(|worker_deps| worker_deps.quiesce_handle.clone()),
+        );
+
         let shared_state = state_builder.build().expect("should be infallible");
 
         // Trace layer
         let span_factory = tower_http::trace::DefaultMakeSpan::new()
-            .include_headers(true)
-            .level(tracing::Level::ERROR);
+            .include_headers(options.request_tracing_include_headers)
+            .level(options.request_tracing_level);
 
         // -- HTTP service (for prometheus et al.)
-        let router = axum::Router::new()
-            .route("/metrics", get(handler::render_metrics))
-            .with_state(shared_state)
+        let max_request_size = options.max_request_size.get();
+        let mut router = http_router(shared_state, max_request_size);
+        let authenticator = match self.authenticator {
+            Some(authenticator) => Some(authenticator),
+            None => configured_authenticator(options.auth.as_ref())?,
+        };
+        if let Some(authenticator) = &authenticator {
+            router = router.layer(auth_layer(Arc::clone(authenticator)));
+        }
+        if options.enable_access_log {
+            router = with_access_log(router);
+        }
+        let router = router
             .layer(TraceLayer::new_for_http().make_span_with(span_factory.clone()))
             .fallback(handler_404);
 
         // -- GRPC Service Setup
-        let mut reflection_service_builder = tonic_reflection::server::Builder::configure()
-            .register_encoded_file_descriptor_set(node::FILE_DESCRIPTOR_SET)
-            .register_encoded_file_descriptor_set(common::FILE_DESCRIPTOR_SET);
-
-        if self.admin_deps.is_some() {
-            reflection_service_builder = reflection_service_builder
-                .register_encoded_file_descriptor_set(cluster_ctrl::FILE_DESCRIPTOR_SET);
-        }
+        let reflection_service = reflection_service(&options, self.admin_deps.is_some())?;
 
         let cluster_controller_service = self.admin_deps.map(|admin_deps| {
             ClusterCtrlSvcServer::new(ClusterCtrlSvcHandler::new(admin_deps))
+                .max_decoding_message_size(max_request_size)
                 .accept_compressed(CompressionEncoding::Gzip)
                 .send_compressed(CompressionEncoding::Gzip)
         });
 
         let server_builder = tonic::transport::Server::builder()
             .layer(TraceLayer::new_for_grpc().make_span_with(span_factory))
+            .layer(option_layer(grpc_web_layer(&options)))
+            .layer(option_layer(authenticator.map(grpc_auth_layer)))
             .add_service(
                 NodeSvcServer::new(NodeSvcHandler::new(
                     tc,
                     self.worker_deps,
                     self.connection_manager,
+                    self.log_filter_handle,
                 ))
+                .max_decoding_message_size(max_request_size)
                 .accept_compressed(CompressionEncoding::Gzip)
                 .send_compressed(CompressionEncoding::Gzip),
             )
             .add_optional_service(cluster_controller_service)
-            .add_service(reflection_service_builder.build()?);
+            .add_optional_service(reflection_service);
 
-        // Multiplex both grpc and http based on content-type
-        let service = MultiplexService::new(router, server_builder.into_service());
+        // Multiplex both grpc and http based on content-type, falling back to the path of the
+        // grpc services for clients which don't set it
+        let service = MultiplexService::new(router, server_builder.into_service())
+            .with_grpc_services([
+                NodeSvcServer::<NodeSvcHandler>::NAME,
+                ClusterCtrlSvcServer::<ClusterCtrlSvcHandler>::NAME,
+            ]);
 
-        run_hyper_server(
-            &options.bind_address,
-            service,
-            cancellation_watcher(),
-            "node-grpc",
-        )
-        .await?;
+        // Fail early if the certificate or key can't be loaded
+        let tls_acceptor = options.tls.as_ref().map(load_tls_acceptor).transpose()?;
+
+        // one server per bind address, all of them serving the same service
+        let server = future::try_join_all(
+            std::iter::once(&options.bind_address)
+                .chain(&options.additional_bind_addresses)
+                .map(|bind_address| {
+                    // unix domain sockets are only reachable locally, they stay plain
+                    let tls_acceptor = match bind_address {
+                        BindAddress::Socket(_) => tls_acceptor.clone(),
+                        BindAddress::Uds(_) => None,
+                    };
+                    run_hyper_server_with_tls(
+                        bind_address,
+                        tls_acceptor,
+                        service.clone(),
+                        cancellation_watcher(),
+                        "node-grpc",
+                    )
+                }),
+        );
+        tokio::pin!(server);
+
+        // the servers only complete by themselves if one of them fails
+        tokio::select! {
+            result = &mut server => {
+                result?;
+                return Ok(());
+            }
+            _ = cancellation_watcher() => {}
+        }
+
+        // bound the time for draining the in-flight requests
+        tokio::select! {
+            result = &mut server => {
+                result?;
+                info!("Node server shut down gracefully");
+            }
+            _ = tokio::time::sleep(options.server_shutdown_timeout.into()) => {
+                warn!(
+                    "Node server did not shut down gracefully within {}, stopping it forcibly",
+                    options.server_shutdown_timeout
+                );
+            }
+        }
 
         Ok(())
     }
 }
 
-// handle 404
-async fn handler_404() -> (http::StatusCode, &'static str) {
-    (
-        http::StatusCode::NOT_FOUND,
-        "Are you lost? Maybe visit https://restate.dev instead!",
+fn grpc_web_layer(options: &CommonOptions) -> Option<GrpcWebLayer> {
+    options.enable_grpc_web.then(GrpcWebLayer::new)
+}
+
+fn reflection_service(
+    options: &CommonOptions,
+    with_cluster_ctrl: bool,
+) -> Result<Option<ServerReflectionServer<impl ServerReflection>>, tonic_reflection::server::Error>
+{
+    if options.disable_grpc_reflection {
+        return Ok(None);
+    }
+
+    let mut reflection_service_builder = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(node::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(common::FILE_DESCRIPTOR_SET);
+
+    if with_cluster_ctrl {
+        reflection_service_builder = reflection_service_builder
+            .register_encoded_file_descriptor_set(cluster_ctrl::FILE_DESCRIPTOR_SET);
+    }
+
+    reflection_service_builder.build().map(Some)
+}
+
+fn http_router(state: NodeCtrlHandlerState, max_request_size: usize) -> axum::Router {
+    axum::Router::new()
+        .route(
+            "/metrics",
+            // compression is scoped to the metrics route to leave the grpc services untouched
+            get(handler::render_metrics).layer(CompressionLayer::new().gzip(true)),
+        )
+        .route("/features", get(handler::render_features))
+        .layer(RequestBodyLimitLayer::new(max_request_size))
+        .with_state(state)
+}
+
+const ACCESS_LOG_TARGET: &str = "restate::access_log";
+
+/// Logs one event per request, carrying the status and latency of the response. The method and
+/// path of the request are fields of the enclosing `access_log` span.
+fn with_access_log(router: axum::Router) -> axum::Router {
+    router.layer(
+        TraceLayer::new_for_http()
+            .make_span_with(|request: &http::Request<hyper::Body>| {
+                tracing::info_span!(
+                    target: ACCESS_LOG_TARGET,
+                    "access_log",
+                    method = %request.method(),
+                    path = request.uri().path(),
+                )
+            })
+            .on_request(())
+            .on_response(
+                |response: &http::Response<axum::body::BoxBody>, latency: Duration, _: &Span| {
+                    tracing::info!(
+                        target: ACCESS_LOG_TARGET,
+                        status = response.status().as_u16(),
+                        latency_ms = latency.as_millis() as u64,
+                        "Request completed"
+                    );
+                },
+            )
+            .on_failure(()),
     )
 }
 
+// handle 404
+async fn handler_404(uri: http::Uri, headers: http::HeaderMap) -> axum::response::Response {
+    let accepts_json = headers
+        .get_all(http::header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media_type| media_type.split(';').next())
+        .any(|media_type| media_type.trim() == "application/json");
+
+    if accepts_json {
+        (
+            http::StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": "not_found",
+                "path": uri.path(),
+            })),
+        )
+            .into_response()
+    } else {
+        (
+            http::StatusCode::NOT_FOUND,
+            "Are you lost? Maybe visit https://restate.dev instead!",
+        )
+            .into_response()
+    }
+}
+
 pub struct WorkerDependencies {
     pub query_context: QueryContext,
     pub subscription_controller: Option<SubscriptionControllerHandle>,
+    pub quiesce_handle: QuiesceHandle,
+    pub invoker_status_reader: ChannelStatusReader,
+    pub processors_manager_handle: ProcessorsManagerHandle,
 }
 
 impl WorkerDependencies {
     pub fn new(
         query_context: QueryContext,
         subscription_controller: Option<SubscriptionControllerHandle>,
+        quiesce_handle: QuiesceHandle,
+        invoker_status_reader: ChannelStatusReader,
+        processors_manager_handle: ProcessorsManagerHandle,
     ) -> Self {
         WorkerDependencies {
             query_context,
             subscription_controller,
+            quiesce_handle,
+            invoker_status_reader,
+            processors_manager_handle,
         }
     }
 }
@@ -161,3 +356,245 @@ impl AdminDependencies {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+    use http::{Request, StatusCode};
+    use hyper::Body;
+    use tower::ServiceExt;
+
+    use restate_core::TestCoreEnv;
+    use restate_grpc_util::auth::BearerTokenAuthenticator;
+
+    use crate::test_util::{self, LogBuffer};
+
+    fn handler_state_builder() -> NodeCtrlHandlerStateBuilder {
+        let mut builder = NodeCtrlHandlerStateBuilder::default();
+        builder.task_center(test_util::task_center());
+        builder
+    }
+
+    async fn get_metrics(accept_encoding: Option<&str>) -> http::Response<axum::body::BoxBody> {
+        // avoids reading the rocksdb statistics which requires an initialized RocksDbManager
+        let state = handler_state_builder()
+            .prometheus_install_error(Some(
+                "a global recorder has already been installed in this test process".to_owned(),
+            ))
+            .build()
+            .unwrap();
+
+        let mut request = Request::get("/metrics");
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(ACCEPT_ENCODING, accept_encoding);
+        }
+
+        http_router(state, CommonOptions::default().max_request_size.get())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn get_unknown_route(accept: &str) -> http::Response<axum::body::BoxBody> {
+        axum::Router::new()
+            .fallback(handler_404)
+            .oneshot(
+                Request::get("/unknown")
+                    .header(http::header::ACCEPT, accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn not_found_content_type_depends_on_accept() {
+        let response = get_unknown_route("application/json").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({"error": "not_found", "path": "/unknown"})
+        );
+
+        let response = get_unknown_route("text/html,application/xhtml+xml").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_requests_are_rejected() {
+        let state = handler_state_builder().build().unwrap();
+
+        let response = http_router(state, 16)
+            .oneshot(
+                Request::post("/features")
+                    .header(http::header::CONTENT_LENGTH, 17)
+                    .body(Body::from(vec![0; 17]))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn reflection_service_can_be_disabled() {
+        let mut options = CommonOptions::default();
+        assert!(reflection_service(&options, true).unwrap().is_some());
+
+        options.disable_grpc_reflection = true;
+        assert!(reflection_service(&options, true).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn grpc_web_requests_are_accepted_if_enabled() {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut options = CommonOptions::default();
+        options.enable_grpc_web = true;
+
+        let service = tonic::transport::Server::builder()
+            .layer(option_layer(grpc_web_layer(&options)))
+            .add_service(NodeSvcServer::new(NodeSvcHandler::new(
+                env.tc.clone(),
+                None,
+                ConnectionManager::default(),
+                None,
+            )))
+            .into_service();
+
+        // grpc-web frame carrying the empty request message
+        let response = service
+            .oneshot(
+                Request::post("/dev.restate.node_svc.NodeSvc/GetIdent")
+                    .header(http::header::CONTENT_TYPE, "application/grpc-web+proto")
+                    .body(Body::from(vec![0u8; 5]))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/grpc-web+proto"
+        );
+        // grpc-web sends the trailers as part of the body
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let trailers = String::from_utf8_lossy(&body);
+        assert!(trailers.contains("grpc-status:0"), "{trailers}");
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_grpc_calls_are_rejected() {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let authenticator = Arc::new(BearerTokenAuthenticator::new("secret"));
+
+        let service = tonic::transport::Server::builder()
+            .layer(grpc_auth_layer(authenticator))
+            .add_service(NodeSvcServer::new(NodeSvcHandler::new(
+                env.tc.clone(),
+                None,
+                ConnectionManager::default(),
+                None,
+            )))
+            .into_service();
+
+        let grpc_status = |response: http::Response<_>| {
+            assert_eq!(response.status(), StatusCode::OK);
+            response
+                .headers()
+                .get("grpc-status")
+                .map(|status| status.to_str().unwrap().to_owned())
+        };
+        // empty request message, the status is checked before it is decoded
+        let request = |method: &str| {
+            Request::post(format!("/dev.restate.node_svc.NodeSvc/{method}"))
+                .header(http::header::CONTENT_TYPE, "application/grpc")
+                .body(Body::from(vec![0u8; 5]))
+                .unwrap()
+        };
+
+        // node-to-node calls such as GetIdent need the credentials as well
+        for method in [
+            "SetLogFilter",
+            "SetQuiesced",
+            "SyncSchema",
+            "GetInvocationRetryState",
+            "GetIdent",
+            "CreateConnection",
+        ] {
+            let response = service.clone().oneshot(request(method)).await.unwrap();
+            assert_eq!(
+                grpc_status(response),
+                Some((tonic::Code::Unauthenticated as i32).to_string()),
+                "{method}"
+            );
+        }
+
+        let mut authenticated_request = request("GetIdent");
+        authenticated_request.headers_mut().insert(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_static("Bearer secret"),
+        );
+        let response = service
+            .clone()
+            .oneshot(authenticated_request)
+            .await
+            .unwrap();
+        assert_ne!(
+            grpc_status(response),
+            Some((tonic::Code::Unauthenticated as i32).to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_produce_access_log_events() {
+        let state = handler_state_builder().build().unwrap();
+
+        let logs = LogBuffer::default();
+        let _guard = tracing::subscriber::set_default(logs.subscriber());
+
+        let response = with_access_log(http_router(state, 1024))
+            .oneshot(Request::get("/features").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let logs = logs.contents();
+        let access_log = logs
+            .lines()
+            .find(|line| line.contains(ACCESS_LOG_TARGET))
+            .expect("access log event");
+        assert!(access_log.contains("method=GET"), "{access_log}");
+        assert!(access_log.contains("path=\"/features\""), "{access_log}");
+        assert!(access_log.contains("status=200"), "{access_log}");
+        assert!(access_log.contains("latency_ms="), "{access_log}");
+    }
+
+    #[test]
+    fn grpc_web_is_disabled_by_default() {
+        assert!(grpc_web_layer(&CommonOptions::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn metrics_are_compressed_if_requested() {
+        let response = get_metrics(Some("gzip")).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let response = get_metrics(None).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+    }
+}
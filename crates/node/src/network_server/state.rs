@@ -10,10 +10,17 @@
 
 use metrics_exporter_prometheus::PrometheusHandle;
 use restate_core::TaskCenter;
+use restate_worker::QuiesceHandle;
 
 #[derive(Clone, derive_builder::Builder)]
 pub struct NodeCtrlHandlerState {
     #[builder(default)]
     pub prometheus_handle: Option<PrometheusHandle>,
+    /// Set if prometheus is enabled but the recorder could not be installed
+    #[builder(default)]
+    pub prometheus_install_error: Option<String>,
+    /// Set if the node runs the worker role
+    #[builder(default)]
+    pub quiesce_handle: Option<QuiesceHandle>,
     pub task_center: TaskCenter,
 }
@@ -10,23 +10,31 @@
 
 use anyhow::Context;
 use codederror::CodedError;
+use metrics::counter;
 use restate_core::network::MessageRouterBuilder;
 use restate_network::Networking;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
-use tonic::transport::Channel;
+use tracing::info;
 
-use restate_admin::service::AdminService;
+use restate_admin::service::{AdminService, BuildVersion};
 use restate_bifrost::Bifrost;
 use restate_cluster_controller::ClusterControllerHandle;
 use restate_core::metadata_store::MetadataStoreClient;
 use restate_core::{task_center, Metadata, MetadataWriter, TaskCenter, TaskKind};
+use restate_grpc_util::auth::{configured_authenticator, BearerTokenInterceptor};
+use restate_grpc_util::create_grpc_channel_from_advertised_address;
 use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
 use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
 use restate_service_protocol::discovery::ServiceDiscovery;
 use restate_types::arc_util::ArcSwapExt;
 use restate_types::config::{IngressOptions, UpdateableConfiguration};
+use restate_types::net::{AdvertisedAddress, BindAddress};
 use restate_types::retries::RetryPolicy;
 
+use crate::build_info;
+use crate::metric_definitions::{self, ADMIN_LOCAL_NODE_ADDRESS_FALLBACKS};
+
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum AdminRoleBuildError {
     #[error("unknown")]
@@ -38,10 +46,14 @@ pub enum AdminRoleBuildError {
     #[error("failed building the service client: {0}")]
     #[code(unknown)]
     ServiceClient(#[from] restate_service_client::BuildError),
+    #[error("failed setting up the authentication: {0}")]
+    #[code(unknown)]
+    Auth(#[from] std::io::Error),
 }
 
 pub struct AdminRole {
     updateable_config: UpdateableConfiguration,
+    grpc_interceptor: BearerTokenInterceptor,
     controller: restate_cluster_controller::Service<Networking>,
     admin: AdminService<IngressOptions>,
 }
@@ -56,6 +68,7 @@ impl AdminRole {
         router_builder: &mut MessageRouterBuilder,
         metadata_store_client: MetadataStoreClient,
     ) -> Result<Self, AdminRoleBuildError> {
+        metric_definitions::describe_metrics();
         let config = updateable_config.pinned();
 
         // Total duration roughly 1s
@@ -64,12 +77,21 @@ impl AdminRole {
             ServiceClient::from_options(&config.common.service_client, AssumeRoleCacheMode::None)?;
         let service_discovery = ServiceDiscovery::new(retry_policy, client);
 
-        let admin = AdminService::new(
+        let mut admin = AdminService::new(
             metadata_writer,
             metadata_store_client,
             config.ingress.clone(),
             service_discovery,
-        );
+        )
+        .with_build_version(BuildVersion::new(
+            build_info::RESTATE_NODE_VERSION,
+            Some(build_info::RESTATE_NODE_COMMIT_SHA.to_owned()),
+        ));
+        if let Some(authenticator) = configured_authenticator(config.common.auth.as_ref())? {
+            admin = admin.with_authenticator(authenticator);
+        }
+        let grpc_interceptor = BearerTokenInterceptor::from_options(config.common.auth.as_ref())?;
+        admin = admin.with_grpc_interceptor(grpc_interceptor.clone());
 
         let controller = restate_cluster_controller::Service::new(
             updateable_config
@@ -83,6 +105,7 @@ impl AdminRole {
 
         Ok(AdminRole {
             updateable_config,
+            grpc_interceptor,
             controller,
             admin,
         })
@@ -100,20 +123,23 @@ impl AdminRole {
         let tc = task_center();
 
         tc.spawn_child(
-            TaskKind::SystemService,
+            TaskKind::ClusterController,
             "cluster-controller-service",
             None,
             self.controller.run(bifrost.clone()),
         )?;
 
-        // todo: Make address configurable
-        let worker_channel = Channel::builder(
-            "http://127.0.0.1:5122/"
-                .parse()
-                .context("valid worker address uri")?,
-        )
-        .connect_lazy();
-        let node_svc_client = NodeSvcClient::new(worker_channel);
+        let config = self.updateable_config.pinned();
+        let node_address = if config.common.tls.is_some() {
+            // the certificate is issued for the advertised address rather than the bind address
+            config.common.advertised_address.clone()
+        } else {
+            local_node_address(&config.common.bind_address, config.admin.local_node_host)
+        };
+        let worker_channel = create_grpc_channel_from_advertised_address(node_address)
+            .context("valid worker address uri")?;
+        let node_svc_client =
+            NodeSvcClient::with_interceptor(worker_channel, self.grpc_interceptor);
 
         tc.spawn_child(
             TaskKind::RpcServer,
@@ -129,3 +155,79 @@ impl AdminRole {
         Ok(())
     }
 }
+
+/// Address under which the admin service reaches the node service of its own node. It is derived
+/// from the node's bind address since the admin service is bound to a different port. If the node
+/// is bound to an unspecified address, `local_node_host` is used, falling back to localhost.
+fn local_node_address(
+    bind_address: &BindAddress,
+    local_node_host: Option<IpAddr>,
+) -> AdvertisedAddress {
+    match bind_address {
+        BindAddress::Uds(path) => AdvertisedAddress::Uds(path.clone()),
+        BindAddress::Socket(socket_addr) => {
+            let mut socket_addr = *socket_addr;
+            if socket_addr.ip().is_unspecified() {
+                let host = local_node_host.unwrap_or_else(|| {
+                    let localhost = match socket_addr.ip() {
+                        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                    };
+                    info!(
+                        %socket_addr,
+                        "Node is bound to an unspecified address, assuming that it is reachable \
+                        via {localhost}. Configure 'admin.local-node-host' if this is not the case"
+                    );
+                    counter!(ADMIN_LOCAL_NODE_ADDRESS_FALLBACKS).increment(1);
+                    localhost
+                });
+                socket_addr.set_ip(host);
+            }
+            AdvertisedAddress::Http(
+                format!("http://{socket_addr}/")
+                    .parse()
+                    .expect("socket address should be a valid uri"),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_node_address_uses_node_bind_port() {
+        assert_eq!(
+            local_node_address(&"0.0.0.0:6122".parse().unwrap(), None),
+            "http://127.0.0.1:6122/".parse().unwrap()
+        );
+        assert_eq!(
+            local_node_address(&"[::]:6122".parse().unwrap(), None),
+            "http://[::1]:6122/".parse().unwrap()
+        );
+        assert_eq!(
+            local_node_address(&"10.0.0.1:6122".parse().unwrap(), None),
+            "http://10.0.0.1:6122/".parse().unwrap()
+        );
+        assert_eq!(
+            local_node_address(&"unix:/tmp/node.sock".parse().unwrap(), None),
+            AdvertisedAddress::Uds("/tmp/node.sock".into())
+        );
+    }
+
+    #[test]
+    fn local_node_address_uses_configured_host() {
+        let local_node_host = Some("10.0.0.2".parse().unwrap());
+
+        assert_eq!(
+            local_node_address(&"0.0.0.0:6122".parse().unwrap(), local_node_host),
+            "http://10.0.0.2:6122/".parse().unwrap()
+        );
+        // only replaces unspecified addresses
+        assert_eq!(
+            local_node_address(&"10.0.0.1:6122".parse().unwrap(), local_node_host),
+            "http://10.0.0.1:6122/".parse().unwrap()
+        );
+    }
+}
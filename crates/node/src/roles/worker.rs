@@ -12,8 +12,10 @@ use codederror::CodedError;
 
 use restate_bifrost::Bifrost;
 use restate_core::network::MessageRouterBuilder;
+use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{cancellation_watcher, metadata, task_center, Metadata};
 use restate_core::{ShutdownError, TaskKind};
+use restate_invoker_impl::ChannelStatusReader;
 use restate_metadata_store::MetadataStoreClient;
 use restate_network::Networking;
 use restate_node_protocol::metadata::MetadataKind;
@@ -23,7 +25,7 @@ use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::config::UpdateableConfiguration;
 use restate_types::Version;
 use restate_worker::SubscriptionController;
-use restate_worker::{SubscriptionControllerHandle, Worker};
+use restate_worker::{QuiesceHandle, SubscriptionControllerHandle, Worker};
 
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum WorkerRoleError {
@@ -94,6 +96,18 @@ impl WorkerRole {
         Some(self.worker.subscription_controller_handle())
     }
 
+    pub fn quiesce_handle(&self) -> QuiesceHandle {
+        self.worker.quiesce_handle()
+    }
+
+    pub fn invoker_status_reader(&self) -> ChannelStatusReader {
+        self.worker.invoker_status_reader()
+    }
+
+    pub fn processors_manager_handle(&self) -> ProcessorsManagerHandle {
+        self.worker.processors_manager_handle()
+    }
+
     pub async fn start(self) -> anyhow::Result<()> {
         let tc = task_center();
         // todo: only run subscriptions on node 0 once being distributed
@@ -18,7 +18,8 @@ use codederror::CodedError;
 use restate_rocksdb::CfName;
 use restate_rocksdb::IoMode;
 use restate_rocksdb::Priority;
-use restate_types::config::Configuration;
+use restate_rocksdb::RocksDbManager;
+use restate_types::config::{CompressionType, Configuration};
 use rocksdb::DBCompressionType;
 use rocksdb::DBPinnableSlice;
 use rocksdb::DBRawIteratorWithThreadMode;
@@ -177,9 +178,16 @@ impl Clone for PartitionStore {
 
 pub(crate) fn cf_options(
     memory_budget: usize,
+    compression: Option<CompressionType>,
+    block_size: Option<usize>,
 ) -> impl Fn(rocksdb::Options) -> rocksdb::Options + Send + Sync + 'static {
     move |mut cf_options| {
         set_memory_related_opts(&mut cf_options, memory_budget);
+        if let Some(block_size) = block_size {
+            cf_options.set_block_based_table_factory(
+                &RocksDbManager::get().block_based_options(block_size),
+            );
+        }
         // Actually, we would love to use CappedPrefixExtractor but unfortunately it's neither exposed
         // in the C API nor the rust binding. That's okay and we can change it later.
         cf_options.set_prefix_extractor(SliceTransform::create_fixed_prefix(DB_PREFIX_LENGTH));
@@ -189,20 +197,32 @@ pub(crate) fn cf_options(
         // As much as we can to increase the chances to observe a deletion.
         //
         cf_options.set_num_levels(7);
-        cf_options.set_compression_per_level(&[
-            DBCompressionType::None,
-            DBCompressionType::None,
-            DBCompressionType::Lz4,
-            DBCompressionType::Lz4,
-            DBCompressionType::Lz4,
-            DBCompressionType::Lz4,
-            DBCompressionType::Zstd,
-        ]);
+        if let Some(compression) = compression {
+            cf_options.set_compression_per_level(&[to_db_compression_type(compression); 7]);
+        } else {
+            cf_options.set_compression_per_level(&[
+                DBCompressionType::None,
+                DBCompressionType::None,
+                DBCompressionType::Lz4,
+                DBCompressionType::Lz4,
+                DBCompressionType::Lz4,
+                DBCompressionType::Lz4,
+                DBCompressionType::Zstd,
+            ]);
+        }
 
         cf_options
     }
 }
 
+fn to_db_compression_type(compression: CompressionType) -> DBCompressionType {
+    match compression {
+        CompressionType::None => DBCompressionType::None,
+        CompressionType::Lz4 => DBCompressionType::Lz4,
+        CompressionType::Zstd => DBCompressionType::Zstd,
+    }
+}
+
 fn set_memory_related_opts(opts: &mut rocksdb::Options, memtables_budget: usize) {
     // We set the budget to allow 1 mutable + 3 immutable.
     opts.set_write_buffer_size(memtables_budget / 4);
@@ -65,7 +65,11 @@ impl PartitionStoreManager {
         let db_spec = DbSpecBuilder::new(DbName::new(DB_NAME), options.data_dir(), db_options())
             .add_cf_pattern(
                 CfPrefixPattern::new(PARTITION_CF_PREFIX),
-                cf_options(per_partition_memory_budget),
+                cf_options(
+                    per_partition_memory_budget,
+                    options.rocksdb_compression(),
+                    options.rocksdb_block_size(),
+                ),
             )
             .ensure_column_families(partition_ids_to_cfs(initial_partition_set))
             .build_as_optimistic_db();
@@ -29,6 +29,9 @@ use crate::{metric_definitions, DbName, DbSpec, Priority, RocksAccess, RocksDb,
 
 static DB_MANAGER: OnceLock<RocksDbManager> = OnceLock::new();
 
+/// Approximate size of the uncompressed data blocks of the column families.
+const DEFAULT_BLOCK_SIZE: usize = 32 * 1024;
+
 enum WatchdogCommand {
     Register(ConfigSubscription),
     #[cfg(any(test, feature = "test-util"))]
@@ -324,6 +327,14 @@ impl RocksDbManager {
         cf_options.set_avoid_unnecessary_blocking_io(true);
 
         cf_options.set_optimize_filters_for_hits(true);
+        cf_options.set_block_based_table_factory(&self.block_based_options(DEFAULT_BLOCK_SIZE));
+
+        cf_options
+    }
+
+    /// Table options sharing the block cache of all databases. Column families which need a
+    /// different block size than the default can set these as their table factory.
+    pub fn block_based_options(&self, block_size: usize) -> BlockBasedOptions {
         // bloom filters and block cache.
         //
         let mut block_opts = BlockBasedOptions::default();
@@ -335,12 +346,10 @@ impl RocksDbManager {
         block_opts.set_index_block_restart_interval(4);
         block_opts.set_cache_index_and_filter_blocks(true);
         block_opts.set_pin_l0_filter_and_index_blocks_in_cache(true);
-        block_opts.set_block_size(32 * 1024);
+        block_opts.set_block_size(block_size);
 
         block_opts.set_block_cache(&self.cache);
-        cf_options.set_block_based_table_factory(&block_opts);
-
-        cf_options
+        block_opts
     }
 
     /// Spawn a rocksdb blocking operation in the background
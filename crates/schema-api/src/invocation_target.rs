@@ -19,7 +19,7 @@ use std::{cmp, fmt};
 pub const DEFAULT_IDEMPOTENCY_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
 pub const DEFAULT_WORKFLOW_COMPLETION_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InvocationTargetMetadata {
     pub public: bool,
@@ -27,6 +27,9 @@ pub struct InvocationTargetMetadata {
     pub completion_retention: Option<Duration>,
     /// Retention timer that should be used only if an idempotency key is set. See [`InvocationTargetMetadata::compute_retention`] for more details.
     pub idempotency_retention: Duration,
+    /// Retention of the journal of completed invocations, if advertised by the handler.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub journal_retention: Option<Duration>,
     pub target_ty: InvocationTargetType,
     pub input_rules: InputRules,
     pub output_rules: OutputRules,
@@ -95,6 +98,16 @@ impl InputRules {
 
         res
     }
+
+    /// Returns true if these rules accept every content-type accepted by `other`. The validation
+    /// of the value itself, e.g. against a JSON schema, is not taken into account.
+    pub fn accepts_content_types_of(&self, other: &InputRules) -> bool {
+        other.input_validation_rules.iter().all(|other_rule| {
+            self.input_validation_rules
+                .iter()
+                .any(|rule| rule.accepts_content_type_of(other_rule))
+        })
+    }
 }
 
 impl Default for InputRules {
@@ -162,6 +175,24 @@ impl fmt::Display for InputValidationRule {
 }
 
 impl InputValidationRule {
+    fn content_type(&self) -> Option<&InputContentType> {
+        match self {
+            InputValidationRule::NoBodyAndContentType => None,
+            InputValidationRule::ContentType { content_type }
+            | InputValidationRule::JsonValue { content_type } => Some(content_type),
+        }
+    }
+
+    fn accepts_content_type_of(&self, other: &InputValidationRule) -> bool {
+        match (self.content_type(), other.content_type()) {
+            (None, None) => true,
+            (Some(content_type), Some(other_content_type)) => {
+                content_type.contains(other_content_type)
+            }
+            _ => false,
+        }
+    }
+
     fn validate(
         &self,
         input_content_type: Option<&str>,
@@ -213,6 +244,22 @@ pub enum InputContentType {
 }
 
 impl InputContentType {
+    /// Returns true if every content-type matching `other` matches this content-type too.
+    fn contains(&self, other: &InputContentType) -> bool {
+        match (self, other) {
+            (InputContentType::Any, _) => true,
+            (InputContentType::MimeType(ty), InputContentType::MimeType(other_ty))
+            | (InputContentType::MimeType(ty), InputContentType::MimeTypeAndSubtype(other_ty, _)) => {
+                ty == other_ty
+            }
+            (
+                InputContentType::MimeTypeAndSubtype(ty, sub_ty),
+                InputContentType::MimeTypeAndSubtype(other_ty, other_sub_ty),
+            ) => ty == other_ty && sub_ty == other_sub_ty,
+            _ => false,
+        }
+    }
+
     fn validate(&self, input_content_type: &str) -> Result<(), InputValidationError> {
         match self {
             InputContentType::Any => Ok(()),
@@ -327,6 +374,14 @@ impl OutputRules {
             }
         }
     }
+
+    /// Content-type of the output, if any.
+    pub fn content_type(&self) -> Option<&http::HeaderValue> {
+        match &self.content_type_rule {
+            OutputContentTypeRule::None => None,
+            OutputContentTypeRule::Set { content_type, .. } => Some(content_type),
+        }
+    }
 }
 
 impl fmt::Display for OutputRules {
@@ -437,6 +492,7 @@ pub mod test_util {
                 public: true,
                 idempotency_retention: DEFAULT_IDEMPOTENCY_RETENTION,
                 completion_retention: None,
+                journal_retention: None,
                 target_ty: invocation_target_type,
                 input_rules: Default::default(),
                 output_rules: Default::default(),
@@ -601,6 +657,54 @@ mod tests {
         assert_input_not_valid!(input_rules, Some("application/restate+json"), Bytes::new());
     }
 
+    #[test]
+    fn accepts_content_types_of_wider_rules() {
+        let json = InputRules {
+            input_validation_rules: vec![InputValidationRule::ContentType {
+                content_type: "application/json".parse().unwrap(),
+            }],
+        };
+        let json_with_schema = InputRules {
+            input_validation_rules: vec![InputValidationRule::JsonValue {
+                content_type: "application/json".parse().unwrap(),
+            }],
+        };
+        let any_application = InputRules {
+            input_validation_rules: vec![InputValidationRule::ContentType {
+                content_type: "application/*".parse().unwrap(),
+            }],
+        };
+
+        assert!(json.accepts_content_types_of(&json));
+        assert!(json_with_schema.accepts_content_types_of(&json));
+        assert!(json.accepts_content_types_of(&json_with_schema));
+        assert!(any_application.accepts_content_types_of(&json));
+        assert!(InputRules::default().accepts_content_types_of(&any_application));
+        assert!(!json.accepts_content_types_of(&any_application));
+        assert!(!any_application.accepts_content_types_of(&InputRules::default()));
+    }
+
+    #[test]
+    fn does_not_accept_content_types_of_other_rules() {
+        let json = InputRules {
+            input_validation_rules: vec![InputValidationRule::ContentType {
+                content_type: "application/json".parse().unwrap(),
+            }],
+        };
+        let octet_stream = InputRules {
+            input_validation_rules: vec![InputValidationRule::ContentType {
+                content_type: "application/octet-stream".parse().unwrap(),
+            }],
+        };
+        let empty_only = InputRules {
+            input_validation_rules: vec![InputValidationRule::NoBodyAndContentType],
+        };
+
+        assert!(!json.accepts_content_types_of(&octet_stream));
+        assert!(!json.accepts_content_types_of(&empty_only));
+        assert!(!empty_only.accepts_content_types_of(&json));
+    }
+
     #[test]
     fn infer_content_type_default() {
         let input_rules = OutputRules::default();
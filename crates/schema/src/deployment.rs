@@ -13,7 +13,7 @@ use restate_schema_api::deployment::{Deployment, DeploymentMetadata, DeploymentR
 use restate_schema_api::service::ServiceMetadata;
 use restate_types::identifiers::{DeploymentId, ServiceRevision};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct DeploymentSchemas {
     pub metadata: DeploymentMetadata,
 
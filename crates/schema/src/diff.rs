@@ -0,0 +1,340 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::Schema;
+
+use crate::deployment::DeploymentSchemas;
+use crate::service::ServiceSchemas;
+use restate_types::identifiers::{DeploymentId, SubscriptionId};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Changes between two [`Schema`]s, see [`Schema::diff`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub added_services: Vec<String>,
+    pub removed_services: Vec<String>,
+    pub changed_services: Vec<ServiceDiff>,
+    pub added_deployments: Vec<DeploymentId>,
+    pub removed_deployments: Vec<DeploymentId>,
+    /// Deployments which exist in both schemas, but whose endpoint, delivery options, supported
+    /// protocol versions or service revisions differ.
+    pub changed_deployments: Vec<DeploymentId>,
+    pub added_subscriptions: Vec<SubscriptionId>,
+    pub removed_subscriptions: Vec<SubscriptionId>,
+    pub changed_subscriptions: Vec<SubscriptionId>,
+}
+
+/// Changes of a service which exists in both schemas.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ServiceDiff {
+    pub name: String,
+    /// Set if the service moved to another deployment.
+    pub deployment_changed: bool,
+    pub type_changed: bool,
+    /// Set if the idempotency or the workflow completion retention changed.
+    pub retention_changed: bool,
+    pub added_handlers: Vec<String>,
+    pub removed_handlers: Vec<String>,
+    pub changed_handlers: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == SchemaDiff::default()
+    }
+}
+
+impl Schema {
+    /// Computes the changes required to get from this schema to the `other` one. All the reported
+    /// names and ids are sorted.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let services = KeyDiff::compute(&self.services, &other.services, |name| name.clone());
+        let deployments = KeyDiff::compute(&self.deployments, &other.deployments, |id| *id);
+        let subscriptions = KeyDiff::compute(&self.subscriptions, &other.subscriptions, |id| *id);
+
+        let mut changed_services: Vec<_> = services
+            .common
+            .into_iter()
+            .filter_map(|name| service_diff(name, &self.services[name], &other.services[name]))
+            .collect();
+        changed_services.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut changed_deployments: Vec<_> = deployments
+            .common
+            .into_iter()
+            .filter(|id| deployment_changed(&self.deployments[*id], &other.deployments[*id]))
+            .copied()
+            .collect();
+        changed_deployments.sort();
+
+        let mut changed_subscriptions: Vec<_> = subscriptions
+            .common
+            .into_iter()
+            .filter(|id| self.subscriptions[*id] != other.subscriptions[*id])
+            .copied()
+            .collect();
+        changed_subscriptions.sort();
+
+        SchemaDiff {
+            added_services: services.added,
+            removed_services: services.removed,
+            changed_services,
+            added_deployments: deployments.added,
+            removed_deployments: deployments.removed,
+            changed_deployments,
+            added_subscriptions: subscriptions.added,
+            removed_subscriptions: subscriptions.removed,
+            changed_subscriptions,
+        }
+    }
+}
+
+fn service_diff(name: &str, old: &ServiceSchemas, new: &ServiceSchemas) -> Option<ServiceDiff> {
+    let handlers = KeyDiff::compute(&old.handlers, &new.handlers, |name| name.clone());
+
+    let mut changed_handlers: Vec<_> = handlers
+        .common
+        .into_iter()
+        .filter(|handler| old.handlers[*handler] != new.handlers[*handler])
+        .cloned()
+        .collect();
+    changed_handlers.sort();
+
+    let diff = ServiceDiff {
+        name: name.to_owned(),
+        deployment_changed: old.location.latest_deployment != new.location.latest_deployment,
+        type_changed: old.ty != new.ty,
+        retention_changed: old.idempotency_retention != new.idempotency_retention
+            || old.workflow_completion_retention != new.workflow_completion_retention,
+        added_handlers: handlers.added,
+        removed_handlers: handlers.removed,
+        changed_handlers,
+    };
+
+    if diff.deployment_changed
+        || diff.type_changed
+        || diff.retention_changed
+        || !diff.added_handlers.is_empty()
+        || !diff.removed_handlers.is_empty()
+        || !diff.changed_handlers.is_empty()
+    {
+        Some(diff)
+    } else {
+        None
+    }
+}
+
+fn deployment_changed(old: &DeploymentSchemas, new: &DeploymentSchemas) -> bool {
+    let service_revisions = |deployment: &DeploymentSchemas| {
+        let mut revisions: Vec<_> = deployment
+            .services
+            .iter()
+            .map(|service| (service.name.clone(), service.revision))
+            .collect();
+        revisions.sort();
+        revisions
+    };
+
+    old.metadata.ty.protocol_type() != new.metadata.ty.protocol_type()
+        || old.metadata.ty.normalized_address() != new.metadata.ty.normalized_address()
+        || old.metadata.delivery_options.additional_headers
+            != new.metadata.delivery_options.additional_headers
+        || old.metadata.supported_protocol_versions != new.metadata.supported_protocol_versions
+        || service_revisions(old) != service_revisions(new)
+}
+
+struct KeyDiff<'a, K, O> {
+    added: Vec<O>,
+    removed: Vec<O>,
+    common: Vec<&'a K>,
+}
+
+impl<'a, K: Eq + Hash, O: Ord> KeyDiff<'a, K, O> {
+    fn compute<V>(
+        old: &'a HashMap<K, V>,
+        new: &'a HashMap<K, V>,
+        to_owned: impl Fn(&K) -> O,
+    ) -> Self {
+        let mut added: Vec<_> = new
+            .keys()
+            .filter(|key| !old.contains_key(*key))
+            .map(&to_owned)
+            .collect();
+        added.sort();
+        let mut removed: Vec<_> = old
+            .keys()
+            .filter(|key| !new.contains_key(*key))
+            .map(&to_owned)
+            .collect();
+        removed.sort();
+        let common = old.keys().filter(|key| new.contains_key(*key)).collect();
+
+        Self {
+            added,
+            removed,
+            common,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::service::{HandlerSchemas, ServiceLocation};
+    use restate_schema_api::deployment::{DeploymentMetadata, ProtocolType};
+    use restate_schema_api::invocation_target::InvocationTargetMetadata;
+    use restate_types::invocation::{InvocationTargetType, ServiceType};
+    use std::time::Duration;
+
+    const GREETER: &str = "greeter.Greeter";
+
+    fn handler() -> HandlerSchemas {
+        HandlerSchemas {
+            target_meta: InvocationTargetMetadata {
+                public: true,
+                completion_retention: None,
+                idempotency_retention: Duration::from_secs(60),
+                journal_retention: None,
+                target_ty: InvocationTargetType::Service,
+                input_rules: Default::default(),
+                output_rules: Default::default(),
+            },
+        }
+    }
+
+    fn deployment() -> DeploymentSchemas {
+        DeploymentSchemas {
+            metadata: DeploymentMetadata::new_http(
+                "http://localhost:9080".parse().unwrap(),
+                ProtocolType::BidiStream,
+                Default::default(),
+                1..=1,
+            ),
+            services: vec![],
+        }
+    }
+
+    /// Schema with the greeter service, which has the given handlers.
+    fn schema_with_greeter(handlers: &[&str]) -> (Schema, DeploymentId) {
+        let deployment_id = DeploymentId::new();
+        let greeter = ServiceSchemas {
+            revision: 1,
+            handlers: handlers
+                .iter()
+                .map(|name| (name.to_string(), handler()))
+                .collect(),
+            ty: ServiceType::Service,
+            location: ServiceLocation {
+                latest_deployment: deployment_id,
+                public: true,
+            },
+            idempotency_retention: Duration::from_secs(60),
+            workflow_completion_retention: None,
+        };
+
+        let schema = Schema {
+            services: HashMap::from([(GREETER.to_owned(), greeter)]),
+            deployments: HashMap::from([(deployment_id, deployment())]),
+            ..Schema::default()
+        };
+        (schema, deployment_id)
+    }
+
+    #[test]
+    fn identical_schemas_have_no_diff() {
+        let (schema, _) = schema_with_greeter(&["greet"]);
+        assert!(schema.diff(&schema).is_empty());
+    }
+
+    #[test]
+    fn added_removed_and_changed_handlers() {
+        let (old_schema, _) = schema_with_greeter(&["greet", "farewell", "count"]);
+
+        let mut new_schema = old_schema.clone();
+        let greeter = new_schema.services.get_mut(GREETER).unwrap();
+        greeter.handlers.remove("farewell");
+        greeter.handlers.insert("welcome".to_owned(), handler());
+        greeter.handlers.insert("hello".to_owned(), handler());
+        greeter
+            .handlers
+            .get_mut("count")
+            .unwrap()
+            .target_meta
+            .public = false;
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(
+            diff.changed_services,
+            vec![ServiceDiff {
+                name: GREETER.to_owned(),
+                added_handlers: vec!["hello".to_owned(), "welcome".to_owned()],
+                removed_handlers: vec!["farewell".to_owned()],
+                changed_handlers: vec!["count".to_owned()],
+                ..ServiceDiff::default()
+            }]
+        );
+        assert!(diff.added_services.is_empty());
+        assert!(diff.removed_services.is_empty());
+    }
+
+    #[test]
+    fn changed_service_type_and_retention() {
+        let (old_schema, _) = schema_with_greeter(&["greet"]);
+
+        let mut new_schema = old_schema.clone();
+        new_schema.services.get_mut(GREETER).unwrap().ty = ServiceType::VirtualObject;
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(
+            diff.changed_services,
+            vec![ServiceDiff {
+                name: GREETER.to_owned(),
+                type_changed: true,
+                ..ServiceDiff::default()
+            }]
+        );
+
+        let mut new_schema = old_schema.clone();
+        new_schema
+            .services
+            .get_mut(GREETER)
+            .unwrap()
+            .idempotency_retention = Duration::from_secs(120);
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(
+            diff.changed_services,
+            vec![ServiceDiff {
+                name: GREETER.to_owned(),
+                retention_changed: true,
+                ..ServiceDiff::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn changed_deployments() {
+        let (old_schema, deployment_id) = schema_with_greeter(&["greet"]);
+
+        let mut new_schema = old_schema.clone();
+        new_schema
+            .deployments
+            .get_mut(&deployment_id)
+            .unwrap()
+            .metadata
+            .supported_protocol_versions = 1..=2;
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.changed_deployments, vec![deployment_id]);
+        assert!(diff.added_deployments.is_empty());
+        assert!(diff.removed_deployments.is_empty());
+        assert!(diff.changed_services.is_empty());
+    }
+}
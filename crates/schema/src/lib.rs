@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use arc_swap::ArcSwap;
+use bytes::Bytes;
 use restate_schema_api::deployment::DeploymentType;
 use restate_schema_api::service::{HandlerMetadata, ServiceMetadata};
 use restate_schema_api::subscription::Subscription;
@@ -18,6 +19,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 pub mod deployment;
+pub mod diff;
 mod invocation_target;
 pub mod service;
 mod subscriptions;
@@ -35,7 +37,7 @@ pub struct UpdateableSchema(Arc<ArcSwap<Schema>>);
 
 /// The schema information
 #[serde_as]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Schema {
     pub version: Version,
     pub services: HashMap<String, ServiceSchemas>,
@@ -89,6 +91,28 @@ impl Schema {
     }
 }
 
+/// Serialized snapshot of the whole [`Schema`], used to back it up and to restore it on another
+/// cluster.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::From, derive_more::Into)]
+pub struct SerializedSchema(Bytes);
+
+impl SerializedSchema {
+    pub fn deserialize(&self) -> Result<Schema, flexbuffers::DeserializationError> {
+        flexbuffers::from_slice(&self.0)
+    }
+}
+
+impl Schema {
+    /// Exports the deployments, services and subscriptions of this schema.
+    pub fn export(&self) -> SerializedSchema {
+        SerializedSchema(
+            flexbuffers::to_vec(self)
+                .expect("schema to be serializable")
+                .into(),
+        )
+    }
+}
+
 impl Versioned for Schema {
     fn version(&self) -> Version {
         self.version
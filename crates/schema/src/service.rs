@@ -15,12 +15,12 @@ use restate_schema_api::invocation_target::InvocationTargetMetadata;
 use restate_schema_api::service::ServiceMetadataResolver;
 use restate_types::invocation::ServiceType;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct HandlerSchemas {
     pub target_meta: InvocationTargetMetadata,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ServiceSchemas {
     pub revision: ServiceRevision,
     pub handlers: HashMap<String, HandlerSchemas>,
@@ -54,7 +54,7 @@ impl ServiceSchemas {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ServiceLocation {
     pub latest_deployment: DeploymentId,
     pub public: bool,
@@ -21,7 +21,8 @@ impl SubscriptionResolver for Schema {
     }
 
     fn list_subscriptions(&self, filters: &[ListSubscriptionFilter]) -> Vec<Subscription> {
-        self.subscriptions
+        let mut subscriptions: Vec<_> = self
+            .subscriptions
             .values()
             .filter(|sub| {
                 for f in filters {
@@ -32,7 +33,10 @@ impl SubscriptionResolver for Schema {
                 true
             })
             .cloned()
-            .collect()
+            .collect();
+        // sort by id for a stable output
+        subscriptions.sort_by_key(|sub| sub.id());
+        subscriptions
     }
 }
 
@@ -14,11 +14,15 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use codederror::CodedError;
+use datafusion::common::ScalarValue;
 use datafusion::error::DataFusionError;
 use datafusion::execution::context::{SQLOptions, SessionState};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::prelude::{SessionConfig, SessionContext};
+use futures::TryStreamExt;
+use metrics::counter;
 
 use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_invoker_api::StatusHandle;
@@ -29,7 +33,8 @@ use restate_types::config::QueryEngineOptions;
 use restate_types::errors::GenericError;
 use restate_types::identifiers::PartitionId;
 
-use crate::{analyzer, physical_optimizer};
+use crate::metric_definitions::QUERY_MEMORY_LIMIT_EXCEEDED;
+use crate::{analyzer, metric_definitions, physical_optimizer};
 
 const SYS_INVOCATION_VIEW: &str = "CREATE VIEW sys_invocation as SELECT
             ss.id,
@@ -102,6 +107,8 @@ impl QueryContext {
             + Clone
             + 'static,
     ) -> Result<QueryContext, BuildError> {
+        metric_definitions::describe_metrics();
+
         let ctx = QueryContext::new(
             options.memory_size.get(),
             options.tmp_dir.clone(),
@@ -206,16 +213,62 @@ impl QueryContext {
     pub async fn execute(
         &self,
         sql: &str,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        self.execute_with_params(sql, Vec::new()).await
+    }
+
+    /// Executes the query after binding the given values to its positional placeholders
+    /// (`$1`, `$2`, ...). Values are cast to the type expected by their placeholder.
+    pub async fn execute_with_params(
+        &self,
+        sql: &str,
+        params: Vec<ScalarValue>,
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
         let state = self.datafusion_context.state();
         let statement = state.sql_to_statement(sql, "postgres")?;
-        let plan = state.statement_to_plan(statement).await?;
+        let mut plan = state.statement_to_plan(statement).await?;
         self.sql_options.verify_plan(&plan)?;
+        if !params.is_empty() {
+            let param_types = plan.get_parameter_types()?;
+            let params = params
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(idx, value)| match param_types.get(&format!("${}", idx + 1)) {
+                        Some(Some(data_type)) if value.data_type() != *data_type => {
+                            value.cast_to(data_type)
+                        }
+                        _ => Ok(value),
+                    },
+                )
+                .collect::<datafusion::common::Result<Vec<_>>>()?;
+            plan = plan.with_param_values(params)?;
+        }
         let df = self.datafusion_context.execute_logical_plan(plan).await?;
-        df.execute_stream().await
+        let stream = df.execute_stream().await.map_err(observe_query_error)?;
+
+        // memory pool exhaustion usually happens while polling the stream (e.g. sorts or
+        // aggregations buffering their input), hence we need to observe the stream errors too
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            stream.schema(),
+            stream.map_err(observe_query_error),
+        )))
     }
 }
 
+/// Returns true if the query failed because it exceeded the configured memory limit of the
+/// query engine.
+pub fn is_memory_limit_exceeded(err: &DataFusionError) -> bool {
+    matches!(err.find_root(), DataFusionError::ResourcesExhausted(_))
+}
+
+fn observe_query_error(err: DataFusionError) -> DataFusionError {
+    if is_memory_limit_exceeded(&err) {
+        counter!(QUERY_MEMORY_LIMIT_EXCEEDED).increment(1);
+    }
+    err
+}
+
 impl AsRef<SessionContext> for QueryContext {
     fn as_ref(&self) -> &SessionContext {
         &self.datafusion_context
@@ -228,3 +281,48 @@ impl SelectPartitions for ProcessorsManagerHandle {
         Ok(self.get_live_partitions().await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::array::{ArrayRef, Int64Array};
+    use datafusion::arrow::record_batch::RecordBatch;
+
+    #[tokio::test]
+    async fn exceeding_the_memory_limit_fails_the_query() {
+        let ctx = QueryContext::new(1024, None, None);
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(0..100_000));
+        ctx.datafusion_context
+            .register_batch(
+                "numbers",
+                RecordBatch::try_from_iter([("value", values)]).unwrap(),
+            )
+            .unwrap();
+
+        // the hash table of a join can't be spilled to disk
+        let result = match ctx
+            .execute("SELECT a.value FROM numbers a JOIN numbers b ON a.value = b.value")
+            .await
+        {
+            Ok(stream) => stream.try_collect::<Vec<_>>().await.map(|_| ()),
+            Err(err) => Err(err),
+        };
+
+        let err = result.expect_err("query should exceed the memory limit");
+        assert!(is_memory_limit_exceeded(&err), "{err}");
+    }
+
+    #[test]
+    fn other_errors_are_not_memory_limit_exceeded() {
+        assert!(!is_memory_limit_exceeded(&DataFusionError::Plan(
+            "unknown table".to_owned()
+        )));
+        assert!(is_memory_limit_exceeded(&DataFusionError::Context(
+            "while executing the query".to_owned(),
+            Box::new(DataFusionError::ResourcesExhausted(
+                "memory pool exhausted".to_owned()
+            ))
+        )));
+    }
+}
@@ -17,6 +17,8 @@ mod invocation_state;
 mod invocation_status;
 mod journal;
 mod keyed_service_status;
+mod metric_definitions;
+pub mod partition_stats;
 mod partition_store_scanner;
 mod physical_optimizer;
 mod promise;
@@ -26,6 +28,7 @@ mod state;
 pub mod table_docs;
 mod table_macro;
 mod table_providers;
+pub mod table_schema;
 mod table_util;
 
 pub use context::BuildError;
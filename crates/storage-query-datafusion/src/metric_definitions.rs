@@ -0,0 +1,23 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+/// Optional to have but adds description/help message to the metrics emitted to
+/// the metrics' sink.
+use metrics::{describe_counter, Unit};
+
+pub const QUERY_MEMORY_LIMIT_EXCEEDED: &str = "restate.storage_query.memory_limit_exceeded.total";
+
+pub(crate) fn describe_metrics() {
+    describe_counter!(
+        QUERY_MEMORY_LIMIT_EXCEEDED,
+        Unit::Count,
+        "Number of storage queries that failed because they exceeded the query engine memory limit"
+    );
+}
@@ -15,6 +15,7 @@ use super::context::QueryContext;
 use async_trait::async_trait;
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::ScalarValue;
 use datafusion::execution::SendableRecordBatchStream;
 use googletest::matcher::{Matcher, MatcherResult};
 use restate_core::task_center;
@@ -153,6 +154,14 @@ impl MockQueryEngine {
     ) -> datafusion::common::Result<SendableRecordBatchStream> {
         self.2.execute(sql).await
     }
+
+    pub async fn execute_with_params(
+        &self,
+        sql: &str,
+        params: Vec<ScalarValue>,
+    ) -> datafusion::common::Result<SendableRecordBatchStream> {
+        self.2.execute_with_params(sql, params).await
+    }
 }
 
 // --- Matchers for rows
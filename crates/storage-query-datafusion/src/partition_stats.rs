@@ -0,0 +1,100 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, UInt64Type};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
+use restate_types::identifiers::PartitionId;
+use restate_types::partition_table::FixedPartitionTable;
+
+/// Tables whose rows are stored in the partitions.
+pub const PARTITIONED_TABLES: &[&str] = &[
+    "state",
+    "sys_idempotency",
+    "sys_inbox",
+    "sys_invocation_status",
+    "sys_journal",
+    "sys_keyed_service_status",
+    "sys_promise",
+];
+
+/// Number of rows per table, keyed by partition.
+pub type PartitionRowCounts = BTreeMap<PartitionId, BTreeMap<String, u64>>;
+
+/// Query counting the rows of the [`PARTITIONED_TABLES`] per partition of the given partition
+/// table. The result has the columns `partition_id`, `table_name` and `num_rows`, and only
+/// contains tables with rows.
+pub fn partition_stats_query(partition_table: &FixedPartitionTable) -> String {
+    // the partitions cover consecutive ranges of the partition keys
+    let mut partition_id = String::from("CASE");
+    let mut last_partition_id = PartitionId::MIN;
+    for (id, range) in partition_table.partitioner() {
+        write!(
+            partition_id,
+            " WHEN partition_key <= {} THEN {id}",
+            range.end()
+        )
+        .expect("writing to a string to succeed");
+        last_partition_id = id;
+    }
+    write!(partition_id, " ELSE {last_partition_id} END").expect("writing to a string to succeed");
+
+    let rows = PARTITIONED_TABLES
+        .iter()
+        .map(|table| {
+            format!("SELECT {partition_id} AS partition_id, '{table}' AS table_name FROM {table}")
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    format!(
+        "SELECT partition_id, table_name, COUNT(*) AS num_rows FROM ({rows}) \
+        GROUP BY partition_id, table_name"
+    )
+}
+
+/// Adds the row counts of a record batch returned by the [`partition_stats_query`].
+pub fn collect_partition_stats(
+    record_batch: &RecordBatch,
+    row_counts: &mut PartitionRowCounts,
+) -> Result<(), ArrowError> {
+    let column = |name: &str, data_type: &DataType| -> Result<ArrayRef, ArrowError> {
+        let column = record_batch
+            .column_by_name(name)
+            .ok_or_else(|| ArrowError::SchemaError(format!("missing column '{name}'")))?;
+        cast(column, data_type)
+    };
+
+    let partition_ids = column("partition_id", &DataType::UInt64)?;
+    let partition_ids = partition_ids.as_primitive::<UInt64Type>();
+    let table_names = column("table_name", &DataType::Utf8)?;
+    let table_names = table_names.as_string::<i32>();
+    let num_rows = column("num_rows", &DataType::UInt64)?;
+    let num_rows = num_rows.as_primitive::<UInt64Type>();
+
+    for row in 0..record_batch.num_rows() {
+        if partition_ids.is_null(row) || table_names.is_null(row) {
+            continue;
+        }
+
+        *row_counts
+            .entry(PartitionId::from(partition_ids.value(row)))
+            .or_default()
+            .entry(table_names.value(row).to_owned())
+            .or_default() += num_rows.value(row);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2024 - Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+
+use datafusion::arrow::array::{ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
+
+/// Query listing the columns of all tables and views which can be queried, in the order of their
+/// definition. The result has the columns `table_name`, `column_name`, `data_type` and
+/// `is_nullable`.
+pub const TABLE_SCHEMA_QUERY: &str = "SELECT table_name, column_name, data_type, is_nullable \
+    FROM information_schema.columns WHERE table_schema = 'public' \
+    ORDER BY table_name, ordinal_position";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// Columns per table, keyed by table name.
+pub type TableSchemas = BTreeMap<String, Vec<ColumnSchema>>;
+
+/// Adds the columns of a record batch returned by the [`TABLE_SCHEMA_QUERY`].
+pub fn collect_table_schemas(
+    record_batch: &RecordBatch,
+    table_schemas: &mut TableSchemas,
+) -> Result<(), ArrowError> {
+    let column = |name: &str| -> Result<ArrayRef, ArrowError> {
+        let column = record_batch
+            .column_by_name(name)
+            .ok_or_else(|| ArrowError::SchemaError(format!("missing column '{name}'")))?;
+        cast(column, &DataType::Utf8)
+    };
+
+    let table_names = column("table_name")?;
+    let table_names = table_names.as_string::<i32>();
+    let column_names = column("column_name")?;
+    let column_names = column_names.as_string::<i32>();
+    let data_types = column("data_type")?;
+    let data_types = data_types.as_string::<i32>();
+    let is_nullable = column("is_nullable")?;
+    let is_nullable = is_nullable.as_string::<i32>();
+
+    for row in 0..record_batch.num_rows() {
+        table_schemas
+            .entry(table_names.value(row).to_owned())
+            .or_default()
+            .push(ColumnSchema {
+                name: column_names.value(row).to_owned(),
+                data_type: data_types.value(row).to_owned(),
+                nullable: is_nullable.value(row) == "YES",
+            });
+    }
+
+    Ok(())
+}
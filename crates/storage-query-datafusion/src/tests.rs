@@ -9,9 +9,12 @@
 // by the Apache License, Version 2.0.
 
 use crate::mocks::*;
+use crate::partition_stats::{collect_partition_stats, partition_stats_query, PartitionRowCounts};
 use crate::row;
-use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use crate::table_schema::{collect_table_schemas, ColumnSchema, TableSchemas, TABLE_SCHEMA_QUERY};
+use datafusion::arrow::array::{AsArray, LargeStringArray, UInt64Array};
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::ScalarValue;
 use futures::StreamExt;
 use googletest::all;
 use googletest::prelude::{assert_that, eq};
@@ -22,13 +25,17 @@ use restate_invoker_api::{InvocationErrorReport, InvocationStatusReport};
 use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, InvocationStatusTable,
 };
+use restate_storage_api::state_table::StateTable;
 use restate_storage_api::Transaction;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::LeaderEpoch;
 use restate_types::identifiers::PartitionId;
-use restate_types::identifiers::{DeploymentId, InvocationId};
+use restate_types::identifiers::{DeploymentId, InvocationId, PartitionKey, ServiceId};
 use restate_types::invocation::InvocationTarget;
 use restate_types::journal::EntryType;
+use restate_types::partition_table::FixedPartitionTable;
+use restate_types::Version;
+use std::collections::BTreeMap;
 use std::time::{Duration, SystemTime};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -120,3 +127,168 @@ async fn query_sys_invocation() {
         ))
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn partition_stats_count_rows_per_partition() {
+    let tc = TaskCenterBuilder::default()
+        .default_runtime_handle(tokio::runtime::Handle::current())
+        .build()
+        .expect("task_center builds");
+    let mut engine = tc
+        .run_in_scope("mock-query-engine", None, MockQueryEngine::create())
+        .await;
+
+    // two rows in the first and one row in the second partition
+    let partition_table = FixedPartitionTable::new(Version::MIN, 2);
+    let mut tx = engine.partition_store().transaction();
+    for (partition_key, key) in [(0, "a"), (1, "b"), (PartitionKey::MAX, "c")] {
+        tx.put_user_state(
+            &ServiceId::with_partition_key(partition_key, "MySvc", key),
+            b"my-key",
+            b"my-value",
+        )
+        .await;
+    }
+    tx.commit().await.unwrap();
+
+    let mut records = engine
+        .execute(&partition_stats_query(&partition_table))
+        .await
+        .unwrap();
+    let mut row_counts = PartitionRowCounts::new();
+    while let Some(record_batch) = records.next().await {
+        collect_partition_stats(&record_batch.unwrap(), &mut row_counts).unwrap();
+    }
+
+    assert_eq!(
+        row_counts,
+        BTreeMap::from([
+            (
+                PartitionId::from(0),
+                BTreeMap::from([("state".to_owned(), 2)])
+            ),
+            (
+                PartitionId::from(1),
+                BTreeMap::from([("state".to_owned(), 1)])
+            ),
+        ])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_with_string_parameter_containing_quotes() {
+    let tc = TaskCenterBuilder::default()
+        .default_runtime_handle(tokio::runtime::Handle::current())
+        .build()
+        .expect("task_center builds");
+    let mut engine = tc
+        .run_in_scope("mock-query-engine", None, MockQueryEngine::create())
+        .await;
+
+    let service_key = r#"it's a "key"'; DROP TABLE state; --"#;
+    let mut tx = engine.partition_store().transaction();
+    for key in [service_key, "other-key"] {
+        tx.put_user_state(&ServiceId::new("MySvc", key), b"my-key", b"my-value")
+            .await;
+    }
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute_with_params(
+            "SELECT service_key FROM state WHERE service_key = $1",
+            vec![ScalarValue::from(service_key)],
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 1);
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "service_key" => LargeStringArray: eq(service_key),
+            }
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn explain_returns_query_plan() {
+    let tc = TaskCenterBuilder::default()
+        .default_runtime_handle(tokio::runtime::Handle::current())
+        .build()
+        .expect("task_center builds");
+    let engine = tc
+        .run_in_scope("mock-query-engine", None, MockQueryEngine::create())
+        .await;
+
+    let records = engine
+        .execute("EXPLAIN SELECT service_key FROM state WHERE service_name = 'MySvc'")
+        .await
+        .unwrap()
+        .collect::<Vec<Result<RecordBatch, _>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    let column_names: Vec<_> = records
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect();
+    assert_eq!(column_names, vec!["plan_type", "plan"]);
+    let plan_types = records.column(0).as_string::<i32>();
+    let plan_types: Vec<_> = plan_types.iter().flatten().collect();
+    assert_eq!(plan_types, vec!["logical_plan", "physical_plan"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn table_schemas_list_columns_of_tables() {
+    let tc = TaskCenterBuilder::default()
+        .default_runtime_handle(tokio::runtime::Handle::current())
+        .build()
+        .expect("task_center builds");
+    let engine = tc
+        .run_in_scope("mock-query-engine", None, MockQueryEngine::create())
+        .await;
+
+    let mut records = engine.execute(TABLE_SCHEMA_QUERY).await.unwrap();
+    let mut table_schemas = TableSchemas::new();
+    while let Some(record_batch) = records.next().await {
+        collect_table_schemas(&record_batch.unwrap(), &mut table_schemas).unwrap();
+    }
+
+    let invocation_status = table_schemas
+        .get("sys_invocation_status")
+        .expect("sys_invocation_status table to be listed");
+    assert_eq!(
+        invocation_status[..3],
+        [
+            ColumnSchema {
+                name: "partition_key".to_owned(),
+                data_type: "UInt64".to_owned(),
+                nullable: true,
+            },
+            ColumnSchema {
+                name: "id".to_owned(),
+                data_type: "LargeUtf8".to_owned(),
+                nullable: true,
+            },
+            ColumnSchema {
+                name: "status".to_owned(),
+                data_type: "LargeUtf8".to_owned(),
+                nullable: true,
+            },
+        ]
+    );
+    // views can be queried as well
+    assert!(table_schemas.contains_key("sys_invocation"));
+    // the information schema is not part of the storage tables
+    assert!(!table_schemas.contains_key("columns"));
+}
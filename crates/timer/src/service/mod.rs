@@ -155,6 +155,12 @@ where
         }
     }
 
+    /// Number of timers that are currently kept in memory. Timers which are only stored in the
+    /// timer reader's storage are not included.
+    pub fn num_timers_in_memory(&self) -> usize {
+        self.timer_queue.len()
+    }
+
     pub fn add_timer(self: Pin<&mut Self>, timer: Timer) {
         let this = self.project();
         let timer_queue = this.timer_queue;
@@ -471,3 +471,42 @@ async fn delete_loading_timer() {
         TimerValue::new(2, MillisSinceEpoch::from(2))
     );
 }
+
+#[test(tokio::test)]
+async fn restarted_service_reloads_timers_from_reader() {
+    let mut clock = ManualClock::new(MillisSinceEpoch::UNIX_EPOCH);
+    let timer_reader = MockTimerReader::<TimerValue>::new();
+    let num_timers = 10;
+
+    for i in 0..num_timers {
+        timer_reader.add_timer(TimerValue::new(i, i.into()));
+    }
+
+    {
+        let service = TimerService::new(clock.clone(), Some(2), timer_reader.clone());
+        tokio::pin!(service);
+
+        clock.advance_time_to(MillisSinceEpoch::new(1));
+
+        // fired timers are deleted from the storage once they have been processed
+        for i in 0..2 {
+            let timer = service.as_mut().next_timer().await;
+            assert_eq!(timer, TimerValue::new(i, i.into()));
+            timer_reader.remove_timer(timer);
+        }
+        assert!(service.num_timers_in_memory() <= 2);
+    }
+
+    // a new service must pick up the remaining timers from the reader
+    let service = TimerService::new(clock.clone(), Some(2), timer_reader);
+    tokio::pin!(service);
+
+    clock.advance_time_to(MillisSinceEpoch::new(num_timers - 1));
+
+    for i in 2..num_timers {
+        assert_eq!(
+            service.as_mut().next_timer().await,
+            TimerValue::new(i, i.into())
+        );
+    }
+}
@@ -231,6 +231,11 @@ impl TracingGuard {
         self.is_dropped = true;
     }
 
+    /// Returns a handle that allows changing the log filter while the process is running.
+    pub fn log_filter_handle(&self) -> LogFilterHandle {
+        LogFilterHandle(self.reload_handle.clone())
+    }
+
     pub fn reload_log_filter(&self, common_opts: &CommonOptions) {
         info!("Setting log filter to '{}'", common_opts.log_filter);
         let _ = &self.reload_handle.modify(|f| {
@@ -252,6 +257,33 @@ impl TracingGuard {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum SetLogFilterError {
+    #[error("invalid log filter: {0}")]
+    InvalidFilter(#[from] ParseError),
+    #[error("could not reload log filter: {0}")]
+    Reload(#[from] tracing_subscriber::reload::Error),
+}
+
+/// Cloneable handle to the log filter installed by [`init_tracing_and_logging`].
+#[derive(Debug, Clone)]
+pub struct LogFilterHandle(Handle<EnvFilter, Registry>);
+
+impl LogFilterHandle {
+    /// Replaces the active log filter with the given [`EnvFilter`] directives and returns the
+    /// directives of the previously active filter. Malformed directives leave the active filter
+    /// untouched.
+    pub fn set_log_filter(&self, directives: &str) -> Result<String, SetLogFilterError> {
+        let new_filter = EnvFilter::try_new(directives)?;
+        let mut previous_filter = String::new();
+        // don't use logging in the closure, tracing will panic!
+        self.0.modify(|filter| {
+            previous_filter = std::mem::replace(filter, new_filter).to_string();
+        })?;
+        Ok(previous_filter)
+    }
+}
+
 impl Drop for TracingGuard {
     fn drop(&mut self) {
         if !self.is_dropped {
@@ -278,3 +310,23 @@ impl Drop for TracingGuard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_log_filter_returns_previous_filter() {
+        let (filter, reload_handle) =
+            tracing_subscriber::reload::Layer::new(EnvFilter::try_new("info").unwrap());
+        let _subscriber = tracing_subscriber::registry().with(filter);
+        let handle = LogFilterHandle(reload_handle);
+
+        assert_eq!(handle.set_log_filter("restate=debug").unwrap(), "info");
+        assert!(matches!(
+            handle.set_log_filter("restate=not_a_level"),
+            Err(SetLogFilterError::InvalidFilter(_))
+        ));
+        assert_eq!(handle.set_log_filter("warn").unwrap(), "restate=debug");
+    }
+}
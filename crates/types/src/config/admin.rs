@@ -10,13 +10,14 @@
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::net::SocketAddr;
+use std::net::IpAddr;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::Semaphore;
 
 use super::QueryEngineOptions;
+use crate::net::BindAddress;
 
 /// # Admin server options
 #[serde_as]
@@ -28,8 +29,33 @@ use super::QueryEngineOptions;
 pub struct AdminOptions {
     /// # Endpoint address
     ///
-    /// Address to bind for the Admin APIs.
-    pub bind_address: SocketAddr,
+    /// Address to bind for the Admin APIs. Use the `unix:` prefix to bind to a unix domain
+    /// socket, e.g. `unix:/var/run/restate/admin.sock`.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub bind_address: BindAddress,
+
+    /// # Base path
+    ///
+    /// Path prefix under which the Admin APIs are served, e.g. `/restate/admin`. Useful when the
+    /// Admin APIs are exposed through a gateway. Default is the root path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+
+    /// # Local node host
+    ///
+    /// Host under which the Admin server reaches the node it is running on, if the node is bound
+    /// to an unspecified address such as `0.0.0.0`. Set this on hosts with multiple network
+    /// interfaces where the node isn't reachable via the loopback address. Default is the
+    /// loopback address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_node_host: Option<IpAddr>,
+
+    /// # CORS
+    ///
+    /// If set, browsers are allowed to call the Admin APIs from the configured origins.
+    /// Otherwise, only same-origin requests are allowed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsOptions>,
 
     /// # Concurrency limit
     ///
@@ -58,6 +84,25 @@ pub struct AdminOptions {
     /// can remove equal or more entries than this threshold. This prevents too many small trim
     /// operations.
     pub log_trim_threshold: u64,
+
+    /// # Default idempotency retention
+    ///
+    /// Retention of the idempotency records of completed invocations, applied to newly registered
+    /// services. Once it expires, the idempotency record is purged in the background and a retried
+    /// request with the same idempotency key is executed again. A too short retention risks duplicate
+    /// executions of slowly retried requests, a too long retention keeps records around which are
+    /// unlikely to be used. The retention can be overridden per service.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub default_idempotency_retention: humantime::Duration,
+
+    /// # Max handlers per service
+    ///
+    /// Maximum number of handlers a single service can expose. Registering a deployment with a
+    /// service exceeding this limit is rejected.
+    pub max_handlers_per_service: NonZeroUsize,
 }
 
 impl AdminOptions {
@@ -65,6 +110,13 @@ impl AdminOptions {
         super::data_dir("registry")
     }
 
+    /// The configured base path without trailing slashes, or `None` if the Admin APIs are served
+    /// under the root path.
+    pub fn base_path(&self) -> Option<String> {
+        let base_path = self.base_path.as_deref()?.trim_matches('/');
+        (!base_path.is_empty()).then(|| format!("/{base_path}"))
+    }
+
     pub fn concurrent_api_requests_limit(&self) -> usize {
         std::cmp::min(
             self.concurrent_api_requests_limit
@@ -79,6 +131,9 @@ impl Default for AdminOptions {
     fn default() -> Self {
         Self {
             bind_address: "0.0.0.0:9070".parse().unwrap(),
+            base_path: None,
+            local_node_host: None,
+            cors: None,
             // max is limited by Tower's LoadShedLayer.
             concurrent_api_requests_limit: None,
             query_engine: Default::default(),
@@ -86,6 +141,35 @@ impl Default for AdminOptions {
             // try to trim the log every hour
             log_trim_interval: Some(Duration::from_secs(60 * 60).into()),
             log_trim_threshold: 1000,
+            default_idempotency_retention: Duration::from_secs(60 * 60 * 24).into(),
+            max_handlers_per_service: NonZeroUsize::new(1000).unwrap(),
         }
     }
 }
+
+/// # CORS options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "CorsOptions", default))]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CorsOptions {
+    /// # Allowed origins
+    ///
+    /// Origins which are allowed to make cross-origin requests, e.g. `https://example.com`.
+    /// Use `*` to allow any origin.
+    pub allowed_origins: Vec<String>,
+
+    /// # Allowed methods
+    ///
+    /// Methods which are allowed in cross-origin requests. Default is `GET`, `POST`, `PUT`,
+    /// `PATCH` and `DELETE`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_methods: Vec<String>,
+
+    /// # Allowed headers
+    ///
+    /// Request headers which are allowed in cross-origin requests. Use `*` to allow any header.
+    /// Default is `content-type`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_headers: Vec<String>,
+}
@@ -22,6 +22,7 @@ use restate_serde_util::NonZeroByteCount;
 
 use crate::net::{AdvertisedAddress, BindAddress};
 use crate::nodes_config::Role;
+use crate::retries::RetryPolicy;
 use crate::PlainNodeId;
 
 use super::{AwsOptions, HttpOptions, PerfStatsLevel, RocksDbOptions};
@@ -67,14 +68,97 @@ pub struct CommonOptions {
     /// Address of the metadata store server to bootstrap the node from.
     pub metadata_store_address: AdvertisedAddress,
 
+    /// # Metadata store retry policy
+    ///
+    /// Retry policy for reaching the metadata store while the node starts, i.e. when registering
+    /// the node and fetching the cluster metadata. Only network errors are retried, so that the
+    /// node can be started before the metadata store is reachable.
+    pub metadata_store_retry_policy: RetryPolicy,
+
     /// Address to bind for the Node server. Default is `0.0.0.0:5122`
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub bind_address: BindAddress,
 
+    /// # Additional bind addresses
+    ///
+    /// Further addresses the Node server binds to in addition to `bind-address`, e.g. to listen
+    /// on both an IPv4 and an IPv6 address on dual-stack hosts. Default is empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<String>"))]
+    pub additional_bind_addresses: Vec<BindAddress>,
+
     /// Address that other nodes will use to connect to this node. Default is `http://127.0.0.1:5122/`
     #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub advertised_address: AdvertisedAddress,
 
+    /// # Request tracing level
+    ///
+    /// Level of the spans created for the requests served by the node server.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub request_tracing_level: tracing::Level,
+
+    /// # Request tracing include headers
+    ///
+    /// Whether to record the request headers in the spans of the requests served by the node server.
+    /// Disable it if the headers might contain sensitive information.
+    pub request_tracing_include_headers: bool,
+
+    /// # Server shutdown timeout
+    ///
+    /// Upper bound for draining the in-flight requests of the node server during shutdown. Once
+    /// it expires, the server is stopped forcibly.
+    ///
+    /// Can be configured using the [`humantime`](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub server_shutdown_timeout: Duration,
+
+    /// # Disable gRPC reflection
+    ///
+    /// Don't expose the gRPC reflection service on the node server. Default is `false`.
+    pub disable_grpc_reflection: bool,
+
+    /// # Enable gRPC-web
+    ///
+    /// Accept gRPC-web requests on the node server, e.g. from browser-based tooling which can't
+    /// speak gRPC. Default is `false`.
+    pub enable_grpc_web: bool,
+
+    /// # Enable access log
+    ///
+    /// Log one event per request to the HTTP endpoints of the node server, such as `/metrics`,
+    /// with its method, path, status and latency. The events are logged at `INFO` level with the
+    /// target `restate::access_log`. Default is `false`.
+    pub enable_access_log: bool,
+
+    /// # Authentication
+    ///
+    /// If set, requests to the Admin APIs, to the HTTP endpoints of the node server, such as
+    /// `/metrics`, and to the gRPC services of the node server are rejected unless they are
+    /// authenticated. The nodes present the same token when calling each other, so all nodes of
+    /// a cluster must be configured with the same token. Default is no authentication.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthOptions>,
+
+    /// # TLS
+    ///
+    /// If set, the Node server is served over TLS on its socket bind addresses, using the given
+    /// certificate and private key. Unix domain sockets stay plain. The nodes then have to
+    /// advertise their address with the `https` scheme, and the certificate has to be trusted by
+    /// the system's root certificates of the other nodes. Otherwise, the Node server is served
+    /// over plain HTTP.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsOptions>,
+
+    /// # Max request size
+    ///
+    /// Maximum size of the requests accepted by the node server, both for the HTTP and the gRPC
+    /// services. Larger requests are rejected. Default is `32 MiB`.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub max_request_size: NonZeroUsize,
+
     /// # Partitions
     ///
     /// Number of partitions that will be provisioned during cluster bootstrap,
@@ -329,8 +413,24 @@ impl Default for CommonOptions {
             metadata_store_address: "http://127.0.0.1:5123"
                 .parse()
                 .expect("valid metadata store address"),
+            metadata_store_retry_policy: RetryPolicy::exponential(
+                std::time::Duration::from_millis(10),
+                2.0,
+                Some(15),
+                Some(std::time::Duration::from_secs(5)),
+            ),
             bind_address: "0.0.0.0:5122".parse().unwrap(),
+            additional_bind_addresses: Vec::new(),
             advertised_address: AdvertisedAddress::from_str("http://127.0.0.1:5122/").unwrap(),
+            request_tracing_level: tracing::Level::ERROR,
+            request_tracing_include_headers: true,
+            server_shutdown_timeout: std::time::Duration::from_secs(10).into(),
+            disable_grpc_reflection: false,
+            enable_grpc_web: false,
+            enable_access_log: false,
+            auth: None,
+            tls: None,
+            max_request_size: NonZeroUsize::new(32 * 1024 * 1024).unwrap(),
             bootstrap_num_partitions: NonZeroU64::new(24).unwrap(),
             histogram_inactivity_timeout: None,
             disable_prometheus: false,
@@ -386,6 +486,55 @@ pub struct ServiceClientOptions {
     pub request_identity_private_key_pem_file: Option<PathBuf>,
 }
 
+/// # Authentication options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "AuthOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthOptions {
+    /// # Bearer token file
+    ///
+    /// Path to a file containing the token which clients have to send in the
+    /// `Authorization: Bearer <token>` header. Leading and trailing whitespace is ignored.
+    pub bearer_token_file: PathBuf,
+}
+
+/// # TLS options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "TlsOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsOptions {
+    /// # Certificate path
+    ///
+    /// Path to a PEM file containing the certificate chain, starting with the server certificate.
+    pub cert_path: PathBuf,
+
+    /// # Private key path
+    ///
+    /// Path to a PEM file containing the private key of the server certificate, either in PKCS#8,
+    /// PKCS#1 (RSA) or SEC1 (EC) format.
+    pub key_path: PathBuf,
+
+    /// # Client CA path
+    ///
+    /// Path to a PEM file containing the certificates of the CAs which may sign client
+    /// certificates. If set, clients are asked for a certificate during the TLS handshake, and
+    /// the verified certificate is passed on to the authentication of the request. Clients
+    /// without a certificate are still accepted. Default is not asking for client certificates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_path: Option<PathBuf>,
+
+    /// # CA path
+    ///
+    /// Path to a PEM file containing the certificates of the CAs which sign the server
+    /// certificates of the other nodes. They are trusted in addition to the system's root
+    /// certificates when connecting to other nodes over TLS. Default is trusting only the
+    /// system's root certificates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_path: Option<PathBuf>,
+}
+
 /// # Log format
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[derive(Debug, Clone, Copy, Hash, Default, Serialize, Deserialize)]
@@ -0,0 +1,88 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+
+use restate_serde_util::NonZeroByteCount;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// # Networking options
+///
+/// Common options for the node-to-node communication.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "NetworkingOptions", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct NetworkingOptions {
+    /// # Unknown message handling
+    ///
+    /// How to handle messages received from peers which this node does not understand, e.g.
+    /// messages sent by a peer running a newer version during a rolling upgrade.
+    pub unknown_message_handling: UnknownMessageHandling,
+
+    /// # Compression
+    ///
+    /// Compression algorithm for messages sent to peers. Messages are only compressed if the
+    /// peer supports the algorithm. Compression trades CPU for bandwidth, hence it's disabled
+    /// by default.
+    pub compression: MessageCompression,
+
+    /// # Compression threshold
+    ///
+    /// Messages with a smaller payload are sent uncompressed.
+    #[serde_as(as = "NonZeroByteCount")]
+    #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
+    pub compression_threshold: NonZeroUsize,
+}
+
+impl Default for NetworkingOptions {
+    fn default() -> Self {
+        Self {
+            unknown_message_handling: UnknownMessageHandling::default(),
+            compression: MessageCompression::default(),
+            compression_threshold: NonZeroUsize::new(4 * 1024).unwrap(),
+        }
+    }
+}
+
+/// # Message compression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum MessageCompression {
+    /// # None
+    ///
+    /// Messages are sent uncompressed.
+    #[default]
+    None,
+    /// # Zstd
+    Zstd,
+    /// # LZ4
+    Lz4,
+}
+
+/// # Unknown message handling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum UnknownMessageHandling {
+    /// # Drop
+    ///
+    /// Log and drop unknown messages, keeping the connection to the peer open.
+    #[default]
+    Drop,
+    /// # Disconnect
+    ///
+    /// Report a codec error to the peer and close the connection.
+    Disconnect,
+}
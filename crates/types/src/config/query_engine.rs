@@ -26,7 +26,9 @@ use restate_serde_util::NonZeroByteCount;
 pub struct QueryEngineOptions {
     /// # Memory size limit
     ///
-    /// The total memory in bytes that can be used to preform sql queries
+    /// The total memory in bytes that can be used to preform sql queries. Operators that
+    /// support it (e.g. sorts) spill to the temp folder when hitting this limit, others fail
+    /// the query with a resources exhausted error.
     #[cfg_attr(feature = "schemars", schemars(with = "NonZeroByteCount"))]
     #[serde_as(as = "NonZeroByteCount")]
     pub memory_size: NonZeroUsize,
@@ -45,6 +47,20 @@ pub struct QueryEngineOptions {
     ///
     /// The address to bind for the psql service.
     pub pgsql_bind_address: SocketAddr,
+
+    /// # Query timeout
+    ///
+    /// Maximum duration of a storage query issued via the Admin API. Requests can lower this
+    /// timeout, but never exceed it. Queries are not limited if unset.
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub query_timeout: Option<humantime::Duration>,
+
+    /// # Max rows
+    ///
+    /// Maximum number of rows a storage query issued via the Admin API can return. Requests can
+    /// lower this limit, but never exceed it. Results are not limited if unset.
+    pub max_rows: Option<NonZeroUsize>,
 }
 
 impl QueryEngineOptions {
@@ -59,6 +75,8 @@ impl Default for QueryEngineOptions {
             tmp_dir: None,
             query_parallelism: None,
             pgsql_bind_address: "0.0.0.0:9071".parse().unwrap(),
+            query_timeout: None,
+            max_rows: None,
         }
     }
 }
@@ -185,3 +185,16 @@ pub enum PerfStatsLevel {
     /// Enables count and time stats
     EnableTime,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "RocksDbCompression"))]
+#[serde(rename_all = "kebab-case")]
+pub enum CompressionType {
+    /// Don't compress the data blocks
+    None,
+    /// LZ4 compression, fast with a moderate compression ratio
+    Lz4,
+    /// Zstandard compression, slower with a high compression ratio
+    Zstd,
+}
@@ -18,7 +18,7 @@ use tracing::warn;
 
 use restate_serde_util::NonZeroByteCount;
 
-use super::{CommonOptions, RocksDbOptions, RocksDbOptionsBuilder};
+use super::{CommonOptions, CompressionType, RocksDbOptions, RocksDbOptionsBuilder};
 use crate::retries::RetryPolicy;
 
 /// # Worker options
@@ -37,6 +37,55 @@ pub struct WorkerOptions {
     /// The number of timers in memory limit is used to bound the amount of timers loaded in memory. If this limit is set, when exceeding it, the timers farther in the future will be spilled to disk.
     num_timers_in_memory_limit: Option<NonZeroUsize>,
 
+    /// # Shuffle channel size
+    ///
+    /// Size of the channel between the shuffle and the partition leader. Heavy outbox traffic might benefit from a larger channel. Defaults to the internal queue length.
+    shuffle_channel_size: Option<NonZeroUsize>,
+
+    /// # Action effects channel size
+    ///
+    /// Size of the channel through which the partition leader receives the effects of its own actions, e.g. scheduled cleanups. Defaults to the internal queue length.
+    action_effects_channel_size: Option<NonZeroUsize>,
+
+    /// # Invoker effects channel size
+    ///
+    /// Size of the channel through which the partition leader receives the effects of the invocations it runs. Defaults to the internal queue length.
+    invoker_effects_channel_size: Option<NonZeroUsize>,
+
+    /// # Processors manager channel size
+    ///
+    /// Size of the channel through which the partition processors manager receives commands, e.g. from the cluster controller. Defaults to the internal queue length.
+    processors_manager_channel_size: Option<NonZeroUsize>,
+
+    /// # Check partition key distribution
+    ///
+    /// If set, the worker counts the partition keys of the stored invocations at startup and reports how they are distributed across the partitions running on this node. A warning is logged if the distribution is heavily skewed. The check is read-only, but scans all stored invocations, and is disabled by default.
+    pub check_partition_key_distribution: bool,
+
+    /// # Orphaned timers
+    ///
+    /// How to handle timers which fire for a service that no longer exists, e.g. because its deployment has been removed.
+    ///
+    /// Dropping timers appends a log command which Restate versions that don't support this option can't read. Only choose `drop` once all nodes of the cluster have been upgraded, and don't downgrade afterwards.
+    pub orphaned_timers: OrphanedTimerBehavior,
+
+    /// # Invoker abort behavior
+    ///
+    /// What happens to the in-flight invocations of a partition leader which is asked to step down. If the leadership is taken over by another node, the in-flight invocations are always aborted right away because the leader can no longer record their progress.
+    pub invoker_abort_behavior: InvokerAbortBehavior,
+
+    /// # Proposal batch delay
+    ///
+    /// If set, the partition leader waits up to this long for further proposals before appending them to the log as a single batch. This improves the write throughput under high load at the cost of a higher latency. Proposal batching is disabled by default.
+    #[serde(with = "serde_with::As::<Option<serde_with::DisplayFromStr>>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    proposal_batch_delay: Option<humantime::Duration>,
+
+    /// # Proposal batch max size
+    ///
+    /// Maximum number of proposals which are appended to the log as a single batch if proposal batching is enabled.
+    proposal_batch_max_size: NonZeroUsize,
+
     pub storage: StorageOptions,
 
     pub invoker: InvokerOptions,
@@ -50,6 +99,38 @@ impl WorkerOptions {
     pub fn num_timers_in_memory_limit(&self) -> Option<usize> {
         self.num_timers_in_memory_limit.map(Into::into)
     }
+
+    pub fn shuffle_channel_size(&self) -> usize {
+        self.shuffle_channel_size
+            .unwrap_or(self.internal_queue_length)
+            .into()
+    }
+
+    pub fn action_effects_channel_size(&self) -> usize {
+        self.action_effects_channel_size
+            .unwrap_or(self.internal_queue_length)
+            .into()
+    }
+
+    pub fn invoker_effects_channel_size(&self) -> usize {
+        self.invoker_effects_channel_size
+            .unwrap_or(self.internal_queue_length)
+            .into()
+    }
+
+    pub fn processors_manager_channel_size(&self) -> usize {
+        self.processors_manager_channel_size
+            .unwrap_or(self.internal_queue_length)
+            .into()
+    }
+
+    pub fn proposal_batch_delay(&self) -> Option<Duration> {
+        self.proposal_batch_delay.map(Into::into)
+    }
+
+    pub fn proposal_batch_max_size(&self) -> usize {
+        self.proposal_batch_max_size.into()
+    }
 }
 
 impl Default for WorkerOptions {
@@ -57,12 +138,58 @@ impl Default for WorkerOptions {
         Self {
             internal_queue_length: NonZeroUsize::new(10000).unwrap(),
             num_timers_in_memory_limit: None,
+            shuffle_channel_size: None,
+            action_effects_channel_size: None,
+            invoker_effects_channel_size: None,
+            processors_manager_channel_size: None,
+            check_partition_key_distribution: false,
+            orphaned_timers: OrphanedTimerBehavior::default(),
+            invoker_abort_behavior: InvokerAbortBehavior::default(),
+            proposal_batch_delay: None,
+            proposal_batch_max_size: NonZeroUsize::new(64).unwrap(),
             storage: StorageOptions::default(),
             invoker: Default::default(),
         }
     }
 }
 
+/// # Orphaned timer behavior
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum OrphanedTimerBehavior {
+    /// # Log
+    ///
+    /// Log a warning and fire the timer nevertheless.
+    #[default]
+    Log,
+    /// # Drop
+    ///
+    /// Drop the timer without invoking the removed service.
+    Drop,
+}
+
+/// # Invoker abort behavior
+#[serde_as]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum InvokerAbortBehavior {
+    /// # Abort
+    ///
+    /// Abort the in-flight invocations right away.
+    #[default]
+    Abort,
+    /// # Drain then abort
+    ///
+    /// Stop starting new invocations and give the in-flight invocations up to the timeout to complete before aborting the remaining ones.
+    DrainThenAbort {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+        timeout: humantime::Duration,
+    },
+}
+
 /// # Invoker options
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, derive_builder::Builder)]
@@ -220,6 +347,29 @@ pub struct StorageOptions {
     /// partitions. The divisor is defined in `num-partitions-to-share-memory-budget`
     rocksdb_memory_ratio: f32,
 
+    /// # Partition column family compression
+    ///
+    /// Every partition stores all of its data (journals, state, timers, inbox, outbox,
+    /// invocation status, ...) in its own column family named `data-<partition-id>`. This
+    /// option sets the compression of all levels of these column families.
+    ///
+    /// By default, the first two levels are not compressed, the middle levels use `lz4` and the
+    /// bottommost level uses `zstd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rocksdb_compression: Option<CompressionType>,
+
+    /// # Partition column family block size
+    ///
+    /// Approximate size of the uncompressed data blocks of the partition column families. Larger
+    /// blocks compress better and reduce the index size, smaller blocks speed up point lookups.
+    /// The column families of the other databases are not affected.
+    ///
+    /// Default: 32KiB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<NonZeroByteCount>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<NonZeroByteCount>"))]
+    rocksdb_block_size: Option<NonZeroUsize>,
+
     /// # Persist lsn interval
     ///
     /// Controls the interval at which worker tries to persist the last applied lsn. Lsn persisting
@@ -273,6 +423,14 @@ impl StorageOptions {
             .get()
     }
 
+    pub fn rocksdb_compression(&self) -> Option<CompressionType> {
+        self.rocksdb_compression
+    }
+
+    pub fn rocksdb_block_size(&self) -> Option<usize> {
+        self.rocksdb_block_size.map(Into::into)
+    }
+
     pub fn num_partitions_to_share_memory_budget(&self) -> u64 {
         self.num_partitions_to_share_memory_budget
             .unwrap_or_else(|| {
@@ -300,6 +458,8 @@ impl Default for StorageOptions {
             // set by apply_common in runtime
             rocksdb_memory_budget: None,
             rocksdb_memory_ratio: 0.49,
+            rocksdb_compression: None,
+            rocksdb_block_size: None,
             // persist the lsn every hour
             persist_lsn_interval: Some(Duration::from_secs(60 * 60).into()),
             persist_lsn_threshold: 1000,
@@ -307,3 +467,53 @@ impl Default for StorageOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_sizes_default_to_internal_queue_length() {
+        let options = WorkerOptionsBuilder::default()
+            .internal_queue_length(NonZeroUsize::new(42).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(options.invoker_effects_channel_size(), 42);
+        assert_eq!(options.processors_manager_channel_size(), 42);
+    }
+
+    #[test]
+    fn channel_sizes_are_independent() {
+        let options = WorkerOptionsBuilder::default()
+            .internal_queue_length(NonZeroUsize::new(42).unwrap())
+            .invoker_effects_channel_size(NonZeroUsize::new(1024))
+            .processors_manager_channel_size(NonZeroUsize::new(16))
+            .build()
+            .unwrap();
+
+        assert_eq!(options.internal_queue_length(), 42);
+        assert_eq!(options.invoker_effects_channel_size(), 1024);
+        assert_eq!(options.processors_manager_channel_size(), 16);
+    }
+
+    #[test]
+    fn partition_column_family_options() {
+        let options = StorageOptions::default();
+        assert_eq!(options.rocksdb_compression(), None);
+        assert_eq!(options.rocksdb_block_size(), None);
+
+        let options = StorageOptionsBuilder::default()
+            .rocksdb_compression(Some(CompressionType::Zstd))
+            .rocksdb_block_size(NonZeroUsize::new(64 * 1024))
+            .build()
+            .unwrap();
+
+        let serialized = serde_json::to_value(&options).unwrap();
+        assert_eq!(serialized["rocksdb-compression"], "zstd");
+
+        let options: StorageOptions = serde_json::from_value(serialized).unwrap();
+        assert_eq!(options.rocksdb_compression(), Some(CompressionType::Zstd));
+        assert_eq!(options.rocksdb_block_size(), Some(64 * 1024));
+    }
+}
@@ -678,7 +678,9 @@ impl WithInvocationId for JournalEntryId {
     }
 }
 
-#[derive(Debug, Clone, serde_with::SerializeDisplay, serde_with::DeserializeFromStr)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde_with::SerializeDisplay, serde_with::DeserializeFromStr,
+)]
 pub struct LambdaARN {
     partition: ByteString,
     region: ByteString,
@@ -238,4 +238,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn partition_keys_at_range_boundaries_resolve_to_adjacent_partitions() {
+        for num_partitions in [1, 3, 7, 64] {
+            let partition_table = FixedPartitionTable::new(Version::MIN, num_partitions);
+            let ranges: Vec<_> = partition_table.partitioner().collect();
+
+            for window in ranges.windows(2) {
+                let (previous_id, previous_range) = &window[0];
+                let (next_id, next_range) = &window[1];
+
+                assert_eq!(*previous_range.end() + 1, *next_range.start());
+                assert_eq!(
+                    partition_table.unchecked_partition_key_to_target_peer(*previous_range.end()),
+                    *previous_id
+                );
+                assert_eq!(
+                    partition_table.unchecked_partition_key_to_target_peer(*next_range.start()),
+                    *next_id
+                );
+            }
+
+            assert_eq!(
+                partition_table.unchecked_partition_key_to_target_peer(0),
+                PartitionId::MIN
+            );
+            assert_eq!(
+                partition_table.unchecked_partition_key_to_target_peer(PartitionKey::MAX),
+                PartitionId::from(num_partitions - 1)
+            );
+            assert_eq!(
+                partition_table.partition_range(PartitionId::from(num_partitions - 1)),
+                ranges.last().map(|(_, range)| range.clone())
+            );
+            assert_eq!(
+                partition_table.partition_range(PartitionId::from(num_partitions)),
+                None
+            );
+        }
+    }
 }
@@ -142,6 +142,8 @@ pub enum Command {
     Timer(TimerKeyValue),
     /// Schedule timer
     ScheduleTimer(TimerKeyValue),
+    /// Delete a timer without firing it
+    DeleteTimer(TimerKeyValue),
     /// Another partition processor is reporting a response of an invocation we requested.
     InvocationResponse(InvocationResponse),
 }
@@ -16,12 +16,15 @@ mod ingress_integration;
 mod invoker_integration;
 mod metric_definitions;
 mod partition;
+mod partition_key_distribution;
 mod partition_processor_manager;
+mod quiesce;
 mod subscription_controller;
 mod subscription_integration;
 
 pub use error::*;
 pub use handle::*;
+pub use quiesce::QuiesceHandle;
 use restate_types::arc_util::ArcSwapExt;
 use restate_types::config::UpdateableConfiguration;
 pub use subscription_controller::SubscriptionController;
@@ -31,12 +34,13 @@ use crate::ingress_integration::InvocationStorageReaderImpl;
 use codederror::CodedError;
 use restate_bifrost::Bifrost;
 use restate_core::network::MessageRouterBuilder;
+use restate_core::worker_api::ProcessorsManagerHandle;
 use restate_core::{task_center, Metadata, TaskKind};
 use restate_ingress_dispatcher::IngressDispatcher;
 use restate_ingress_http::HyperServerIngress;
 use restate_ingress_kafka::Service as IngressKafkaService;
 use restate_invoker_impl::{
-    InvokerHandle as InvokerChannelServiceHandle, Service as InvokerService,
+    ChannelStatusReader, InvokerHandle as InvokerChannelServiceHandle, Service as InvokerService,
 };
 use restate_metadata_store::MetadataStoreClient;
 use restate_network::Networking;
@@ -45,6 +49,7 @@ use restate_schema::UpdateableSchema;
 use restate_service_protocol::codec::ProtobufRawEntryCodec;
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_storage_query_postgres::service::PostgresQueryService;
+use tokio::sync::mpsc;
 
 use crate::invoker_integration::EntryEnricher;
 use crate::partition::storage::invoker::InvokerStorageReader;
@@ -105,7 +110,9 @@ pub struct Worker {
     external_client_ingress: ExternalClientIngress,
     ingress_kafka: IngressKafkaService,
     subscription_controller_handle: SubscriptionControllerHandle,
+    quiesce_handle: QuiesceHandle,
     partition_processor_manager: PartitionProcessorManager,
+    processors_stopped: mpsc::Receiver<()>,
 }
 
 impl Worker {
@@ -132,6 +139,11 @@ impl Worker {
                 config.ingress.clone(),
                 ingress_kafka.create_command_sender(),
             );
+        let quiesce_handle = QuiesceHandle::new(
+            ingress_dispatcher.clone(),
+            subscription_controller_handle.clone(),
+            schema_view.clone(),
+        );
 
         let partition_store_manager = PartitionStoreManager::create(
             updateable_config
@@ -159,6 +171,7 @@ impl Worker {
             schema_view.clone(),
         )?;
 
+        let (processors_alive, processors_stopped) = mpsc::channel(1);
         let partition_processor_manager = PartitionProcessorManager::new(
             task_center(),
             updateable_config.clone(),
@@ -169,6 +182,7 @@ impl Worker {
             networking,
             bifrost,
             invoker.handle(),
+            processors_alive,
         );
 
         let storage_query_context = QueryContext::create(
@@ -193,7 +207,9 @@ impl Worker {
             external_client_ingress: ingress_http,
             ingress_kafka,
             subscription_controller_handle,
+            quiesce_handle,
             partition_processor_manager,
+            processors_stopped,
         })
     }
 
@@ -201,10 +217,22 @@ impl Worker {
         self.subscription_controller_handle.clone()
     }
 
+    pub fn quiesce_handle(&self) -> QuiesceHandle {
+        self.quiesce_handle.clone()
+    }
+
     pub fn storage_query_context(&self) -> &QueryContext {
         &self.storage_query_context
     }
 
+    pub fn processors_manager_handle(&self) -> ProcessorsManagerHandle {
+        self.partition_processor_manager.handle()
+    }
+
+    pub fn invoker_status_reader(&self) -> ChannelStatusReader {
+        self.invoker.status_reader()
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         let tc = task_center();
 
@@ -236,20 +264,27 @@ impl Worker {
             ),
         )?;
 
-        // Invoker service
+        // Invoker service. It is only shut down once the partition processor manager and all
+        // partition processors have stopped, so that no effect is sent to a processor which
+        // is already gone.
+        let mut processors_stopped = self.processors_stopped;
         tc.spawn_child(
             TaskKind::SystemService,
             "invoker",
             None,
-            self.invoker.run(
+            self.invoker.run_until(
                 self.updateable_config
                     .clone()
                     .map_as_updateable_owned(|c| &c.worker.invoker),
+                async move {
+                    // never sent, resolves once all senders are dropped
+                    let _ = processors_stopped.recv().await;
+                },
             ),
         )?;
 
         tc.spawn_child(
-            TaskKind::SystemService,
+            TaskKind::PartitionProcessorManager,
             "partition-processor-manager",
             None,
             self.partition_processor_manager.run(),
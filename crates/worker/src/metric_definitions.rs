@@ -15,9 +15,19 @@ use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
 pub const PARTITION_APPLY_COMMAND: &str = "restate.partition.apply_command.seconds";
 pub const PARTITION_ACTUATOR_HANDLED: &str = "restate.partition.actuator_handled.total";
 pub const PARTITION_TIMER_DUE_HANDLED: &str = "restate.partition.timer_due_handled.total";
+pub const PARTITION_ORPHANED_TIMERS_DROPPED: &str =
+    "restate.partition.orphaned_timers_dropped.total";
 pub const PARTITION_STORAGE_TX_CREATED: &str = "restate.partition.storage_tx_created.total";
 pub const PARTITION_STORAGE_TX_COMMITTED: &str = "restate.partition.storage_tx_committed.total";
+pub const PARTITION_IDEMPOTENCY_IDS_PURGED: &str = "restate.partition.idempotency_ids_purged.total";
+pub const PARTITION_DEDUPLICATED_COMMANDS: &str = "restate.partition.deduplicated_commands.total";
 pub const PARTITION_HANDLE_LEADER_ACTIONS: &str = "restate.partition.handle_leader_action.total";
+pub const PARTITION_HANDLE_LEADER_ACTION_ERRORS: &str =
+    "restate.partition.handle_leader_action_errors.total";
+pub const PARTITION_INVOCATION_STATUS_CLEANUPS_DROPPED: &str =
+    "restate.partition.invocation_status_cleanups_dropped.total";
+pub const PARTITION_INGRESS_RESPONSES_DROPPED: &str =
+    "restate.partition.ingress_responses_dropped.total";
 
 pub const NUM_ACTIVE_PARTITIONS: &str = "restate.num_active_partitions";
 pub const PARTITION_TIME_SINCE_LAST_STATUS_UPDATE: &str =
@@ -27,14 +37,25 @@ pub const PARTITION_LAST_APPLIED_LOG_LSN: &str = "restate.partition.last_applied
 pub const PARTITION_LAST_PERSISTED_LOG_LSN: &str = "restate.partition.last_persisted_lsn";
 pub const PARTITION_IS_EFFECTIVE_LEADER: &str = "restate.partition.is_effective_leader";
 pub const PARTITION_IS_ACTIVE: &str = "restate.partition.is_active";
+pub const PARTITION_KEY_SKEW: &str = "restate.partition.key_skew";
+pub const PARTITION_TIMERS_IN_MEMORY: &str = "restate.partition.timers_in_memory";
+pub const PARTITION_IDEMPOTENCY_IDS: &str = "restate.partition.idempotency_ids";
 
 pub const PP_APPLY_RECORD_DURATION: &str = "restate.partition.apply_record_duration.seconds";
 pub const PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION: &str =
     "restate.partition.handle_action_batch_duration.seconds";
 pub const PARTITION_HANDLE_INVOKER_EFFECT_COMMAND: &str =
     "restate.partition.handle_invoker_effect.seconds";
+pub const PARTITION_PROPOSAL_BATCH_SIZE: &str = "restate.partition.proposal_batch_size";
+pub const PARTITION_PROPOSAL_QUEUE_DEPTH: &str = "restate.partition.proposal_queue_depth";
+
+pub const WORKER_INVOCATIONS: &str = "restate.worker.invocations.total";
+pub const WORKER_QUIESCED: &str = "restate.worker.quiesced";
 
 pub const PARTITION_LABEL: &str = "partition";
+pub const SERVICE_LABEL: &str = "service";
+pub const HANDLER_LABEL: &str = "handler";
+pub const OUTCOME_LABEL: &str = "outcome";
 
 pub(crate) fn describe_metrics() {
     describe_histogram!(
@@ -52,6 +73,11 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of due timer instances processed"
     );
+    describe_counter!(
+        PARTITION_ORPHANED_TIMERS_DROPPED,
+        Unit::Count,
+        "Number of timers dropped because the service they invoke no longer exists"
+    );
     describe_counter!(
         PARTITION_STORAGE_TX_CREATED,
         Unit::Count,
@@ -62,6 +88,16 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Storage transactions committed by applying partition state machine commands"
     );
+    describe_counter!(
+        PARTITION_IDEMPOTENCY_IDS_PURGED,
+        Unit::Count,
+        "Number of idempotency records purged after their retention expired. Only counted by the leader"
+    );
+    describe_counter!(
+        PARTITION_DEDUPLICATED_COMMANDS,
+        Unit::Count,
+        "Number of commands skipped because they were outdated or duplicates, by partition"
+    );
     describe_histogram!(
         PP_APPLY_RECORD_DURATION,
         Unit::Seconds,
@@ -72,16 +108,48 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Time spent applying actions/effects in a single iteration"
     );
-    describe_histogram!(
+    describe_counter!(
         PARTITION_HANDLE_LEADER_ACTIONS,
         Unit::Count,
-        "Number of actions the leader has performed"
+        "Number of actions the leader has performed by action and partition"
+    );
+    describe_counter!(
+        PARTITION_HANDLE_LEADER_ACTION_ERRORS,
+        Unit::Count,
+        "Number of actions the leader failed to perform by action and partition"
+    );
+    describe_counter!(
+        PARTITION_INVOCATION_STATUS_CLEANUPS_DROPPED,
+        Unit::Count,
+        "Number of invocation status cleanups that were dropped because the partition processor was shutting down"
+    );
+    describe_counter!(
+        PARTITION_INGRESS_RESPONSES_DROPPED,
+        Unit::Count,
+        "Number of responses and notifications for the ingress that were dropped because the ingress was unreachable or the partition processor was shutting down, by partition"
     );
     describe_histogram!(
         PARTITION_HANDLE_INVOKER_EFFECT_COMMAND,
         Unit::Seconds,
         "Time spent handling an invoker effect command"
     );
+    describe_histogram!(
+        PARTITION_PROPOSAL_BATCH_SIZE,
+        Unit::Count,
+        "Number of action effects proposed by the leader as a single batch"
+    );
+
+    describe_counter!(
+        WORKER_INVOCATIONS,
+        Unit::Count,
+        "Number of completed invocations by service, handler and outcome"
+    );
+
+    describe_gauge!(
+        WORKER_QUIESCED,
+        Unit::Count,
+        "Set to 1 if the worker is quiesced and does not accept new invocations"
+    );
 
     describe_gauge!(
         NUM_ACTIVE_PARTITIONS,
@@ -101,6 +169,29 @@ pub(crate) fn describe_metrics() {
         "Set to 1 if the partition is an active replay (not catching up or starting)"
     );
 
+    describe_gauge!(
+        PARTITION_KEY_SKEW,
+        Unit::Count,
+        "Ratio between the sampled partition keys of the most loaded partition and the mean per partition"
+    );
+
+    describe_gauge!(
+        PARTITION_PROPOSAL_QUEUE_DEPTH,
+        Unit::Count,
+        "Number of action effects the leader has not proposed yet, sampled on every status update"
+    );
+
+    describe_gauge!(
+        PARTITION_TIMERS_IN_MEMORY,
+        Unit::Count,
+        "Number of timers the leader keeps in memory"
+    );
+    describe_gauge!(
+        PARTITION_IDEMPOTENCY_IDS,
+        Unit::Count,
+        "Number of idempotency ids stored by the partition, which shrinks as their retention expires. Only reported by the leader"
+    );
+
     describe_gauge!(
         PARTITION_TIME_SINCE_LAST_STATUS_UPDATE,
         Unit::Seconds,
@@ -77,6 +77,10 @@ impl ActionEffectHandler {
                     let header = self.create_header(timer.invocation_id().partition_key());
                     Envelope::new(header, Command::Timer(timer))
                 }
+                ActionEffect::DeleteTimer(timer) => {
+                    let header = self.create_header(timer.invocation_id().partition_key());
+                    Envelope::new(header, Command::DeleteTimer(timer))
+                }
                 ActionEffect::ScheduleCleanupTimer(invocation_id, duration) => {
                     //  We need this self proposal because we need to agree between leaders and followers on the wakeup time.
                     //  We can get rid of this once we'll have a synchronized clock between leaders/followers.
@@ -49,6 +49,7 @@ pub(crate) enum ActionEffect {
     Invoker(restate_invoker_api::Effect),
     Shuffle(shuffle::OutboxTruncation),
     Timer(TimerKeyValue),
+    DeleteTimer(TimerKeyValue),
     ScheduleCleanupTimer(InvocationId, Duration),
 }
 
@@ -8,37 +8,47 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::metric_definitions::PARTITION_HANDLE_LEADER_ACTIONS;
+use crate::metric_definitions::{
+    PARTITION_HANDLE_LEADER_ACTIONS, PARTITION_HANDLE_LEADER_ACTION_ERRORS,
+    PARTITION_INGRESS_RESPONSES_DROPPED, PARTITION_INVOCATION_STATUS_CLEANUPS_DROPPED,
+    PARTITION_LABEL, PARTITION_TIMERS_IN_MEMORY,
+};
 use crate::partition::shuffle::{HintSender, Shuffle, ShuffleMetadata};
 use crate::partition::{shuffle, storage};
 use futures::future::OptionFuture;
-use futures::{future, StreamExt};
-use metrics::counter;
+use futures::stream::FuturesUnordered;
+use futures::{future, FutureExt, StreamExt};
+use metrics::{counter, gauge, Counter, Gauge};
 use restate_core::network::NetworkSender;
 use restate_core::{
     current_task_partition_id, metadata, task_center, ShutdownError, TaskId, TaskKind,
 };
-use restate_invoker_api::InvokeInputJournal;
+use restate_invoker_api::{EffectKind, InvokeInputJournal};
 use restate_network::Networking;
 use restate_node_protocol::ingress;
 use restate_timer::TokioClock;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::ops::RangeInclusive;
 use std::pin::Pin;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::time::Instant;
 use tracing::{debug, trace, warn};
 
 mod action_collector;
 
 use crate::partition::action_effect_handler::ActionEffectHandler;
-use crate::partition::state_machine::Action;
+use crate::partition::state_machine::{Action, IndependentAction, OrderedAction, SplitAction};
 pub(crate) use action_collector::{ActionEffect, ActionEffectStream};
 use restate_bifrost::Bifrost;
 use restate_errors::NotRunningError;
 use restate_partition_store::PartitionStore;
 use restate_storage_api::deduplication_table::EpochSequenceNumber;
+use restate_types::config::InvokerAbortBehavior;
 use restate_types::identifiers::{InvocationId, PartitionKey};
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionLeaderEpoch};
+use restate_types::invocation::InvocationTarget;
 use restate_types::GenerationalNodeId;
 use restate_wal_protocol::timer::TimerKeyValue;
 
@@ -52,14 +62,78 @@ pub(crate) struct LeaderState {
     shuffle_hint_tx: HintSender,
     shuffle_task_id: TaskId,
     timer_service: Pin<Box<TimerService>>,
+    /// Resolved once, since it is updated whenever the timer service is polled.
+    timers_in_memory: Gauge,
+    /// Resolved once, since it is looked up for every ingress response.
+    ingress_responses_dropped: Counter,
     action_effect_handler: ActionEffectHandler,
     actions_effects_tx: mpsc::Sender<ActionEffect>,
+    /// Weak so that the effect stream still terminates if the invoker drops its sender.
+    invoker_effects_tx: mpsc::WeakSender<restate_invoker_api::Effect>,
+    /// Whether the number of in-memory timers was at the configured limit when last checked.
+    timer_limit_reached: bool,
+    /// Set while invocation processing is paused.
+    paused_invocations: Option<PausedInvocations>,
+    /// Set while draining the in-flight invocations before stepping down.
+    draining: Option<Draining>,
+}
+
+/// Invocations which a leader lets complete before stepping down.
+#[derive(Debug)]
+struct Draining {
+    deadline: Instant,
+    /// Invocations which were running when draining started and from which the invoker has not
+    /// reported a final effect yet.
+    in_flight: HashSet<InvocationId>,
+}
+
+impl Draining {
+    /// Stops waiting for invocations which the invoker is done with.
+    fn on_invoker_effect(&mut self, effect: &restate_invoker_api::Effect) {
+        if matches!(
+            effect.kind,
+            EffectKind::End | EffectKind::Failed(_) | EffectKind::Suspended { .. }
+        ) {
+            self.in_flight.remove(&effect.invocation_id);
+        }
+    }
+}
+
+/// Invocations which have not been started because invocation processing is paused.
+#[derive(Debug, Default)]
+struct PausedInvocations {
+    held: Vec<(InvocationId, InvocationTarget)>,
+}
+
+impl PausedInvocations {
+    /// Holds back `Invoke` actions and returns all other actions, which still need to be handled.
+    /// Aborting a held invocation drops it so that it is not started once resumed.
+    fn hold(&mut self, action: OrderedAction) -> Option<OrderedAction> {
+        match action {
+            OrderedAction::Invoke {
+                invocation_id,
+                invocation_target,
+                ..
+            } => {
+                self.held.push((invocation_id, invocation_target));
+                None
+            }
+            OrderedAction::AbortInvocation(invocation_id) => {
+                self.held.retain(|(held_id, _)| *held_id != invocation_id);
+                Some(action)
+            }
+            action => Some(action),
+        }
+    }
 }
 
 pub(crate) struct FollowerState<I> {
     partition_id: PartitionId,
     num_timers_in_memory_limit: Option<usize>,
-    channel_size: usize,
+    invoker_effects_channel_size: usize,
+    shuffle_channel_size: usize,
+    action_effects_channel_size: usize,
+    invoker_abort_behavior: InvokerAbortBehavior,
     invoker_tx: I,
     networking: Networking,
     partition_key_range: RangeInclusive<PartitionKey>,
@@ -70,6 +144,12 @@ pub(crate) struct FollowerState<I> {
 pub(crate) enum Error {
     #[error("invoker is unreachable. This indicates a bug or the system is shutting down: {0}")]
     Invoker(NotRunningError),
+    #[error("invoker is unreachable while changing the leadership of partition {partition_id} in leader epoch {leader_epoch}. This indicates a bug or the system is shutting down: {source}")]
+    InvokerLeadershipTransition {
+        partition_id: PartitionId,
+        leader_epoch: LeaderEpoch,
+        source: NotRunningError,
+    },
     #[error(transparent)]
     Storage(#[from] restate_storage_api::StorageError),
     #[error(transparent)]
@@ -94,7 +174,10 @@ where
         partition_id: PartitionId,
         partition_key_range: RangeInclusive<PartitionKey>,
         num_timers_in_memory_limit: Option<usize>,
-        channel_size: usize,
+        invoker_effects_channel_size: usize,
+        shuffle_channel_size: usize,
+        action_effects_channel_size: usize,
+        invoker_abort_behavior: InvokerAbortBehavior,
         invoker_tx: InvokerInputSender,
         bifrost: Bifrost,
         networking: Networking,
@@ -104,7 +187,10 @@ where
                 partition_id,
                 partition_key_range,
                 num_timers_in_memory_limit,
-                channel_size,
+                invoker_effects_channel_size,
+                shuffle_channel_size,
+                action_effects_channel_size,
+                invoker_abort_behavior,
                 invoker_tx,
                 bifrost,
                 networking,
@@ -143,12 +229,12 @@ where
             let leader_epoch = epoch_sequence_number.leader_epoch;
             let metadata = metadata();
 
-            let invoker_rx = Self::resume_invoked_invocations(
+            let (invoker_rx, invoker_effects_tx) = Self::resume_invoked_invocations(
                 &mut follower_state.invoker_tx,
                 (follower_state.partition_id, leader_epoch),
                 follower_state.partition_key_range.clone(),
                 partition_storage,
-                follower_state.channel_size,
+                follower_state.invoker_effects_channel_size,
             )
             .await?;
 
@@ -157,8 +243,12 @@ where
                 follower_state.num_timers_in_memory_limit,
                 partition_storage.clone(),
             ));
+            let timers_in_memory = gauge!(PARTITION_TIMERS_IN_MEMORY,
+                PARTITION_LABEL => follower_state.partition_id.to_string());
+            let ingress_responses_dropped = counter!(PARTITION_INGRESS_RESPONSES_DROPPED,
+                PARTITION_LABEL => follower_state.partition_id.to_string());
 
-            let (shuffle_tx, shuffle_rx) = mpsc::channel(follower_state.channel_size);
+            let (shuffle_tx, shuffle_rx) = mpsc::channel(follower_state.shuffle_channel_size);
 
             let shuffle = Shuffle::new(
                 ShuffleMetadata::new(
@@ -168,7 +258,7 @@ where
                 ),
                 partition_storage.clone(),
                 shuffle_tx,
-                follower_state.channel_size,
+                follower_state.shuffle_channel_size,
                 follower_state.bifrost.clone(),
             );
 
@@ -190,7 +280,7 @@ where
             );
 
             let (actions_effects_tx, actions_effects_rx) =
-                mpsc::channel(follower_state.channel_size);
+                mpsc::channel(follower_state.action_effects_channel_size);
 
             Ok((
                 LeadershipState::Leader {
@@ -200,8 +290,14 @@ where
                         shuffle_task_id,
                         shuffle_hint_tx,
                         timer_service,
+                        timers_in_memory,
+                        ingress_responses_dropped,
                         action_effect_handler,
                         actions_effects_tx,
+                        invoker_effects_tx,
+                        timer_limit_reached: false,
+                        paused_invocations: None,
+                        draining: None,
                     },
                 },
                 ActionEffectStream::leader(invoker_rx, shuffle_rx, actions_effects_rx),
@@ -216,9 +312,16 @@ where
         partition_leader_epoch: PartitionLeaderEpoch,
         partition_key_range: RangeInclusive<PartitionKey>,
         partition_storage: &mut PartitionStorage,
-        channel_size: usize,
-    ) -> Result<mpsc::Receiver<restate_invoker_api::Effect>, Error> {
-        let (invoker_tx, invoker_rx) = mpsc::channel(channel_size);
+        invoker_effects_channel_size: usize,
+    ) -> Result<
+        (
+            mpsc::Receiver<restate_invoker_api::Effect>,
+            mpsc::WeakSender<restate_invoker_api::Effect>,
+        ),
+        Error,
+    > {
+        let (invoker_tx, invoker_rx) = mpsc::channel(invoker_effects_channel_size);
+        let weak_invoker_tx = invoker_tx.downgrade();
 
         let storage = partition_storage.clone_storage();
         invoker_handle
@@ -229,7 +332,11 @@ where
                 invoker_tx,
             )
             .await
-            .map_err(Error::Invoker)?;
+            .map_err(|source| Error::InvokerLeadershipTransition {
+                partition_id: partition_leader_epoch.0,
+                leader_epoch: partition_leader_epoch.1,
+                source,
+            })?;
 
         {
             let invoked_invocations = partition_storage.scan_invoked_invocations();
@@ -252,7 +359,7 @@ where
             debug!(partition_id = %partition_leader_epoch.0, "Leader partition resumed {} invocations", count);
         }
 
-        Ok(invoker_rx)
+        Ok((invoker_rx, weak_invoker_tx))
     }
 
     pub(crate) async fn become_follower(self) -> Result<(Self, ActionEffectStream), Error> {
@@ -261,8 +368,11 @@ where
                 FollowerState {
                     partition_id,
                     partition_key_range,
-                    channel_size,
+                    invoker_effects_channel_size,
+                    shuffle_channel_size,
+                    action_effects_channel_size,
                     num_timers_in_memory_limit,
+                    invoker_abort_behavior,
                     mut invoker_tx,
                     bifrost,
                     networking,
@@ -271,10 +381,21 @@ where
                 LeaderState {
                     leader_epoch,
                     shuffle_task_id,
+                    timer_service,
+                    timers_in_memory,
                     ..
                 },
         } = self
         {
+            // Timers are persisted in the partition storage, so the next leader reloads them from
+            // there. Only the in-memory timers of this leader are gone.
+            debug!(
+                %partition_id,
+                num_timers_in_memory = timer_service.num_timers_in_memory(),
+                "Stopping timer service"
+            );
+            timers_in_memory.set(0.0);
+
             let shuffle_handle = OptionFuture::from(task_center().cancel_task(shuffle_task_id));
 
             let (shuffle_result, abort_result) = tokio::join!(
@@ -282,7 +403,11 @@ where
                 invoker_tx.abort_all_partition((partition_id, leader_epoch)),
             );
 
-            abort_result.map_err(Error::Invoker)?;
+            abort_result.map_err(|source| Error::InvokerLeadershipTransition {
+                partition_id,
+                leader_epoch,
+                source,
+            })?;
 
             if let Some(shuffle_result) = shuffle_result {
                 shuffle_result.expect("graceful termination of shuffle task");
@@ -292,7 +417,10 @@ where
                 partition_id,
                 partition_key_range,
                 num_timers_in_memory_limit,
-                channel_size,
+                invoker_effects_channel_size,
+                shuffle_channel_size,
+                action_effects_channel_size,
+                invoker_abort_behavior,
                 invoker_tx,
                 bifrost,
                 networking,
@@ -302,13 +430,202 @@ where
         }
     }
 
+    /// Number of invoker and self-proposed action effects which are queued for being proposed by
+    /// the leader. Always 0 for followers.
+    pub(crate) fn proposal_queue_depth(&self) -> usize {
+        fn queued<T>(tx: &mpsc::Sender<T>) -> usize {
+            tx.max_capacity() - tx.capacity()
+        }
+
+        match self {
+            LeadershipState::Follower(_) => 0,
+            LeadershipState::Leader { leader_state, .. } => {
+                queued(&leader_state.actions_effects_tx)
+                    + leader_state
+                        .invoker_effects_tx
+                        .upgrade()
+                        .map_or(0, |tx| queued(&tx))
+            }
+        }
+    }
+
+    /// Stops forwarding new invocations to the invoker until [`Self::resume_invocations`] is
+    /// called. Timers, completions and acks of already running invocations are still processed.
+    /// Only leaders can pause, and leadership changes resume invocation processing.
+    pub(crate) fn pause_invocations(&mut self) {
+        match self {
+            LeadershipState::Follower(follower_state) => {
+                debug!(
+                    partition_id = %follower_state.partition_id,
+                    "Ignoring request to pause invocations since partition processor is not the leader"
+                );
+            }
+            LeadershipState::Leader {
+                follower_state,
+                leader_state,
+            } => {
+                if leader_state.paused_invocations.is_none() {
+                    debug!(partition_id = %follower_state.partition_id, "Pausing invocations");
+                    leader_state.paused_invocations = Some(PausedInvocations::default());
+                }
+            }
+        }
+    }
+
+    /// Starts the invocations which were held back while paused. A draining leader keeps holding
+    /// them back, since it is about to step down anyway.
+    pub(crate) async fn resume_invocations(&mut self) -> Result<(), Error> {
+        if let LeadershipState::Leader {
+            follower_state,
+            leader_state,
+        } = self
+        {
+            if leader_state.draining.is_some() {
+                debug!(
+                    partition_id = %follower_state.partition_id,
+                    "Ignoring request to resume invocations since partition processor is draining"
+                );
+                return Ok(());
+            }
+
+            if let Some(paused_invocations) = leader_state.paused_invocations.take() {
+                debug!(
+                    partition_id = %follower_state.partition_id,
+                    held_invocations = paused_invocations.held.len(),
+                    "Resuming invocations"
+                );
+                let partition_leader_epoch =
+                    (follower_state.partition_id, leader_state.leader_epoch);
+                for (invocation_id, invocation_target) in paused_invocations.held {
+                    // the journal might have changed while paused, let the invoker read it
+                    follower_state
+                        .invoker_tx
+                        .invoke(
+                            partition_leader_epoch,
+                            invocation_id,
+                            invocation_target,
+                            InvokeInputJournal::NoCachedJournal,
+                        )
+                        .await
+                        .map_err(Error::Invoker)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepares the leader for stepping down according to the configured
+    /// [`InvokerAbortBehavior`]. Returns true if the leader drains its in-flight invocations
+    /// first, in which case it should step down once it [`Self::is_drained`] or the
+    /// [`Self::drain_deadline`] has passed. Returns false if it can step down right away.
+    ///
+    /// The in-flight invocations are read from storage once. Afterwards, the leader stops
+    /// waiting for an invocation when the invoker reports its final effect or it is aborted.
+    pub(crate) async fn start_draining(
+        &mut self,
+        partition_storage: &mut PartitionStorage,
+    ) -> Result<bool, Error> {
+        let LeadershipState::Leader {
+            follower_state,
+            leader_state,
+        } = self
+        else {
+            return Ok(false);
+        };
+
+        let InvokerAbortBehavior::DrainThenAbort { timeout } =
+            follower_state.invoker_abort_behavior
+        else {
+            return Ok(false);
+        };
+
+        if leader_state.draining.is_none() {
+            // held back invocations have not been started, so there is nothing to wait for
+            let held = leader_state
+                .paused_invocations
+                .as_ref()
+                .map(|paused_invocations| paused_invocations.held.as_slice())
+                .unwrap_or_default();
+            let mut in_flight = HashSet::new();
+            let invoked_invocations = partition_storage.scan_invoked_invocations();
+            tokio::pin!(invoked_invocations);
+            while let Some(invocation_id_and_target) = invoked_invocations.next().await {
+                let (invocation_id, _) = invocation_id_and_target?;
+                if !held.iter().any(|(held_id, _)| *held_id == invocation_id) {
+                    in_flight.insert(invocation_id);
+                }
+            }
+
+            debug!(
+                partition_id = %follower_state.partition_id,
+                %timeout,
+                in_flight_invocations = in_flight.len(),
+                "Draining in-flight invocations before stepping down"
+            );
+            leader_state.draining = Some(Draining {
+                deadline: Instant::now() + *timeout,
+                in_flight,
+            });
+        }
+        self.pause_invocations();
+        Ok(true)
+    }
+
+    /// Point in time at which a draining leader stops waiting for its in-flight invocations.
+    pub(crate) fn drain_deadline(&self) -> Option<Instant> {
+        match self {
+            LeadershipState::Follower(_) => None,
+            LeadershipState::Leader { leader_state, .. } => leader_state
+                .draining
+                .as_ref()
+                .map(|draining| draining.deadline),
+        }
+    }
+
+    /// Returns true if a draining leader has no in-flight invocations left.
+    pub(crate) fn is_drained(&self) -> bool {
+        match self {
+            LeadershipState::Follower(_) => false,
+            LeadershipState::Leader { leader_state, .. } => leader_state
+                .draining
+                .as_ref()
+                .is_some_and(|draining| draining.in_flight.is_empty()),
+        }
+    }
+
     pub(crate) async fn run_timer(&mut self) -> TimerKeyValue {
         match self {
             LeadershipState::Follower { .. } => future::pending().await,
             LeadershipState::Leader {
-                leader_state: LeaderState { timer_service, .. },
-                ..
-            } => timer_service.as_mut().next_timer().await,
+                follower_state,
+                leader_state:
+                    LeaderState {
+                        timer_service,
+                        timers_in_memory,
+                        timer_limit_reached,
+                        ..
+                    },
+            } => {
+                let num_timers_in_memory = timer_service.num_timers_in_memory();
+                timers_in_memory.set(num_timers_in_memory as f64);
+
+                let limit_reached = follower_state
+                    .num_timers_in_memory_limit
+                    .is_some_and(|limit| num_timers_in_memory >= limit);
+                if limit_reached && !*timer_limit_reached {
+                    warn!(
+                        partition_id = %follower_state.partition_id,
+                        num_timers_in_memory,
+                        "Reached the limit of timers kept in memory. Further timers will be loaded \
+                        from storage on demand; consider increasing 'worker.num-timers-in-memory-limit' \
+                        if this happens frequently"
+                    );
+                }
+                *timer_limit_reached = limit_reached;
+
+                timer_service.as_mut().next_timer().await
+            }
         }
     }
 
@@ -324,40 +641,122 @@ where
                 follower_state,
                 leader_state,
             } => {
+                let FollowerState {
+                    partition_id,
+                    invoker_tx,
+                    networking,
+                    ..
+                } = follower_state;
+                let LeaderState {
+                    leader_epoch,
+                    shuffle_hint_tx,
+                    timer_service,
+                    ingress_responses_dropped,
+                    actions_effects_tx,
+                    paused_invocations,
+                    draining,
+                    ..
+                } = leader_state;
+                let partition_leader_epoch = (*partition_id, *leader_epoch);
+                let networking = &*networking;
+                let actions_effects_tx = &*actions_effects_tx;
+
+                let partition_id_str = partition_id.to_string();
+                let record_error = |action_name: &'static str| {
+                    counter!(PARTITION_HANDLE_LEADER_ACTION_ERRORS,
+                        "action" => action_name,
+                        PARTITION_LABEL => partition_id_str.clone())
+                    .increment(1);
+                };
+
+                // Actions which interact with the invoker, the shuffle or the timer service must
+                // be handled in order. The remaining ones are independent and run concurrently,
+                // so that a status cleanup waiting for room in the action effects channel doesn't
+                // hold back the invoker.
+                let mut ordered_actions = Vec::new();
+                let mut independent_actions = Vec::new();
                 for action in actions {
                     trace!(?action, "Apply action");
-                    counter!(PARTITION_HANDLE_LEADER_ACTIONS, "action" =>
-                        action.name())
+                    counter!(PARTITION_HANDLE_LEADER_ACTIONS,
+                        "action" => action.name(),
+                        PARTITION_LABEL => partition_id_str.clone())
                     .increment(1);
-                    Self::handle_action(
-                        action,
-                        (follower_state.partition_id, leader_state.leader_epoch),
-                        &mut follower_state.invoker_tx,
-                        &leader_state.shuffle_hint_tx,
-                        leader_state.timer_service.as_mut(),
-                        &mut leader_state.actions_effects_tx,
-                        &follower_state.networking,
-                    )
-                    .await?;
+                    match action.split() {
+                        SplitAction::Ordered(action) => ordered_actions.push(action),
+                        SplitAction::Independent(action) => independent_actions.push(action),
+                    }
                 }
+
+                let ordered = async {
+                    for action in ordered_actions {
+                        let Some(action) = (match paused_invocations {
+                            Some(paused_invocations) => paused_invocations.hold(action),
+                            None => Some(action),
+                        }) else {
+                            continue;
+                        };
+                        if let (Some(draining), OrderedAction::AbortInvocation(invocation_id)) =
+                            (draining.as_mut(), &action)
+                        {
+                            draining.in_flight.remove(invocation_id);
+                        }
+                        let action_name = action.name();
+                        Self::handle_ordered_action(
+                            action,
+                            partition_leader_epoch,
+                            invoker_tx,
+                            shuffle_hint_tx,
+                            timer_service.as_mut(),
+                        )
+                        .await
+                        .map_err(|err| {
+                            record_error(action_name);
+                            err
+                        })?;
+                    }
+                    Ok::<_, Error>(())
+                };
+
+                let independent = async {
+                    let mut in_flight: FuturesUnordered<_> = independent_actions
+                        .into_iter()
+                        .map(|action| {
+                            let action_name = action.name();
+                            Self::handle_independent_action(
+                                action,
+                                actions_effects_tx,
+                                networking,
+                                ingress_responses_dropped,
+                            )
+                            .map(move |result| (action_name, result))
+                        })
+                        .collect();
+
+                    while let Some((action_name, result)) = in_flight.next().await {
+                        result.map_err(|err| {
+                            record_error(action_name);
+                            err
+                        })?;
+                    }
+                    Ok::<_, Error>(())
+                };
+
+                future::try_join(ordered, independent).await?;
             }
         }
 
         Ok(())
     }
 
-    #[allow(clippy::too_many_arguments)]
-    async fn handle_action(
-        action: Action,
+    async fn handle_ordered_action(
+        action: OrderedAction,
         partition_leader_epoch: PartitionLeaderEpoch,
         invoker_tx: &mut InvokerInputSender,
         shuffle_hint_tx: &HintSender,
         mut timer_service: Pin<&mut TimerService>,
-        actions_effects_tx: &mut mpsc::Sender<ActionEffect>,
-        networking: &Networking,
     ) -> Result<(), Error> {
         match action {
-            Action::Invoke {
+            OrderedAction::Invoke {
                 invocation_id,
                 invocation_target,
                 invoke_input_journal,
@@ -370,13 +769,17 @@ where
                 )
                 .await
                 .map_err(Error::Invoker)?,
-            Action::NewOutboxMessage {
+            OrderedAction::NewOutboxMessage {
                 seq_number,
                 message,
             } => shuffle_hint_tx.send(shuffle::NewOutboxMessage::new(seq_number, message)),
-            Action::RegisterTimer { timer_value } => timer_service.as_mut().add_timer(timer_value),
-            Action::DeleteTimer { timer_key } => timer_service.as_mut().remove_timer(timer_key),
-            Action::AckStoredEntry {
+            OrderedAction::RegisterTimer { timer_value } => {
+                timer_service.as_mut().add_timer(timer_value)
+            }
+            OrderedAction::DeleteTimer { timer_key } => {
+                timer_service.as_mut().remove_timer(timer_key)
+            }
+            OrderedAction::AckStoredEntry {
                 invocation_id,
                 entry_index,
             } => {
@@ -385,29 +788,43 @@ where
                     .await
                     .map_err(Error::Invoker)?;
             }
-            Action::ForwardCompletion {
+            OrderedAction::ForwardCompletion {
                 invocation_id,
                 completion,
             } => invoker_tx
                 .notify_completion(partition_leader_epoch, invocation_id, completion)
                 .await
                 .map_err(Error::Invoker)?,
-            Action::AbortInvocation(invocation_id) => invoker_tx
+            OrderedAction::AbortInvocation(invocation_id) => invoker_tx
                 .abort_invocation(partition_leader_epoch, invocation_id)
                 .await
                 .map_err(Error::Invoker)?,
-            Action::IngressResponse(ingress_response) => {
+        }
+
+        Ok(())
+    }
+
+    async fn handle_independent_action(
+        action: IndependentAction,
+        actions_effects_tx: &mpsc::Sender<ActionEffect>,
+        networking: &Networking,
+        ingress_responses_dropped: &Counter,
+    ) -> Result<(), Error> {
+        match action {
+            IndependentAction::IngressResponse(ingress_response) => {
                 Self::send_ingress_message(
                     networking,
+                    ingress_responses_dropped,
                     ingress_response.inner.invocation_id,
                     ingress_response.target_node,
                     ingress::IngressMessage::InvocationResponse(ingress_response.inner),
                 )
                 .await?;
             }
-            Action::IngressSubmitNotification(attach_notification) => {
+            IndependentAction::IngressSubmitNotification(attach_notification) => {
                 Self::send_ingress_message(
                     networking,
+                    ingress_responses_dropped,
                     Some(attach_notification.inner.original_invocation_id),
                     attach_notification.target_node,
                     ingress::IngressMessage::SubmittedInvocationNotification(
@@ -416,13 +833,11 @@ where
                 )
                 .await?;
             }
-            Action::ScheduleInvocationStatusCleanup {
+            IndependentAction::ScheduleInvocationStatusCleanup {
                 invocation_id,
                 retention,
             } => {
-                // We can ignore this error. It means the PP is shutting down.
-                let _ = actions_effects_tx
-                    .send(ActionEffect::ScheduleCleanupTimer(invocation_id, retention))
+                schedule_invocation_status_cleanup(actions_effects_tx, invocation_id, retention)
                     .await;
             }
         }
@@ -439,9 +854,19 @@ where
                 // nothing to do :-)
             }
             LeadershipState::Leader { leader_state, .. } => {
-                leader_state
-                    .action_effect_handler
-                    .handle(action_effects)
+                let LeaderState {
+                    action_effect_handler,
+                    draining,
+                    ..
+                } = leader_state;
+                action_effect_handler
+                    .handle(action_effects.into_iter().inspect(|action_effect| {
+                        if let (Some(draining), ActionEffect::Invoker(effect)) =
+                            (draining.as_mut(), action_effect)
+                        {
+                            draining.on_invoker_effect(effect);
+                        }
+                    }))
                     .await?
             }
         };
@@ -451,6 +876,7 @@ where
 
     async fn send_ingress_message(
         networking: &Networking,
+        dropped_responses: &Counter,
         invocation_id: Option<InvocationId>,
         target_node: GenerationalNodeId,
         ingress_message: ingress::IngressMessage,
@@ -476,25 +902,23 @@ where
             current_task_partition_id(),
             {
                 let networking = networking.clone();
+                let dropped_responses = dropped_responses.clone();
                 async move {
-                    if let Err(e) = networking.send(target_node.into(), &ingress_message).await {
-                        let invocation_id_str = invocation_id
-                            .as_ref()
-                            .map(|i| i.to_string())
-                            .unwrap_or_default();
-                        warn!(
-                            ?e,
-                            ingress.node_id = %target_node,
-                            restate.invocation.id = %invocation_id_str,
-                            "Failed to send ingress message, will drop the message on the floor"
-                        );
-                    }
+                    send_to_ingress(
+                        &networking,
+                        invocation_id,
+                        target_node,
+                        &ingress_message,
+                        &dropped_responses,
+                    )
+                    .await;
                     Ok(())
                 }
             },
         );
 
         if maybe_task.is_err() {
+            dropped_responses.increment(1);
             let invocation_id_str = invocation_id
                 .as_ref()
                 .map(|i| i.to_string())
@@ -509,8 +933,866 @@ where
     }
 }
 
+/// Sends the message to the ingress, dropping it if the ingress is unreachable. Dropped
+/// messages are counted by `dropped_responses`.
+async fn send_to_ingress<N: NetworkSender>(
+    networking: &N,
+    invocation_id: Option<InvocationId>,
+    target_node: GenerationalNodeId,
+    ingress_message: &ingress::IngressMessage,
+    dropped_responses: &Counter,
+) {
+    if let Err(e) = networking.send(target_node.into(), ingress_message).await {
+        dropped_responses.increment(1);
+        let invocation_id_str = invocation_id
+            .as_ref()
+            .map(|i| i.to_string())
+            .unwrap_or_default();
+        warn!(
+            ?e,
+            ingress.node_id = %target_node,
+            restate.invocation.id = %invocation_id_str,
+            "Failed to send ingress message, will drop the message on the floor"
+        );
+    }
+}
+
+/// Schedules the cleanup of the invocation status, waiting for capacity if the action effects
+/// channel is full. Returns `false` if the cleanup was dropped because the channel is closed,
+/// which only happens if the partition processor is shutting down.
+async fn schedule_invocation_status_cleanup(
+    actions_effects_tx: &mpsc::Sender<ActionEffect>,
+    invocation_id: InvocationId,
+    retention: Duration,
+) -> bool {
+    if actions_effects_tx
+        .send(ActionEffect::ScheduleCleanupTimer(invocation_id, retention))
+        .await
+        .is_err()
+    {
+        counter!(PARTITION_INVOCATION_STATUS_CLEANUPS_DROPPED).increment(1);
+        debug!(
+            restate.invocation.id = %invocation_id,
+            "Dropping the invocation status cleanup because the partition processor is shutting down"
+        );
+        return false;
+    }
+
+    true
+}
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum TaskError {
     #[error(transparent)]
     Error(#[from] anyhow::Error),
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use crate::partition::fired_timer_effect;
+    use crate::partition::state_machine::StateStorage;
+    use bytestring::ByteString;
+    use restate_bifrost::Record;
+    use restate_core::{MockNetworkSender, TaskCenter, TestCoreEnv};
+    use restate_invoker_api::{Effect, ServiceHandle};
+    use restate_partition_store::{OpenMode, PartitionStoreManager};
+    use restate_rocksdb::RocksDbManager;
+    use restate_schema_api::service::test_util::MockServiceMetadataResolver;
+    use restate_schema_api::service::ServiceMetadata;
+    use restate_storage_api::idempotency_table::{IdempotencyMetadata, IdempotencyTable};
+    use restate_storage_api::invocation_status_table::{
+        InFlightInvocationMetadata, InvocationStatus,
+    };
+    use restate_test_util::{assert, let_assert};
+    use restate_types::arc_util::Constant;
+    use restate_types::config::{
+        CommonOptions, OrphanedTimerBehavior, RocksDbOptions, StorageOptions,
+    };
+    use restate_types::identifiers::{
+        EntryIndex, IdempotencyId, IngressRequestId, WithPartitionKey,
+    };
+    use restate_types::ingress::SubmittedInvocationNotification;
+    use restate_types::invocation::{ServiceInvocation, Source};
+    use restate_types::journal::Completion;
+    use restate_types::logs::{LogId, Lsn, SequenceNumber};
+    use restate_types::partition_table::FindPartition;
+    use restate_types::storage::StorageCodec;
+    use restate_types::time::MillisSinceEpoch;
+    use restate_wal_protocol::{Command, Envelope};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use test_log::test;
+
+    #[test(tokio::test)]
+    async fn cleanup_is_dropped_if_channel_is_closed() {
+        let (actions_effects_tx, actions_effects_rx) = mpsc::channel(1);
+        drop(actions_effects_rx);
+
+        assert!(
+            !schedule_invocation_status_cleanup(
+                &actions_effects_tx,
+                InvocationId::mock_random(),
+                Duration::from_secs(60)
+            )
+            .await
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn cleanup_waits_for_channel_capacity() {
+        let (actions_effects_tx, mut actions_effects_rx) = mpsc::channel(1);
+        let first_invocation_id = InvocationId::mock_random();
+        let second_invocation_id = InvocationId::mock_random();
+        let retention = Duration::from_secs(60);
+
+        assert!(
+            schedule_invocation_status_cleanup(&actions_effects_tx, first_invocation_id, retention)
+                .await
+        );
+
+        // the channel is full, so the second cleanup must wait until the first one is consumed
+        let second_cleanup = schedule_invocation_status_cleanup(
+            &actions_effects_tx,
+            second_invocation_id,
+            retention,
+        );
+        tokio::pin!(second_cleanup);
+        assert!(futures::poll!(second_cleanup.as_mut()).is_pending());
+
+        let_assert!(
+            Some(ActionEffect::ScheduleCleanupTimer(invocation_id, _)) =
+                actions_effects_rx.recv().await
+        );
+        assert!(invocation_id == first_invocation_id);
+        assert!(second_cleanup.await);
+        let_assert!(
+            Some(ActionEffect::ScheduleCleanupTimer(invocation_id, _)) =
+                actions_effects_rx.recv().await
+        );
+        assert!(invocation_id == second_invocation_id);
+    }
+
+    #[test(tokio::test)]
+    async fn failed_ingress_response_is_counted() {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        // the ingress is unreachable because nobody receives the sent messages
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        drop(rx);
+        let networking = MockNetworkSender::from_sender(tx);
+
+        let dropped = Arc::new(AtomicU64::new(0));
+        let dropped_responses = Counter::from_arc(dropped.clone());
+        let invocation_id = InvocationId::mock_random();
+        let message = ingress::IngressMessage::SubmittedInvocationNotification(
+            SubmittedInvocationNotification {
+                request_id: IngressRequestId::default(),
+                original_invocation_id: invocation_id,
+                attached_invocation_id: invocation_id,
+            },
+        );
+
+        env.tc
+            .run_in_scope(
+                "test",
+                None,
+                send_to_ingress(
+                    &networking,
+                    Some(invocation_id),
+                    GenerationalNodeId::new(1, 1),
+                    &message,
+                    &dropped_responses,
+                ),
+            )
+            .await;
+
+        assert!(dropped.load(Ordering::Relaxed) == 1);
+    }
+
+    #[test]
+    fn paused_invocations_hold_back_invoke_actions() {
+        let invocation_id = InvocationId::mock_random();
+        let aborted_invocation_id = InvocationId::mock_random();
+        let invoke = |invocation_id| OrderedAction::Invoke {
+            invocation_id,
+            invocation_target: InvocationTarget::mock_service(),
+            invoke_input_journal: InvokeInputJournal::NoCachedJournal,
+        };
+
+        let mut paused_invocations = PausedInvocations::default();
+
+        assert!(paused_invocations.hold(invoke(invocation_id)).is_none());
+        assert!(paused_invocations
+            .hold(invoke(aborted_invocation_id))
+            .is_none());
+        let_assert!(
+            Some(OrderedAction::AckStoredEntry {
+                invocation_id: acked,
+                entry_index: 1,
+            }) = paused_invocations.hold(OrderedAction::AckStoredEntry {
+                invocation_id,
+                entry_index: 1,
+            })
+        );
+        assert!(acked == invocation_id);
+        let_assert!(
+            Some(OrderedAction::AbortInvocation(aborted)) =
+                paused_invocations.hold(OrderedAction::AbortInvocation(aborted_invocation_id))
+        );
+        assert!(aborted == aborted_invocation_id);
+
+        let_assert!([(held, _)] = paused_invocations.held.as_slice());
+        assert!(*held == invocation_id);
+    }
+
+    #[test]
+    fn invoker_leadership_transition_error_contains_partition_and_epoch() {
+        let err = Error::InvokerLeadershipTransition {
+            partition_id: PartitionId::from(7),
+            leader_epoch: LeaderEpoch::from(3),
+            source: NotRunningError,
+        };
+
+        assert!(
+            err.to_string()
+                == "invoker is unreachable while changing the leadership of partition 7 in leader \
+                epoch e3. This indicates a bug or the system is shutting down: not running"
+        );
+    }
+
+    /// Invoker which records the started invocations, the acknowledged entries and for which
+    /// partitions all invocations were aborted. It keeps the effect sender of the last registered
+    /// partition so that tests can report invoker effects.
+    #[derive(Clone, Default)]
+    pub(crate) struct RecordingInvoker {
+        pub(crate) invoked: Arc<Mutex<Vec<InvocationId>>>,
+        pub(crate) acked_entries: Arc<Mutex<Vec<(InvocationId, EntryIndex)>>>,
+        pub(crate) aborted_partitions: Arc<Mutex<Vec<PartitionLeaderEpoch>>>,
+        pub(crate) effects_tx: Arc<Mutex<Option<mpsc::Sender<Effect>>>>,
+    }
+
+    impl ServiceHandle<InvokerStorageReader<PartitionStore>> for RecordingInvoker {
+        type Future = future::Ready<Result<(), NotRunningError>>;
+
+        fn invoke(
+            &mut self,
+            _partition: PartitionLeaderEpoch,
+            invocation_id: InvocationId,
+            _invocation_target: InvocationTarget,
+            _journal: InvokeInputJournal,
+        ) -> Self::Future {
+            self.invoked.lock().unwrap().push(invocation_id);
+            future::ready(Ok(()))
+        }
+
+        fn notify_completion(
+            &mut self,
+            _partition: PartitionLeaderEpoch,
+            _invocation_id: InvocationId,
+            _completion: Completion,
+        ) -> Self::Future {
+            future::ready(Ok(()))
+        }
+
+        fn notify_stored_entry_ack(
+            &mut self,
+            _partition: PartitionLeaderEpoch,
+            invocation_id: InvocationId,
+            entry_index: EntryIndex,
+        ) -> Self::Future {
+            self.acked_entries
+                .lock()
+                .unwrap()
+                .push((invocation_id, entry_index));
+            future::ready(Ok(()))
+        }
+
+        fn abort_all_partition(&mut self, partition: PartitionLeaderEpoch) -> Self::Future {
+            self.aborted_partitions.lock().unwrap().push(partition);
+            future::ready(Ok(()))
+        }
+
+        fn abort_invocation(
+            &mut self,
+            _partition_leader_epoch: PartitionLeaderEpoch,
+            _invocation_id: InvocationId,
+        ) -> Self::Future {
+            future::ready(Ok(()))
+        }
+
+        fn register_partition(
+            &mut self,
+            _partition: PartitionLeaderEpoch,
+            _partition_key_range: RangeInclusive<PartitionKey>,
+            _storage_reader: InvokerStorageReader<PartitionStore>,
+            sender: mpsc::Sender<Effect>,
+        ) -> Self::Future {
+            *self.effects_tx.lock().unwrap() = Some(sender);
+            future::ready(Ok(()))
+        }
+    }
+
+    pub(crate) async fn create_partition_store(tc: &TaskCenter) -> anyhow::Result<PartitionStore> {
+        tc.run_in_scope_sync("db-manager-init", None, || {
+            RocksDbManager::init(Constant::new(CommonOptions::default()))
+        });
+
+        let all_partition_keys = 0..=PartitionKey::MAX;
+        let rocksdb_options = RocksDbOptions::default();
+        let partition_store_manager = PartitionStoreManager::create(
+            Constant::new(StorageOptions::default()),
+            Constant::new(rocksdb_options.clone()),
+            &[(PartitionId::MIN, all_partition_keys.clone())],
+        )
+        .await?;
+        let partition_store = partition_store_manager
+            .open_partition_store(
+                PartitionId::MIN,
+                all_partition_keys,
+                OpenMode::CreateIfMissing,
+                &rocksdb_options,
+            )
+            .await?;
+
+        Ok(partition_store)
+    }
+
+    async fn create_partition_storage(tc: &TaskCenter) -> anyhow::Result<PartitionStorage> {
+        Ok(PartitionStorage::from(create_partition_store(tc).await?))
+    }
+
+    pub(crate) async fn store_invoked_invocation(
+        partition_storage: &mut PartitionStorage,
+        invocation_id: InvocationId,
+    ) -> anyhow::Result<()> {
+        let mut transaction = partition_storage.create_transaction();
+        transaction
+            .store_invocation_status(
+                &invocation_id,
+                InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+            )
+            .await?;
+        Ok(transaction.commit().await?)
+    }
+
+    async fn leader(
+        invoker_abort_behavior: InvokerAbortBehavior,
+        invoker: RecordingInvoker,
+        partition_storage: &mut PartitionStorage,
+    ) -> Result<LeadershipState<RecordingInvoker>, Error> {
+        leader_with_bifrost(
+            invoker_abort_behavior,
+            invoker,
+            Bifrost::init().await,
+            partition_storage,
+        )
+        .await
+    }
+
+    fn follower(
+        invoker_abort_behavior: InvokerAbortBehavior,
+        invoker: RecordingInvoker,
+        bifrost: Bifrost,
+    ) -> LeadershipState<RecordingInvoker> {
+        let (state, _) = LeadershipState::follower(
+            PartitionId::MIN,
+            0..=PartitionKey::MAX,
+            None,
+            10,
+            10,
+            10,
+            invoker_abort_behavior,
+            invoker,
+            bifrost,
+            Networking::default(),
+        );
+        state
+    }
+
+    async fn leader_with_bifrost(
+        invoker_abort_behavior: InvokerAbortBehavior,
+        invoker: RecordingInvoker,
+        bifrost: Bifrost,
+        partition_storage: &mut PartitionStorage,
+    ) -> Result<LeadershipState<RecordingInvoker>, Error> {
+        let (state, _) = follower(invoker_abort_behavior, invoker, bifrost)
+            .become_leader(
+                EpochSequenceNumber::new(LeaderEpoch::INITIAL),
+                partition_storage,
+            )
+            .await?;
+
+        Ok(state)
+    }
+
+    #[test(tokio::test)]
+    async fn stepping_down_aborts_invocations_of_leader() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let state = follower(InvokerAbortBehavior::Abort, invoker, Bifrost::init().await);
+                assert!(!state.is_leader());
+
+                // stepping down is a no-op for followers
+                let (state, _) = state.become_follower().await?;
+                assert!(!state.is_leader());
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                let (mut state, _) = state
+                    .become_leader(
+                        EpochSequenceNumber::new(LeaderEpoch::INITIAL),
+                        &mut partition_storage,
+                    )
+                    .await?;
+                assert!(state.is_leader());
+
+                // leaders abort right away by default
+                assert!(!state.start_draining(&mut partition_storage).await?);
+                assert!(state.drain_deadline().is_none());
+
+                let (state, _) = state.become_follower().await?;
+                assert!(!state.is_leader());
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    fn idempotency_id(idempotency_key: &str) -> IdempotencyId {
+        IdempotencyId::new(
+            "greeter".into(),
+            None,
+            "greet".into(),
+            ByteString::from(idempotency_key.to_owned()),
+        )
+    }
+
+    async fn store_idempotency_id(
+        partition_storage: &mut PartitionStorage,
+        idempotency_key: &str,
+    ) -> anyhow::Result<()> {
+        let mut transaction = partition_storage.create_transaction();
+        transaction
+            .put_idempotency_metadata(
+                &idempotency_id(idempotency_key),
+                IdempotencyMetadata {
+                    invocation_id: InvocationId::mock_random(),
+                },
+            )
+            .await;
+        Ok(transaction.commit().await?)
+    }
+
+    #[test(tokio::test)]
+    async fn partition_storage_counts_stored_idempotency_ids() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+
+        store_idempotency_id(&mut partition_storage, "first").await?;
+        store_idempotency_id(&mut partition_storage, "second").await?;
+        // replaying a command stores the same idempotency id again
+        store_idempotency_id(&mut partition_storage, "second").await?;
+
+        let mut transaction = partition_storage.create_transaction();
+        transaction
+            .delete_idempotency_metadata(&idempotency_id("first"))
+            .await;
+        transaction.commit().await?;
+
+        assert!(partition_storage.count_idempotency_ids().await? == 1);
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn draining_leader_defers_abort_until_stepping_down() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+        let timeout = Duration::from_secs(30);
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let mut state = leader(
+                    InvokerAbortBehavior::DrainThenAbort {
+                        timeout: timeout.into(),
+                    },
+                    invoker,
+                    &mut partition_storage,
+                )
+                .await?;
+
+                assert!(state.is_leader());
+
+                let drain_started = Instant::now();
+                assert!(state.start_draining(&mut partition_storage).await?);
+                let_assert!(Some(deadline) = state.drain_deadline());
+                assert!(deadline >= drain_started + timeout);
+                assert!(deadline <= Instant::now() + timeout);
+
+                // asking again doesn't extend the deadline
+                assert!(state.start_draining(&mut partition_storage).await?);
+                assert!(state.drain_deadline() == Some(deadline));
+
+                // new invocations are held back while draining and nothing is aborted yet
+                let_assert!(LeadershipState::Leader { leader_state, .. } = &state);
+                assert!(leader_state.paused_invocations.is_some());
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                // there are no in-flight invocations, so the leader doesn't need to wait
+                assert!(state.is_drained());
+
+                let (state, _) = state.become_follower().await?;
+                assert!(state.drain_deadline().is_none());
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test(start_paused = true))]
+    async fn draining_leader_waits_for_invoked_invocations_until_timeout() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+        let timeout = Duration::from_secs(30);
+
+        store_invoked_invocation(&mut partition_storage, InvocationId::mock_random()).await?;
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let mut state = leader(
+                    InvokerAbortBehavior::DrainThenAbort {
+                        timeout: timeout.into(),
+                    },
+                    invoker,
+                    &mut partition_storage,
+                )
+                .await?;
+
+                let drain_started = Instant::now();
+                assert!(state.start_draining(&mut partition_storage).await?);
+                let_assert!(Some(deadline) = state.drain_deadline());
+
+                // resuming doesn't start the held back invocations while draining
+                state.resume_invocations().await?;
+                let_assert!(LeadershipState::Leader { leader_state, .. } = &state);
+                assert!(leader_state.paused_invocations.is_some());
+
+                // the invoked invocation keeps the leader draining until the deadline
+                tokio::time::sleep(timeout - Duration::from_secs(1)).await;
+                assert!(!state.is_drained());
+                assert!(state.drain_deadline() == Some(deadline));
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                tokio::time::sleep_until(deadline).await;
+                assert!(Instant::now() >= drain_started + timeout);
+                assert!(!state.is_drained());
+
+                let (state, _) = state.become_follower().await?;
+                assert!(!state.is_leader());
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test(start_paused = true))]
+    async fn invoker_actions_are_not_held_back_by_independent_actions() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let invoked = Arc::clone(&invoker.invoked);
+        let acked_entries = Arc::clone(&invoker.acked_entries);
+        let first_invocation_id = InvocationId::mock_random();
+        let second_invocation_id = InvocationId::mock_random();
+        let invoke = |invocation_id| Action::Invoke {
+            invocation_id,
+            invocation_target: InvocationTarget::mock_service(),
+            invoke_input_journal: InvokeInputJournal::NoCachedJournal,
+        };
+        let ack = |invocation_id| Action::AckStoredEntry {
+            invocation_id,
+            entry_index: 1,
+        };
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                // keep the action effects stream so that its channel fills up
+                let (mut state, _action_effects) =
+                    follower(InvokerAbortBehavior::Abort, invoker, Bifrost::init().await)
+                        .become_leader(
+                            EpochSequenceNumber::new(LeaderEpoch::INITIAL),
+                            &mut partition_storage,
+                        )
+                        .await?;
+
+                // one more cleanup than the action effects channel can hold
+                let cleanups = (0..=10).map(|_| Action::ScheduleInvocationStatusCleanup {
+                    invocation_id: InvocationId::mock_random(),
+                    retention: Duration::from_secs(60),
+                });
+                let actions = cleanups.chain([
+                    invoke(first_invocation_id),
+                    ack(first_invocation_id),
+                    invoke(second_invocation_id),
+                    ack(second_invocation_id),
+                ]);
+
+                // the last cleanup waits for the partition processor to make room ...
+                assert!(tokio::time::timeout(
+                    Duration::from_secs(1),
+                    state.handle_actions(actions)
+                )
+                .await
+                .is_err());
+                // ... while the invoker actions have been handled in order
+                assert!(*invoked.lock().unwrap() == [first_invocation_id, second_invocation_id]);
+                assert!(
+                    *acked_entries.lock().unwrap()
+                        == [(first_invocation_id, 1), (second_invocation_id, 1)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn draining_leader_stops_waiting_for_ended_and_aborted_invocations() -> anyhow::Result<()>
+    {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let ended_invocation_id = InvocationId::mock_random();
+        let aborted_invocation_id = InvocationId::mock_random();
+        store_invoked_invocation(&mut partition_storage, ended_invocation_id).await?;
+        store_invoked_invocation(&mut partition_storage, aborted_invocation_id).await?;
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let mut state = leader(
+                    InvokerAbortBehavior::DrainThenAbort {
+                        timeout: Duration::from_secs(30).into(),
+                    },
+                    RecordingInvoker::default(),
+                    &mut partition_storage,
+                )
+                .await?;
+                assert!(state.start_draining(&mut partition_storage).await?);
+                assert!(!state.is_drained());
+
+                state
+                    .handle_action_effect([ActionEffect::Invoker(Effect {
+                        invocation_id: ended_invocation_id,
+                        kind: EffectKind::End,
+                    })])
+                    .await?;
+                assert!(!state.is_drained());
+
+                state
+                    .handle_actions([Action::AbortInvocation(aborted_invocation_id)].into_iter())
+                    .await?;
+                assert!(state.is_drained());
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn new_leader_reloads_timers_from_storage() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+
+        let timer = TimerKeyValue::complete_journal_entry(
+            MillisSinceEpoch::UNIX_EPOCH,
+            InvocationId::mock_random(),
+            1,
+        );
+        let mut transaction = partition_storage.create_transaction();
+        let (timer_key, timer_value) = timer.clone().into_inner();
+        transaction.store_timer(timer_key, timer_value).await?;
+        transaction.commit().await?;
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let mut state = leader(
+                    InvokerAbortBehavior::Abort,
+                    RecordingInvoker::default(),
+                    &mut partition_storage,
+                )
+                .await?;
+                let fired =
+                    tokio::time::timeout(Duration::from_secs(10), state.run_timer()).await?;
+                assert!(fired.key() == timer.key());
+
+                // the timer is only removed from storage once its fire command is applied
+                let (state, _) = state.become_follower().await?;
+                assert!(!state.is_leader());
+
+                let (mut state, _) = state
+                    .become_leader(
+                        EpochSequenceNumber::new(LeaderEpoch::INITIAL.next()),
+                        &mut partition_storage,
+                    )
+                    .await?;
+                let fired =
+                    tokio::time::timeout(Duration::from_secs(10), state.run_timer()).await?;
+                assert!(fired.key() == timer.key());
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn timer_of_removed_service_is_deleted_instead_of_fired() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+
+        let (invocation_id, invocation_target) =
+            InvocationId::mock_with(InvocationTarget::mock_service());
+        let mut schema = MockServiceMetadataResolver::default();
+        schema.add(ServiceMetadata::mock_service(
+            invocation_target.service_name(),
+            [invocation_target.handler_name()],
+        ));
+
+        // schedule a delayed invocation of the service, which is due right away
+        let timer = TimerKeyValue::invoke(
+            MillisSinceEpoch::UNIX_EPOCH,
+            ServiceInvocation::initialize(invocation_id, invocation_target, Source::Ingress),
+        );
+        let mut transaction = partition_storage.create_transaction();
+        let (timer_key, timer_value) = timer.clone().into_inner();
+        transaction.store_timer(timer_key, timer_value).await?;
+        transaction.commit().await?;
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let bifrost = Bifrost::init().await;
+                let mut state = leader_with_bifrost(
+                    InvokerAbortBehavior::Abort,
+                    RecordingInvoker::default(),
+                    bifrost.clone(),
+                    &mut partition_storage,
+                )
+                .await?;
+
+                let_assert!(
+                    ActionEffect::Timer(_) =
+                        fired_timer_effect(timer.clone(), &schema, OrphanedTimerBehavior::Drop)
+                );
+
+                // the service is removed before the timer fires
+                let schema = MockServiceMetadataResolver::default();
+                let fired =
+                    tokio::time::timeout(Duration::from_secs(10), state.run_timer()).await?;
+                let action_effect = fired_timer_effect(fired, &schema, OrphanedTimerBehavior::Drop);
+                state.handle_action_effect([action_effect]).await?;
+
+                let log_id = LogId::from(
+                    metadata()
+                        .partition_table()
+                        .expect("partition table is available")
+                        .find_partition_id(invocation_id.partition_key())?,
+                );
+                let record = bifrost.read_next_single(log_id, Lsn::INVALID).await?;
+                let_assert!(Record::Data(data) = record.record);
+                let envelope = StorageCodec::decode::<Envelope, _>(&mut data.into_body())?;
+                let_assert!(Command::DeleteTimer(deleted_timer) = envelope.command);
+                assert!(deleted_timer.key() == timer.key());
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn paused_leader_only_forwards_non_invoke_actions() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let mut partition_storage = create_partition_storage(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let invoked = Arc::clone(&invoker.invoked);
+        let acked_entries = Arc::clone(&invoker.acked_entries);
+        let invocation_id = InvocationId::mock_random();
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let mut state =
+                    leader(InvokerAbortBehavior::Abort, invoker, &mut partition_storage).await?;
+                state.pause_invocations();
+
+                state
+                    .handle_actions(
+                        [
+                            Action::Invoke {
+                                invocation_id,
+                                invocation_target: InvocationTarget::mock_service(),
+                                invoke_input_journal: InvokeInputJournal::NoCachedJournal,
+                            },
+                            Action::AckStoredEntry {
+                                invocation_id,
+                                entry_index: 1,
+                            },
+                        ]
+                        .into_iter(),
+                    )
+                    .await?;
+
+                assert!(invoked.lock().unwrap().is_empty());
+                assert!(*acked_entries.lock().unwrap() == [(invocation_id, 1)]);
+
+                // the held back invocation is started once resumed
+                state.resume_invocations().await?;
+                assert!(*invoked.lock().unwrap() == [invocation_id]);
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+}
@@ -9,18 +9,24 @@
 // by the Apache License, Version 2.0.
 
 use crate::metric_definitions::{
-    PARTITION_ACTUATOR_HANDLED, PARTITION_LABEL, PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION,
-    PARTITION_TIMER_DUE_HANDLED, PP_APPLY_RECORD_DURATION,
+    PARTITION_ACTUATOR_HANDLED, PARTITION_DEDUPLICATED_COMMANDS, PARTITION_IDEMPOTENCY_IDS,
+    PARTITION_LABEL, PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION,
+    PARTITION_ORPHANED_TIMERS_DROPPED, PARTITION_PROPOSAL_BATCH_SIZE,
+    PARTITION_PROPOSAL_QUEUE_DEPTH, PARTITION_TIMER_DUE_HANDLED, PP_APPLY_RECORD_DURATION,
 };
 use crate::partition::leadership::{ActionEffect, LeadershipState};
 use crate::partition::state_machine::{ActionCollector, Effects, StateMachine};
 use crate::partition::storage::{DedupSequenceNumberResolver, PartitionStorage, Transaction};
 use assert2::let_assert;
+use futures::future::OptionFuture;
 use futures::TryStreamExt as _;
-use metrics::{counter, histogram};
+use metrics::{counter, gauge, histogram, Counter};
 use restate_core::metadata;
 use restate_network::Networking;
 use restate_partition_store::{PartitionStore, RocksDBTransaction};
+use restate_schema_api::service::ServiceMetadataResolver;
+use restate_storage_api::timer_table::Timer;
+use restate_types::config::{InvokerAbortBehavior, OrphanedTimerBehavior, WorkerOptions};
 use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::processors::{PartitionProcessorStatus, ReplayStatus, RunMode};
 use restate_types::time::MillisSinceEpoch;
@@ -31,7 +37,7 @@ use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, watch};
 use tokio::time::MissedTickBehavior;
 use tokio_stream::StreamExt;
-use tracing::{debug, instrument, trace, Span};
+use tracing::{debug, instrument, trace, warn, Span};
 
 mod action_effect_handler;
 mod leadership;
@@ -48,12 +54,20 @@ use restate_storage_api::deduplication_table::{
 use restate_storage_api::StorageError;
 use restate_types::logs::{LogId, Lsn, SequenceNumber};
 use restate_wal_protocol::control::AnnounceLeader;
+use restate_wal_protocol::timer::TimerKeyValue;
 use restate_wal_protocol::{Command, Destination, Envelope, Header};
 
 use self::storage::invoker::InvokerStorageReader;
 
 /// Control messages from Manager to individual partition processor instances.
-pub enum PartitionProcessorControlCommand {}
+#[derive(Debug)]
+pub enum PartitionProcessorControlCommand {
+    /// Stop starting new invocations while still processing timers and completions.
+    PauseInvocations,
+    ResumeInvocations,
+    /// Relinquish leadership and become a follower. Has no effect on followers.
+    StepDown,
+}
 
 #[derive(Debug)]
 pub(super) struct PartitionProcessor<RawEntryCodec, InvokerInputSender> {
@@ -61,7 +75,12 @@ pub(super) struct PartitionProcessor<RawEntryCodec, InvokerInputSender> {
     pub partition_key_range: RangeInclusive<PartitionKey>,
 
     num_timers_in_memory_limit: Option<usize>,
-    channel_size: usize,
+    invoker_effects_channel_size: usize,
+    shuffle_channel_size: usize,
+    action_effects_channel_size: usize,
+    orphaned_timers: OrphanedTimerBehavior,
+    invoker_abort_behavior: InvokerAbortBehavior,
+    proposal_batching: Option<ProposalBatching>,
 
     status: PartitionProcessorStatus,
     invoker_tx: InvokerInputSender,
@@ -83,7 +102,12 @@ where
         partition_key_range: RangeInclusive<PartitionKey>,
         status: PartitionProcessorStatus,
         num_timers_in_memory_limit: Option<usize>,
-        channel_size: usize,
+        invoker_effects_channel_size: usize,
+        shuffle_channel_size: usize,
+        action_effects_channel_size: usize,
+        orphaned_timers: OrphanedTimerBehavior,
+        invoker_abort_behavior: InvokerAbortBehavior,
+        proposal_batching: Option<ProposalBatching>,
         control_rx: mpsc::Receiver<PartitionProcessorControlCommand>,
         status_watch_tx: watch::Sender<PartitionProcessorStatus>,
         invoker_tx: InvokerInputSender,
@@ -93,7 +117,12 @@ where
             partition_key_range,
             status,
             num_timers_in_memory_limit,
-            channel_size,
+            invoker_effects_channel_size,
+            shuffle_channel_size,
+            action_effects_channel_size,
+            orphaned_timers,
+            invoker_abort_behavior,
+            proposal_batching,
             invoker_tx,
             control_rx,
             status_watch_tx,
@@ -112,7 +141,12 @@ where
             partition_id,
             partition_key_range,
             num_timers_in_memory_limit,
-            channel_size,
+            invoker_effects_channel_size,
+            shuffle_channel_size,
+            action_effects_channel_size,
+            orphaned_timers,
+            invoker_abort_behavior,
+            proposal_batching,
             invoker_tx,
             ..
         } = self;
@@ -167,12 +201,16 @@ where
 
         let mut action_collector = ActionCollector::default();
         let mut effects = Effects::default();
+        let mut proposal_batcher = ProposalBatcher::new(proposal_batching);
 
         let (mut state, mut action_effect_stream) = LeadershipState::follower(
             partition_id,
             partition_key_range.clone(),
             num_timers_in_memory_limit,
-            channel_size,
+            invoker_effects_channel_size,
+            shuffle_channel_size,
+            action_effects_channel_size,
+            invoker_abort_behavior,
             invoker_tx,
             bifrost,
             networking,
@@ -190,13 +228,36 @@ where
         let record_actions_latency = histogram!(PARTITION_LEADER_HANDLE_ACTION_BATCH_DURATION);
         let actuator_effects_handled = counter!(PARTITION_ACTUATOR_HANDLED);
         let timer_events_handled = counter!(PARTITION_TIMER_DUE_HANDLED);
+        let proposal_batch_size = histogram!(PARTITION_PROPOSAL_BATCH_SIZE);
+        let deduplicated_commands =
+            counter!(PARTITION_DEDUPLICATED_COMMANDS, PARTITION_LABEL => partition_id_str);
+        let proposal_queue_depth =
+            gauge!(PARTITION_PROPOSAL_QUEUE_DEPTH, PARTITION_LABEL => partition_id_str);
+        let idempotency_ids =
+            gauge!(PARTITION_IDEMPOTENCY_IDS, PARTITION_LABEL => partition_id_str);
         loop {
+            let mut step_down = false;
             tokio::select! {
                 _ = &mut cancellation => break,
-                _command = self.control_rx.recv() => {
-                    // todo: handle leadership change requests here
+                Some(command) = self.control_rx.recv() => {
+                    match command {
+                        PartitionProcessorControlCommand::PauseInvocations => state.pause_invocations(),
+                        PartitionProcessorControlCommand::ResumeInvocations => state.resume_invocations().await?,
+                        PartitionProcessorControlCommand::StepDown => {
+                            if state.is_leader() {
+                                step_down = !state.start_draining(&mut partition_storage).await?;
+                            } else {
+                                debug!("Ignoring request to step down since partition processor is not the leader");
+                            }
+                        }
+                    }
+                }
+                Some(()) = OptionFuture::from(state.drain_deadline().map(tokio::time::sleep_until)) => {
+                    debug!("Timeout elapsed while draining, aborting the remaining in-flight invocations");
+                    step_down = true;
                 }
                 _ = status_update_timer.tick() => {
+                    proposal_queue_depth.set(state.proposal_queue_depth() as f64);
                     self.status_watch_tx.send_modify(|old| {
                         old.clone_from(&self.status);
                         old.updated_at = MillisSinceEpoch::now();
@@ -224,7 +285,8 @@ where
                             &mut transaction,
                             &mut action_collector,
                             &mut effects, state.is_leader(),
-                            &partition_key_range)
+                            &partition_key_range,
+                            &deduplicated_commands)
                         .await?;
 
                     if let Some(announce_leader) = leadership_change {
@@ -245,6 +307,7 @@ where
                         if announce_leader.node_id == metadata().my_node_id() {
                             let was_follower = !state.is_leader();
                             (state, action_effect_stream) = state.become_leader(new_esn, &mut partition_storage).await?;
+                            proposal_batcher.clear();
                             self.status.effective_mode = Some(RunMode::Leader);
                             if was_follower {
                                 Span::current().record("is_leader", state.is_leader());
@@ -253,17 +316,28 @@ where
                         } else {
                             let was_leader = state.is_leader();
                             (state, action_effect_stream) = state.become_follower().await?;
+                            proposal_batcher.clear();
                             self.status.effective_mode = Some(RunMode::Follower);
                             if was_leader {
                                 Span::current().record("is_leader", state.is_leader());
                                 debug!(leader_epoch = %new_esn.leader_epoch, "Partition leadership lost to {}", announce_leader.node_id);
                             }
                         }
+                        // publish leadership changes right away instead of waiting for the next status update
+                        self.status_watch_tx.send_modify(|old| {
+                            old.clone_from(&self.status);
+                            old.updated_at = MillisSinceEpoch::now();
+                        });
+                        // only leaders report the stored idempotency ids
+                        idempotency_ids.set(if state.is_leader() { state_machine.idempotency_ids() as f64 } else { 0.0 });
                         apply_record_latency.record(command_start.elapsed());
                     } else {
                         // Commit our changes and notify actuators about actions if we are the leader
                         transaction.commit().await?;
                         apply_record_latency.record(command_start.elapsed());
+                        if state.is_leader() {
+                            idempotency_ids.set(state_machine.idempotency_ids() as f64);
+                        }
                         let actions_start = Instant::now();
                         state.handle_actions(action_collector.drain(..)).await?;
                         record_actions_latency.record(actions_start.elapsed());
@@ -271,14 +345,40 @@ where
                 },
                 action_effects = action_effect_stream.next() => {
                     let action_effects = action_effects.ok_or_else(|| anyhow::anyhow!("action effect stream is closed"))?;
-                    actuator_effects_handled.increment(action_effects.len() as u64);
-                    state.handle_action_effect(action_effects).await?;
+                    for batch in proposal_batcher.push(action_effects) {
+                        proposal_batch_size.record(batch.len() as f64);
+                        actuator_effects_handled.increment(batch.len() as u64);
+                        state.handle_action_effect(batch).await?;
+                    }
+                },
+                Some(()) = OptionFuture::from(proposal_batcher.deadline().map(tokio::time::sleep_until)) => {
+                    let batch = proposal_batcher.take();
+                    proposal_batch_size.record(batch.len() as f64);
+                    actuator_effects_handled.increment(batch.len() as u64);
+                    state.handle_action_effect(batch).await?;
                 },
                 timer = state.run_timer() => {
                     timer_events_handled.increment(1);
-                    state.handle_action_effect([ActionEffect::Timer(timer)]).await?;
+                    let action_effect = fired_timer_effect(timer, metadata().schema().as_ref(), orphaned_timers);
+                    state.handle_action_effect([action_effect]).await?;
                 },
             }
+
+            // a draining leader steps down as soon as its in-flight invocations are done
+            if step_down || state.is_drained() {
+                (state, action_effect_stream) = state.become_follower().await?;
+                proposal_batcher.clear();
+                self.status.effective_mode = Some(RunMode::Follower);
+                Span::current().record("is_leader", state.is_leader());
+                debug!("Partition leadership relinquished");
+                // only leaders report the stored idempotency ids
+                idempotency_ids.set(0.0);
+                // publish leadership changes right away instead of waiting for the next status update
+                self.status_watch_tx.send_modify(|old| {
+                    old.clone_from(&self.status);
+                    old.updated_at = MillisSinceEpoch::now();
+                });
+            }
         }
 
         debug!(restate.node = %metadata().my_node_id(), %partition_id, "Shutting partition processor down.");
@@ -296,9 +396,16 @@ where
     {
         let inbox_seq_number = partition_storage.load_inbox_seq_number().await?;
         let outbox_seq_number = partition_storage.load_outbox_seq_number().await?;
+        // the only full scan of the idempotency table, afterwards the state machine keeps the
+        // count up to date while applying commands
+        let idempotency_ids = partition_storage.count_idempotency_ids().await?;
 
-        let state_machine =
-            StateMachine::new(inbox_seq_number, outbox_seq_number, partition_key_range);
+        let state_machine = StateMachine::new(
+            inbox_seq_number,
+            outbox_seq_number,
+            partition_key_range,
+            idempotency_ids,
+        );
 
         Ok(state_machine)
     }
@@ -313,6 +420,7 @@ where
         effects: &mut Effects,
         is_leader: bool,
         partition_key_range: &RangeInclusive<PartitionKey>,
+        deduplicated_commands: &Counter,
     ) -> Result<Option<AnnounceLeader>, state_machine::Error>
     where
         Codec: restate_types::journal::raw::RawEntryCodec + Default + Debug,
@@ -341,6 +449,7 @@ where
                         "Ignoring outdated or duplicate message: {:?}",
                         envelope.header
                     );
+                    deduplicated_commands.increment(1);
                     return Ok(None);
                 }
                 transaction
@@ -401,6 +510,119 @@ where
     }
 }
 
+/// Coalesces the action effects of the leader into larger batches which are proposed together.
+/// Batches are bounded by size and by the time to wait for further action effects.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ProposalBatching {
+    max_delay: Duration,
+    max_size: usize,
+}
+
+impl ProposalBatching {
+    pub(super) fn from_options(options: &WorkerOptions) -> Option<Self> {
+        options
+            .proposal_batch_delay()
+            .map(|max_delay| ProposalBatching {
+                max_delay,
+                max_size: options.proposal_batch_max_size(),
+            })
+    }
+}
+
+/// Collects the action effects of a leader into proposal batches. Without [`ProposalBatching`],
+/// every set of action effects is proposed right away.
+struct ProposalBatcher {
+    batching: Option<ProposalBatching>,
+    pending: Vec<ActionEffect>,
+    deadline: Option<tokio::time::Instant>,
+}
+
+impl ProposalBatcher {
+    fn new(batching: Option<ProposalBatching>) -> Self {
+        Self {
+            batching,
+            pending: Vec::new(),
+            deadline: None,
+        }
+    }
+
+    /// Adds the action effects to the pending batch and returns the batches which are full.
+    /// A batch never holds more than the configured maximum number of action effects.
+    fn push(&mut self, action_effects: Vec<ActionEffect>) -> Vec<Vec<ActionEffect>> {
+        let Some(batching) = &self.batching else {
+            return vec![action_effects];
+        };
+
+        self.pending.extend(action_effects);
+        let mut full_batches = Vec::new();
+        while self.pending.len() >= batching.max_size {
+            let remaining = self.pending.split_off(batching.max_size);
+            full_batches.push(std::mem::replace(&mut self.pending, remaining));
+            self.deadline = None;
+        }
+
+        if !self.pending.is_empty() && self.deadline.is_none() {
+            self.deadline = Some(tokio::time::Instant::now() + batching.max_delay);
+        }
+
+        full_batches
+    }
+
+    /// Instant at which the pending batch needs to be proposed, if there is one.
+    fn deadline(&self) -> Option<tokio::time::Instant> {
+        self.deadline
+    }
+
+    /// Takes the pending batch once its delay has elapsed.
+    fn take(&mut self) -> Vec<ActionEffect> {
+        self.deadline = None;
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Drops the pending batch since it belongs to a previous leadership.
+    fn clear(&mut self) {
+        self.deadline = None;
+        self.pending.clear();
+    }
+}
+
+/// Returns the effect to propose for a fired timer. Timers invoking a service which no longer
+/// exists are handled according to the configured [`OrphanedTimerBehavior`].
+fn fired_timer_effect(
+    timer: TimerKeyValue,
+    schema: &impl ServiceMetadataResolver,
+    orphaned_timers: OrphanedTimerBehavior,
+) -> ActionEffect {
+    let Timer::Invoke(service_invocation) = timer.value() else {
+        return ActionEffect::Timer(timer);
+    };
+
+    let service_name = service_invocation.invocation_target.service_name();
+    if schema.resolve_latest_service_type(service_name).is_some() {
+        return ActionEffect::Timer(timer);
+    }
+
+    match orphaned_timers {
+        OrphanedTimerBehavior::Log => {
+            warn!(
+                restate.invocation.id = %timer.invocation_id(),
+                "Timer fired for service '{}' which no longer exists",
+                service_name
+            );
+            ActionEffect::Timer(timer)
+        }
+        OrphanedTimerBehavior::Drop => {
+            debug!(
+                restate.invocation.id = %timer.invocation_id(),
+                "Dropping timer for service '{}' which no longer exists",
+                service_name
+            );
+            counter!(PARTITION_ORPHANED_TIMERS_DROPPED).increment(1);
+            ActionEffect::DeleteTimer(timer)
+        }
+    }
+}
+
 fn is_targeted_to_me<'a>(
     header: &'a Header,
     partition_key_range: &RangeInclusive<PartitionKey>,
@@ -435,3 +657,318 @@ async fn is_outdated_or_duplicate(
 
     Ok(is_duplicate)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::partition::leadership::tests::{
+        create_partition_store, store_invoked_invocation, RecordingInvoker,
+    };
+    use restate_core::{task_center, TaskKind, TestCoreEnv};
+    use restate_invoker_api::{Effect, EffectKind};
+    use restate_schema_api::service::test_util::MockServiceMetadataResolver;
+    use restate_schema_api::service::ServiceMetadata;
+    use restate_service_protocol::codec::ProtobufRawEntryCodec;
+    use restate_test_util::{assert, let_assert};
+    use restate_types::identifiers::{InvocationId, LeaderEpoch};
+    use restate_types::invocation::{InvocationTarget, ServiceInvocation, Source};
+    use restate_types::logs::Payload;
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[tokio::test(start_paused = true)]
+    async fn proposals_are_batched_up_to_max_size() {
+        let mut proposal_batcher = ProposalBatcher::new(Some(ProposalBatching {
+            max_delay: Duration::from_millis(10),
+            max_size: 3,
+        }));
+        let cleanup_timers = |count| {
+            (0..count)
+                .map(|_| {
+                    ActionEffect::ScheduleCleanupTimer(InvocationId::mock_random(), Duration::ZERO)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(proposal_batcher.push(cleanup_timers(1)).is_empty());
+        let_assert!(Some(deadline) = proposal_batcher.deadline());
+        assert!(deadline == tokio::time::Instant::now() + Duration::from_millis(10));
+
+        // larger sets of action effects are split to respect the maximum batch size
+        let full_batches = proposal_batcher.push(cleanup_timers(6));
+        assert!(full_batches.len() == 2);
+        assert!(full_batches.iter().all(|batch| batch.len() == 3));
+
+        // the remaining action effect is proposed once the delay elapses
+        let_assert!(Some(deadline) = proposal_batcher.deadline());
+        tokio::time::sleep_until(deadline).await;
+        assert!(proposal_batcher.take().len() == 1);
+        assert!(proposal_batcher.deadline().is_none());
+    }
+
+    #[test]
+    fn proposals_are_not_batched_without_batching() {
+        let mut proposal_batcher = ProposalBatcher::new(None);
+        let action_effects = (0..5)
+            .map(|_| {
+                ActionEffect::ScheduleCleanupTimer(InvocationId::mock_random(), Duration::ZERO)
+            })
+            .collect();
+
+        let batches = proposal_batcher.push(action_effects);
+        assert!(batches.len() == 1);
+        assert!(batches[0].len() == 5);
+        assert!(proposal_batcher.deadline().is_none());
+    }
+
+    #[test]
+    fn orphaned_timers_are_dropped() {
+        let (invocation_id, invocation_target) =
+            InvocationId::mock_with(InvocationTarget::mock_service());
+        let mut schema = MockServiceMetadataResolver::default();
+        schema.add(ServiceMetadata::mock_service(
+            invocation_target.service_name(),
+            [invocation_target.handler_name()],
+        ));
+
+        let timer = TimerKeyValue::invoke(
+            MillisSinceEpoch::now(),
+            ServiceInvocation::initialize(invocation_id, invocation_target, Source::Ingress),
+        );
+
+        let_assert!(
+            ActionEffect::Timer(_) =
+                fired_timer_effect(timer.clone(), &schema, OrphanedTimerBehavior::Drop)
+        );
+
+        // the target service has been removed in the meantime
+        let schema = MockServiceMetadataResolver::default();
+        let_assert!(
+            ActionEffect::Timer(_) =
+                fired_timer_effect(timer.clone(), &schema, OrphanedTimerBehavior::Log)
+        );
+        let_assert!(
+            ActionEffect::DeleteTimer(_) =
+                fired_timer_effect(timer, &schema, OrphanedTimerBehavior::Drop)
+        );
+    }
+
+    /// Runs a partition processor and returns once it has become the leader of its partition.
+    async fn start_leader(
+        invoker: RecordingInvoker,
+        invoker_abort_behavior: InvokerAbortBehavior,
+        partition_store: PartitionStore,
+    ) -> anyhow::Result<(
+        mpsc::Sender<PartitionProcessorControlCommand>,
+        watch::Receiver<PartitionProcessorStatus>,
+    )> {
+        let mut bifrost = Bifrost::init().await;
+        let header = Header {
+            dest: Destination::Processor {
+                partition_key: 0,
+                dedup: None,
+            },
+            source: restate_wal_protocol::Source::ControlPlane {},
+        };
+        let envelope = Envelope::new(
+            header,
+            Command::AnnounceLeader(AnnounceLeader {
+                node_id: metadata().my_node_id(),
+                leader_epoch: LeaderEpoch::INITIAL,
+            }),
+        );
+        bifrost
+            .append(
+                LogId::from(PartitionId::MIN),
+                Payload::new(envelope.to_bytes()?),
+            )
+            .await?;
+
+        let (control_tx, control_rx) = mpsc::channel(1);
+        let (status_tx, mut status_rx) =
+            watch::channel(PartitionProcessorStatus::new(RunMode::Leader));
+        let processor = PartitionProcessor::<ProtobufRawEntryCodec, _>::new(
+            PartitionId::MIN,
+            0..=PartitionKey::MAX,
+            PartitionProcessorStatus::new(RunMode::Leader),
+            None,
+            10,
+            10,
+            10,
+            OrphanedTimerBehavior::default(),
+            invoker_abort_behavior,
+            None,
+            control_rx,
+            status_tx,
+            invoker,
+        );
+        task_center().spawn(
+            TaskKind::PartitionProcessor,
+            "partition-processor",
+            Some(PartitionId::MIN),
+            processor.run(Networking::default(), bifrost, partition_store),
+        )?;
+
+        status_rx
+            .wait_for(PartitionProcessorStatus::is_effective_leader)
+            .await?;
+        Ok((control_tx, status_rx))
+    }
+
+    #[test(tokio::test)]
+    async fn leader_steps_down_on_command() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let partition_store = create_partition_store(&env.tc).await?;
+        let invoker = RecordingInvoker::default();
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let (control_tx, mut status_rx) =
+                    start_leader(invoker, InvokerAbortBehavior::Abort, partition_store).await?;
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                control_tx
+                    .send(PartitionProcessorControlCommand::StepDown)
+                    .await?;
+                status_rx
+                    .wait_for(|status| status.effective_mode == Some(RunMode::Follower))
+                    .await?;
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                // stepping down again is a no-op for the follower
+                control_tx
+                    .send(PartitionProcessorControlCommand::StepDown)
+                    .await?;
+                status_rx.changed().await?;
+                assert!(status_rx.borrow().effective_mode == Some(RunMode::Follower));
+                assert!(aborted_partitions.lock().unwrap().len() == 1);
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test(start_paused = true))]
+    async fn draining_leader_steps_down_once_invocations_are_done() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let partition_store = create_partition_store(&env.tc).await?;
+        let invocation_id = InvocationId::mock_random();
+        store_invoked_invocation(
+            &mut PartitionStorage::from(partition_store.clone()),
+            invocation_id,
+        )
+        .await?;
+        let invoker = RecordingInvoker::default();
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+        let effects_tx = Arc::clone(&invoker.effects_tx);
+        let timeout = Duration::from_secs(30);
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let (control_tx, mut status_rx) = start_leader(
+                    invoker,
+                    InvokerAbortBehavior::DrainThenAbort {
+                        timeout: timeout.into(),
+                    },
+                    partition_store,
+                )
+                .await?;
+
+                control_tx
+                    .send(PartitionProcessorControlCommand::StepDown)
+                    .await?;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                assert!(status_rx.borrow().is_effective_leader());
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                // the leader steps down as soon as the invoker is done with the invocation
+                let effects_tx = effects_tx
+                    .lock()
+                    .unwrap()
+                    .clone()
+                    .expect("partition is registered at the invoker");
+                effects_tx
+                    .send(Effect {
+                        invocation_id,
+                        kind: EffectKind::End,
+                    })
+                    .await?;
+                tokio::time::timeout(
+                    timeout / 2,
+                    status_rx.wait_for(|status| !status.is_effective_leader()),
+                )
+                .await??;
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test(tokio::test(start_paused = true))]
+    async fn draining_leader_steps_down_once_deadline_passed() -> anyhow::Result<()> {
+        let env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let partition_store = create_partition_store(&env.tc).await?;
+        let invocation_id = InvocationId::mock_random();
+        store_invoked_invocation(
+            &mut PartitionStorage::from(partition_store.clone()),
+            invocation_id,
+        )
+        .await?;
+        let invoker = RecordingInvoker::default();
+        let invoked = Arc::clone(&invoker.invoked);
+        let aborted_partitions = Arc::clone(&invoker.aborted_partitions);
+        let timeout = Duration::from_secs(30);
+
+        env.tc
+            .run_in_scope("test", Some(PartitionId::MIN), async move {
+                let (control_tx, mut status_rx) = start_leader(
+                    invoker,
+                    InvokerAbortBehavior::DrainThenAbort {
+                        timeout: timeout.into(),
+                    },
+                    partition_store,
+                )
+                .await?;
+                // the new leader took over the invocation
+                assert!(*invoked.lock().unwrap() == [invocation_id]);
+
+                let drain_started = tokio::time::Instant::now();
+                control_tx
+                    .send(PartitionProcessorControlCommand::StepDown)
+                    .await?;
+                tokio::time::sleep(timeout - Duration::from_secs(1)).await;
+                assert!(status_rx.borrow().is_effective_leader());
+                assert!(aborted_partitions.lock().unwrap().is_empty());
+
+                status_rx
+                    .wait_for(|status| !status.is_effective_leader())
+                    .await?;
+                assert!(tokio::time::Instant::now() >= drain_started + timeout);
+                assert!(
+                    *aborted_partitions.lock().unwrap()
+                        == [(PartitionId::MIN, LeaderEpoch::INITIAL)]
+                );
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+
+        env.tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+}
@@ -58,4 +58,122 @@ impl Action {
     pub fn name(&self) -> &'static str {
         self.into()
     }
+
+    /// Splits the action by whether it has to be handled in order.
+    pub fn split(self) -> SplitAction {
+        match self {
+            Action::Invoke {
+                invocation_id,
+                invocation_target,
+                invoke_input_journal,
+            } => SplitAction::Ordered(OrderedAction::Invoke {
+                invocation_id,
+                invocation_target,
+                invoke_input_journal,
+            }),
+            Action::NewOutboxMessage {
+                seq_number,
+                message,
+            } => SplitAction::Ordered(OrderedAction::NewOutboxMessage {
+                seq_number,
+                message,
+            }),
+            Action::RegisterTimer { timer_value } => {
+                SplitAction::Ordered(OrderedAction::RegisterTimer { timer_value })
+            }
+            Action::DeleteTimer { timer_key } => {
+                SplitAction::Ordered(OrderedAction::DeleteTimer { timer_key })
+            }
+            Action::AckStoredEntry {
+                invocation_id,
+                entry_index,
+            } => SplitAction::Ordered(OrderedAction::AckStoredEntry {
+                invocation_id,
+                entry_index,
+            }),
+            Action::ForwardCompletion {
+                invocation_id,
+                completion,
+            } => SplitAction::Ordered(OrderedAction::ForwardCompletion {
+                invocation_id,
+                completion,
+            }),
+            Action::AbortInvocation(invocation_id) => {
+                SplitAction::Ordered(OrderedAction::AbortInvocation(invocation_id))
+            }
+            Action::IngressResponse(ingress_response) => {
+                SplitAction::Independent(IndependentAction::IngressResponse(ingress_response))
+            }
+            Action::IngressSubmitNotification(attach_notification) => SplitAction::Independent(
+                IndependentAction::IngressSubmitNotification(attach_notification),
+            ),
+            Action::ScheduleInvocationStatusCleanup {
+                invocation_id,
+                retention,
+            } => SplitAction::Independent(IndependentAction::ScheduleInvocationStatusCleanup {
+                invocation_id,
+                retention,
+            }),
+        }
+    }
+}
+
+/// An [`Action`] split by whether it has to be handled in order.
+#[derive(Debug)]
+pub enum SplitAction {
+    Ordered(OrderedAction),
+    Independent(IndependentAction),
+}
+
+/// Actions which interact with the invoker, the shuffle or the timer service. They have to be
+/// handled in the order in which they were collected.
+#[derive(Debug, strum_macros::IntoStaticStr)]
+pub enum OrderedAction {
+    Invoke {
+        invocation_id: InvocationId,
+        invocation_target: InvocationTarget,
+        invoke_input_journal: InvokeInputJournal,
+    },
+    NewOutboxMessage {
+        seq_number: MessageIndex,
+        message: OutboxMessage,
+    },
+    RegisterTimer {
+        timer_value: TimerKeyValue,
+    },
+    DeleteTimer {
+        timer_key: TimerKey,
+    },
+    AckStoredEntry {
+        invocation_id: InvocationId,
+        entry_index: EntryIndex,
+    },
+    ForwardCompletion {
+        invocation_id: InvocationId,
+        completion: Completion,
+    },
+    AbortInvocation(InvocationId),
+}
+
+impl OrderedAction {
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Actions which can be handled concurrently and in any order.
+#[derive(Debug, strum_macros::IntoStaticStr)]
+pub enum IndependentAction {
+    IngressResponse(IngressResponseEnvelope<ingress::InvocationResponse>),
+    IngressSubmitNotification(IngressResponseEnvelope<ingress::SubmittedInvocationNotification>),
+    ScheduleInvocationStatusCleanup {
+        invocation_id: InvocationId,
+        retention: Duration,
+    },
+}
+
+impl IndependentAction {
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
 }
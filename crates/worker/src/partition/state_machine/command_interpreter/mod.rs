@@ -216,6 +216,11 @@ where
                 effects.register_timer(timer, Default::default());
                 Ok(())
             }
+            Command::DeleteTimer(timer) => {
+                let (key, _) = timer.into_inner();
+                effects.delete_timer(key);
+                Ok(())
+            }
         }
     }
 
@@ -8,9 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use crate::metric_definitions::{
+    HANDLER_LABEL, OUTCOME_LABEL, PARTITION_IDEMPOTENCY_IDS_PURGED, SERVICE_LABEL,
+    WORKER_INVOCATIONS,
+};
 use crate::partition::types::InvocationIdAndTarget;
 use bytes::Bytes;
 use bytestring::ByteString;
+use metrics::counter;
 use opentelemetry::trace::SpanId;
 use restate_storage_api::inbox_table::InboxEntry;
 use restate_storage_api::invocation_status_table::{
@@ -1134,6 +1139,48 @@ impl Effects {
             effect.log(is_leader);
         }
     }
+
+    /// Only the leader records the invocation metrics, otherwise every replica of the partition
+    /// would count the same invocation. The cardinality of the service and handler labels is
+    /// bounded by the registered schemas since only invocations to known targets are accepted.
+    pub(crate) fn record_metrics(&self, is_leader: bool) {
+        if !is_leader {
+            return;
+        }
+
+        for effect in self.effects.iter() {
+            match effect {
+                Effect::TraceInvocationResult {
+                    invocation_id_and_target: (_, invocation_target),
+                    result,
+                    ..
+                } => {
+                    counter!(WORKER_INVOCATIONS,
+                        SERVICE_LABEL => invocation_target.service_name().to_string(),
+                        HANDLER_LABEL => invocation_target.handler_name().to_string(),
+                        OUTCOME_LABEL => if result.is_ok() { "success" } else { "failure" }
+                    )
+                    .increment(1);
+                }
+                Effect::DeleteIdempotencyId(_) => {
+                    counter!(PARTITION_IDEMPOTENCY_IDS_PURGED).increment(1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Change of the number of stored idempotency ids once these effects are interpreted.
+    pub(crate) fn idempotency_ids_delta(&self) -> isize {
+        self.effects
+            .iter()
+            .map(|effect| match effect {
+                Effect::StoreIdempotencyId(..) => 1,
+                Effect::DeleteIdempotencyId(_) => -1,
+                _ => 0,
+            })
+            .sum()
+    }
 }
 
 #[cfg(test)]
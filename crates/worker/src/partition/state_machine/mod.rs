@@ -21,7 +21,7 @@ mod command_interpreter;
 mod effect_interpreter;
 mod effects;
 
-pub use actions::Action;
+pub use actions::{Action, IndependentAction, OrderedAction, SplitAction};
 pub use command_interpreter::StateReader;
 pub use effect_interpreter::ActionCollector;
 pub use effect_interpreter::StateStorage;
@@ -31,7 +31,12 @@ use restate_types::journal::raw::{RawEntryCodec, RawEntryCodecError};
 use restate_wal_protocol::Command;
 
 #[derive(Debug)]
-pub struct StateMachine<Codec>(CommandInterpreter<Codec>);
+pub struct StateMachine<Codec> {
+    command_interpreter: CommandInterpreter<Codec>,
+    /// Number of stored idempotency ids, kept up to date by the applied commands so that it
+    /// can be reported without scanning the idempotency table.
+    idempotency_ids: usize,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -46,12 +51,20 @@ impl<Codec> StateMachine<Codec> {
         inbox_seq_number: MessageIndex,
         outbox_seq_number: MessageIndex,
         partition_key_range: RangeInclusive<PartitionKey>,
+        idempotency_ids: usize,
     ) -> Self {
-        Self(CommandInterpreter::new(
-            inbox_seq_number,
-            outbox_seq_number,
-            partition_key_range,
-        ))
+        Self {
+            command_interpreter: CommandInterpreter::new(
+                inbox_seq_number,
+                outbox_seq_number,
+                partition_key_range,
+            ),
+            idempotency_ids,
+        }
+    }
+
+    pub fn idempotency_ids(&self) -> usize {
+        self.idempotency_ids
     }
 }
 
@@ -67,10 +80,16 @@ impl<Codec: RawEntryCodec> StateMachine<Codec> {
         let start = Instant::now();
         // Handle the command, returns the span_relation to use to log effects
         let command_type = command.name();
-        self.0.on_apply(command, effects, transaction).await?;
+        self.command_interpreter
+            .on_apply(command, effects, transaction)
+            .await?;
 
         // Log the effects
         effects.log(is_leader);
+        effects.record_metrics(is_leader);
+        self.idempotency_ids = self
+            .idempotency_ids
+            .saturating_add_signed(effects.idempotency_ids_delta());
 
         // Interpret effects
         let res = effect_interpreter::EffectInterpreter::<Codec>::interpret_effects(
@@ -177,6 +196,7 @@ mod tests {
                     0, /* inbox_seq_number */
                     0, /* outbox_seq_number */
                     PartitionKey::MIN..=PartitionKey::MAX,
+                    0, /* idempotency_ids */
                 ),
                 rocksdb_storage,
                 effects_buffer: Default::default(),
@@ -840,7 +860,8 @@ mod tests {
                 }))
             );
 
-            // Assert idempotency key mapping exists
+            // Assert idempotency key mapping exists and is counted
+            assert!(state_machine.state_machine.idempotency_ids() == 1);
             let mut txn = state_machine.storage().transaction();
             assert_that!(
                 txn.get_idempotency_metadata(&idempotency_id)
@@ -1578,6 +1599,8 @@ mod tests {
             )
             .await;
             txn.commit().await.unwrap();
+            // The count loaded on startup includes the prepared idempotency id
+            state_machine.state_machine.idempotency_ids = 1;
 
             // Send timer fired command
             let _ = state_machine
@@ -1609,6 +1632,7 @@ mod tests {
                     .unwrap(),
                 none()
             );
+            assert!(state_machine.state_machine.idempotency_ids() == 0);
         }
     }
 
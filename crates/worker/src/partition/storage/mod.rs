@@ -12,14 +12,14 @@ use crate::metric_definitions::{PARTITION_STORAGE_TX_COMMITTED, PARTITION_STORAG
 use crate::partition::shuffle::{OutboxReader, OutboxReaderError};
 use bytes::Bytes;
 use bytestring::ByteString;
-use futures::{Stream, StreamExt, TryStreamExt};
+use futures::{future, Stream, StreamExt, TryStreamExt};
 use metrics::counter;
 use restate_partition_store::PartitionStore;
 use restate_storage_api::deduplication_table::{
     DedupSequenceNumber, ProducerId, ReadOnlyDeduplicationTable,
 };
 use restate_storage_api::fsm_table::{ReadOnlyFsmTable, SequenceNumber};
-use restate_storage_api::idempotency_table::IdempotencyMetadata;
+use restate_storage_api::idempotency_table::{IdempotencyMetadata, ReadOnlyIdempotencyTable};
 use restate_storage_api::inbox_table::{InboxEntry, SequenceNumberInboxEntry};
 use restate_storage_api::invocation_status_table::{
     InvocationStatus, ReadOnlyInvocationStatusTable,
@@ -165,6 +165,19 @@ where
     }
 }
 
+impl<Storage> PartitionStorage<Storage>
+where
+    Storage: ReadOnlyIdempotencyTable + Send,
+{
+    /// Number of idempotency ids stored for this partition.
+    pub async fn count_idempotency_ids(&self) -> StorageResult<usize> {
+        self.storage
+            .all_idempotency_metadata(self.partition_key_range.clone())
+            .try_fold(0, |count, _| future::ready(Ok(count + 1)))
+            .await
+    }
+}
+
 #[inline]
 fn assert_partition_key(
     partition_key_range: &RangeInclusive<PartitionKey>,
@@ -0,0 +1,213 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use futures::StreamExt;
+use metrics::gauge;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use restate_core::metadata;
+use restate_partition_store::PartitionStoreManager;
+use restate_storage_api::invocation_status_table::ReadOnlyInvocationStatusTable;
+use restate_types::identifiers::{PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::partition_table::{FindPartition, FixedPartitionTable};
+
+use crate::metric_definitions::PARTITION_KEY_SKEW;
+
+/// Distributions whose most loaded partition holds more than this multiple of the mean number of
+/// keys are reported as skewed.
+const SKEW_WARNING_THRESHOLD: f64 = 2.0;
+/// Upper bound for waiting on the partition processors to open their partition stores. Partitions
+/// whose store isn't open by then are left out of the check.
+const PARTITION_STORE_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Number of partition keys per checked partition.
+#[derive(Debug)]
+struct PartitionKeyDistribution {
+    keys_per_partition: BTreeMap<PartitionId, usize>,
+}
+
+impl PartitionKeyDistribution {
+    fn new(partition_ids: impl IntoIterator<Item = PartitionId>) -> Self {
+        Self {
+            keys_per_partition: partition_ids.into_iter().map(|id| (id, 0)).collect(),
+        }
+    }
+
+    fn record(
+        &mut self,
+        partition_table: &FixedPartitionTable,
+        partition_key: PartitionKey,
+    ) -> anyhow::Result<()> {
+        let partition_id = partition_table.find_partition_id(partition_key)?;
+        *self.keys_per_partition.entry(partition_id).or_default() += 1;
+        Ok(())
+    }
+
+    fn total(&self) -> usize {
+        self.keys_per_partition.values().sum()
+    }
+
+    /// Ratio between the number of keys of the most loaded partition and the mean number of keys
+    /// per partition. A perfectly even distribution has a skew of 1.
+    fn skew(&self) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let mean = total as f64 / self.keys_per_partition.len() as f64;
+        let max = self
+            .keys_per_partition
+            .values()
+            .max()
+            .copied()
+            .unwrap_or_default();
+        Some(max as f64 / mean)
+    }
+}
+
+/// Counts the partition keys of the stored invocations and reports how they are distributed
+/// across the given partitions. Only the partitions running on this node can be checked, since
+/// the check reads from the local partition stores.
+pub(crate) struct PartitionKeyDistributionCheck {
+    partition_store_manager: PartitionStoreManager,
+    partition_ids: Vec<PartitionId>,
+}
+
+impl PartitionKeyDistributionCheck {
+    pub(crate) fn new(
+        partition_store_manager: PartitionStoreManager,
+        partition_ids: Vec<PartitionId>,
+    ) -> Self {
+        Self {
+            partition_store_manager,
+            partition_ids,
+        }
+    }
+
+    pub(crate) async fn run(self) -> anyhow::Result<()> {
+        let partition_table = metadata()
+            .partition_table()
+            .ok_or_else(|| anyhow!("Can't find partition table"))?;
+        let deadline = Instant::now() + PARTITION_STORE_WAIT_TIMEOUT;
+        let mut partition_stores = Vec::with_capacity(self.partition_ids.len());
+
+        for partition_id in self.partition_ids {
+            // partition stores are opened asynchronously by the partition processors
+            let partition_store = loop {
+                if let Some(partition_store) = self
+                    .partition_store_manager
+                    .get_partition_store(partition_id)
+                    .await
+                {
+                    break Some(partition_store);
+                }
+                if Instant::now() >= deadline {
+                    break None;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            };
+
+            match partition_store {
+                Some(partition_store) => partition_stores.push((partition_id, partition_store)),
+                None => warn!(
+                    %partition_id,
+                    "Partition store wasn't opened within {:?}, leaving the partition out of the partition key distribution check",
+                    PARTITION_STORE_WAIT_TIMEOUT
+                ),
+            }
+        }
+
+        let mut distribution =
+            PartitionKeyDistribution::new(partition_stores.iter().map(|(id, _)| *id));
+        for (_, partition_store) in partition_stores {
+            let mut invocations = std::pin::pin!(partition_store
+                .all_invocation_statuses(partition_store.partition_key_range().clone()));
+
+            while let Some(invocation) = invocations.next().await {
+                let (invocation_id, _) = invocation?;
+                distribution.record(&partition_table, invocation_id.partition_key())?;
+            }
+        }
+
+        let Some(skew) = distribution.skew() else {
+            debug!(
+                "No stored invocations found in the partitions running on this node to check the partition key distribution"
+            );
+            return Ok(());
+        };
+        gauge!(PARTITION_KEY_SKEW).set(skew);
+
+        info!(
+            "Partition key distribution of {} keys across the {} partitions running on this node (other partitions are not checked): {:?}",
+            distribution.total(),
+            distribution.keys_per_partition.len(),
+            distribution.keys_per_partition
+        );
+        if skew > SKEW_WARNING_THRESHOLD {
+            warn!(
+                "Partition keys are unevenly distributed across the partitions running on this node, the most loaded partition holds {:.1}x the mean number of keys. This can cause hot partitions.",
+                skew
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_types::Version;
+
+    #[test]
+    fn skew_of_partition_key_distribution() -> anyhow::Result<()> {
+        let partition_table = FixedPartitionTable::new(Version::MIN, 4);
+        let mut distribution =
+            PartitionKeyDistribution::new(partition_table.partitioner().map(|(id, _)| id));
+        assert_eq!(distribution.skew(), None);
+
+        for (_, range) in partition_table.partitioner() {
+            distribution.record(&partition_table, *range.start())?;
+        }
+        assert_eq!(distribution.skew(), Some(1.0));
+
+        let (_, hot_range) = partition_table.partitioner().next().unwrap();
+        for _ in 0..4 {
+            distribution.record(&partition_table, *hot_range.end())?;
+        }
+        assert_eq!(distribution.total(), 8);
+        assert_eq!(distribution.skew(), Some(2.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn skew_only_considers_checked_partitions() -> anyhow::Result<()> {
+        let partition_table = FixedPartitionTable::new(Version::MIN, 4);
+        let mut partitions = partition_table.partitioner();
+        let (first_id, first_range) = partitions.next().unwrap();
+        let (second_id, second_range) = partitions.next().unwrap();
+
+        // the partitions which aren't running on this node must not lower the mean
+        let mut distribution = PartitionKeyDistribution::new([first_id, second_id]);
+        distribution.record(&partition_table, *first_range.start())?;
+        distribution.record(&partition_table, *second_range.start())?;
+        assert_eq!(distribution.skew(), Some(1.0));
+
+        Ok(())
+    }
+}
@@ -26,13 +26,16 @@ use restate_node_protocol::RpcMessage;
 use restate_types::processors::ReplayStatus;
 use restate_types::processors::{PartitionProcessorStatus, RunMode};
 use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use tokio::time;
 use tokio::time::MissedTickBehavior;
 use tracing::{debug, info, trace, warn};
 
 use restate_bifrost::Bifrost;
 use restate_core::network::MessageRouterBuilder;
-use restate_core::worker_api::{ProcessorsManagerCommand, ProcessorsManagerHandle};
+use restate_core::worker_api::{
+    PartitionLeadership, ProcessorsManagerCommand, ProcessorsManagerHandle,
+};
 use restate_core::{cancellation_watcher, Metadata, ShutdownError, TaskId, TaskKind};
 use restate_invoker_impl::InvokerHandle;
 use restate_metadata_store::{MetadataStoreClient, ReadModifyWriteError};
@@ -65,7 +68,8 @@ use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_RECORD;
 use crate::metric_definitions::PARTITION_TIME_SINCE_LAST_STATUS_UPDATE;
 use crate::partition::storage::invoker::InvokerStorageReader;
 use crate::partition::storage::PartitionStorage;
-use crate::partition::PartitionProcessorControlCommand;
+use crate::partition::{PartitionProcessorControlCommand, ProposalBatching};
+use crate::partition_key_distribution::PartitionKeyDistributionCheck;
 use crate::PartitionProcessor;
 
 pub struct PartitionProcessorManager {
@@ -87,6 +91,10 @@ pub struct PartitionProcessorManager {
     latest_attach_response: Option<(GenerationalNodeId, AttachResponse)>,
 
     persisted_lsns_rx: Option<watch::Receiver<BTreeMap<PartitionId, Lsn>>>,
+
+    // Cloned into every partition processor task. The channel closes once the manager and all
+    // processors are gone, see `Worker::run`.
+    processors_alive: mpsc::Sender<()>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -100,9 +108,9 @@ enum AttachError {
 struct State {
     _created_at: MillisSinceEpoch,
     _key_range: RangeInclusive<PartitionKey>,
-    _control_tx: mpsc::Sender<PartitionProcessorControlCommand>,
+    control_tx: mpsc::Sender<PartitionProcessorControlCommand>,
     watch_rx: watch::Receiver<PartitionProcessorStatus>,
-    _task_id: TaskId,
+    task_id: TaskId,
 }
 
 impl PartitionProcessorManager {
@@ -117,11 +125,17 @@ impl PartitionProcessorManager {
         networking: Networking,
         bifrost: Bifrost,
         invoker_handle: InvokerHandle<InvokerStorageReader<PartitionStore>>,
+        processors_alive: mpsc::Sender<()>,
     ) -> Self {
         let attach_router = RpcRouter::new(networking.clone(), router_builder);
         let incoming_get_state = router_builder.subscribe_to_stream(2);
 
-        let (tx, rx) = mpsc::channel(updateable_config.load().worker.internal_queue_length());
+        let (tx, rx) = mpsc::channel(
+            updateable_config
+                .load()
+                .worker
+                .processors_manager_channel_size(),
+        );
         Self {
             task_center,
             updateable_config,
@@ -139,6 +153,7 @@ impl PartitionProcessorManager {
             tx,
             latest_attach_response: None,
             persisted_lsns_rx: None,
+            processors_alive,
         }
     }
 
@@ -219,6 +234,24 @@ impl PartitionProcessorManager {
             watchdog.run(),
         )?;
 
+        if self
+            .updateable_config
+            .pinned()
+            .worker
+            .check_partition_key_distribution
+        {
+            let check = PartitionKeyDistributionCheck::new(
+                self.partition_store_manager.clone(),
+                self.running_partition_processors.keys().cloned().collect(),
+            );
+            self.task_center.spawn_child(
+                TaskKind::Disposable,
+                "partition-key-distribution-check",
+                None,
+                check.run(),
+            )?;
+        }
+
         loop {
             tokio::select! {
                 Some(command) = self.rx.recv() => {
@@ -310,9 +343,90 @@ impl PartitionProcessorManager {
                 let live_partitions = self.running_partition_processors.keys().cloned().collect();
                 let _ = sender.send(live_partitions);
             }
+            GetLeadershipSnapshot(sender) => {
+                let _ = sender.send(Self::leadership_snapshot(
+                    &self.running_partition_processors,
+                ));
+            }
+            StopPartition(partition_id) => {
+                if Self::stop_partition_processor(
+                    &self.task_center,
+                    &mut self.running_partition_processors,
+                    partition_id,
+                )
+                .is_some()
+                {
+                    info!(%partition_id, "Stopping partition processor");
+                } else {
+                    debug!(
+                        "Partition processor for partition id '{}' is not running.",
+                        partition_id
+                    );
+                }
+                gauge!(NUM_ACTIVE_PARTITIONS).set(self.running_partition_processors.len() as f64);
+            }
+            PauseInvocations(partition_id) => self.send_control_command(
+                partition_id,
+                PartitionProcessorControlCommand::PauseInvocations,
+            ),
+            ResumeInvocations(partition_id) => self.send_control_command(
+                partition_id,
+                PartitionProcessorControlCommand::ResumeInvocations,
+            ),
+            StepDown(partition_id) => {
+                self.send_control_command(partition_id, PartitionProcessorControlCommand::StepDown)
+            }
+        }
+    }
+
+    fn send_control_command(
+        &self,
+        partition_id: PartitionId,
+        command: PartitionProcessorControlCommand,
+    ) {
+        let Some(state) = self.running_partition_processors.get(&partition_id) else {
+            debug!(
+                "Partition processor for partition id '{}' is not running.",
+                partition_id
+            );
+            return;
+        };
+
+        if let Err(err) = state.control_tx.try_send(command) {
+            warn!(%partition_id, "Failed sending {:?} to partition processor", err.into_inner());
         }
     }
 
+    fn leadership_snapshot(
+        running_partition_processors: &BTreeMap<PartitionId, State>,
+    ) -> BTreeMap<PartitionId, PartitionLeadership> {
+        running_partition_processors
+            .iter()
+            .map(|(partition_id, state)| {
+                let status = state.watch_rx.borrow();
+                (
+                    *partition_id,
+                    PartitionLeadership {
+                        is_leader: status.is_effective_leader(),
+                        leader_epoch: status.last_observed_leader_epoch,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Cancels the task of the partition processor of the given partition, which makes it step
+    /// down to follower before it terminates. Returns the handle of the stopping task if the
+    /// partition processor was running.
+    fn stop_partition_processor(
+        task_center: &TaskCenter,
+        running_partition_processors: &mut BTreeMap<PartitionId, State>,
+        partition_id: PartitionId,
+    ) -> Option<JoinHandle<()>> {
+        let state = running_partition_processors.remove(&partition_id)?;
+        task_center.cancel_task(state.task_id)
+    }
+
     pub fn apply_plan(&mut self, actions: &[Action]) -> Result<(), ShutdownError> {
         let config = self.updateable_config.pinned();
         let options = &config.worker;
@@ -329,7 +443,7 @@ impl PartitionProcessorManager {
                         let status = PartitionProcessorStatus::new(action.mode);
                         let (watch_tx, watch_rx) = watch::channel(status.clone());
 
-                        let _task_id = self.spawn_partition_processor(
+                        let task_id = self.spawn_partition_processor(
                             options,
                             action.partition_id,
                             action.key_range_inclusive.clone().into(),
@@ -340,8 +454,8 @@ impl PartitionProcessorManager {
                         let state = State {
                             _created_at: MillisSinceEpoch::now(),
                             _key_range: action.key_range_inclusive.clone().into(),
-                            _task_id,
-                            _control_tx: control_tx,
+                            task_id,
+                            control_tx,
                             watch_rx,
                         };
                         self.running_partition_processors
@@ -375,7 +489,12 @@ impl PartitionProcessorManager {
             key_range.clone(),
             status,
             options.num_timers_in_memory_limit(),
-            options.internal_queue_length(),
+            options.invoker_effects_channel_size(),
+            options.shuffle_channel_size(),
+            options.action_effects_channel_size(),
+            options.orphaned_timers,
+            options.invoker_abort_behavior,
+            ProposalBatching::from_options(options),
             control_rx,
             watch_tx,
             self.invoker_handle.clone(),
@@ -399,7 +518,9 @@ impl PartitionProcessorManager {
             {
                 let storage_manager = self.partition_store_manager.clone();
                 let options = options.clone();
+                let processors_alive = self.processors_alive.clone();
                 async move {
+                    let _processors_alive = processors_alive;
                     let partition_store = storage_manager
                         .open_partition_store(
                             partition_id,
@@ -629,19 +750,41 @@ impl PersistedLogLsnWatchdog {
 #[cfg(test)]
 mod tests {
     use crate::partition::storage::PartitionStorage;
-    use crate::partition_processor_manager::PersistedLogLsnWatchdog;
-    use restate_core::{TaskKind, TestCoreEnv};
+    use crate::partition_processor_manager::{
+        PartitionProcessorManager, PersistedLogLsnWatchdog, State,
+    };
+    use restate_bifrost::Bifrost;
+    use restate_core::network::MessageRouterBuilder;
+    use restate_core::worker_api::PartitionLeadership;
+    use restate_core::{
+        cancellation_watcher, metadata, TaskId, TaskKind, TestCoreEnv, TestCoreEnvBuilder,
+    };
+    use restate_invoker_api::entry_enricher::test_util::MockEntryEnricher;
+    use restate_invoker_impl::Service as InvokerService;
+    use restate_network::Networking;
+    use restate_node_protocol::cluster_controller::{Action, RunPartition};
+    use restate_node_protocol::common::KeyRange;
     use restate_partition_store::{OpenMode, PartitionStoreManager};
     use restate_rocksdb::RocksDbManager;
+    use restate_schema_api::deployment::test_util::MockDeploymentMetadataRegistry;
     use restate_types::arc_util::Constant;
-    use restate_types::config::{CommonOptions, RocksDbOptions, StorageOptions};
-    use restate_types::identifiers::{PartitionId, PartitionKey};
+    use restate_types::config::{
+        CommonOptions, Configuration, InvokerOptions, RocksDbOptions, ServiceClientOptions,
+        StorageOptions,
+    };
+    use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey};
     use restate_types::logs::{Lsn, SequenceNumber};
+    use restate_types::partition_table::FixedPartitionTable;
+    use restate_types::processors::{PartitionProcessorStatus, RunMode};
+    use restate_types::time::MillisSinceEpoch;
+    use restate_types::Version;
     use std::collections::BTreeMap;
     use std::ops::RangeInclusive;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
     use std::time::Duration;
     use test_log::test;
-    use tokio::sync::watch;
+    use tokio::sync::{mpsc, watch};
     use tokio::time::Instant;
 
     #[test(tokio::test(start_paused = true))]
@@ -737,4 +880,207 @@ mod tests {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn stopping_a_partition_processor_keeps_the_others_running() -> anyhow::Result<()> {
+        let node_env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
+        let tc = node_env.tc;
+
+        let mut running_partition_processors = BTreeMap::default();
+        let mut stopped = Vec::new();
+        for partition_id in [PartitionId::from(0), PartitionId::from(1)] {
+            let is_stopped = Arc::new(AtomicBool::new(false));
+            let task_id = tc.spawn(
+                TaskKind::PartitionProcessor,
+                "test-partition-processor",
+                Some(partition_id),
+                {
+                    let is_stopped = Arc::clone(&is_stopped);
+                    async move {
+                        cancellation_watcher().await;
+                        is_stopped.store(true, Ordering::Relaxed);
+                        Ok(())
+                    }
+                },
+            )?;
+            let (control_tx, _control_rx) = mpsc::channel(1);
+            let (_watch_tx, watch_rx) =
+                watch::channel(PartitionProcessorStatus::new(RunMode::Leader));
+            running_partition_processors.insert(
+                partition_id,
+                State {
+                    _created_at: MillisSinceEpoch::now(),
+                    _key_range: 0..=PartitionKey::MAX,
+                    control_tx,
+                    watch_rx,
+                    task_id,
+                },
+            );
+            stopped.push(is_stopped);
+        }
+
+        let handle = PartitionProcessorManager::stop_partition_processor(
+            &tc,
+            &mut running_partition_processors,
+            PartitionId::from(0),
+        )
+        .expect("partition processor to be running");
+        handle.await?;
+
+        assert!(stopped[0].load(Ordering::Relaxed));
+        assert!(!stopped[1].load(Ordering::Relaxed));
+        assert_eq!(
+            running_partition_processors.keys().collect::<Vec<_>>(),
+            vec![&PartitionId::from(1)]
+        );
+
+        // stopping a partition processor which is not running is a no-op
+        assert!(PartitionProcessorManager::stop_partition_processor(
+            &tc,
+            &mut running_partition_processors,
+            PartitionId::from(0),
+        )
+        .is_none());
+
+        tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
+
+    #[test]
+    fn fresh_follower_is_not_leader() {
+        let (control_tx, _control_rx) = mpsc::channel(1);
+        let (_watch_tx, watch_rx) =
+            watch::channel(PartitionProcessorStatus::new(RunMode::Follower));
+        let running_partition_processors = BTreeMap::from([(
+            PartitionId::MIN,
+            State {
+                _created_at: MillisSinceEpoch::now(),
+                _key_range: 0..=PartitionKey::MAX,
+                control_tx,
+                watch_rx,
+                task_id: TaskId::from(1),
+            },
+        )]);
+
+        let snapshot =
+            PartitionProcessorManager::leadership_snapshot(&running_partition_processors);
+
+        assert_eq!(
+            snapshot.get(&PartitionId::MIN),
+            Some(&PartitionLeadership {
+                is_leader: false,
+                leader_epoch: None,
+            })
+        );
+    }
+
+    #[test]
+    fn promoted_leader_reports_its_epoch() {
+        let (control_tx, _control_rx) = mpsc::channel(1);
+        let (watch_tx, watch_rx) = watch::channel(PartitionProcessorStatus::new(RunMode::Leader));
+        let running_partition_processors = BTreeMap::from([(
+            PartitionId::MIN,
+            State {
+                _created_at: MillisSinceEpoch::now(),
+                _key_range: 0..=PartitionKey::MAX,
+                control_tx,
+                watch_rx,
+                task_id: TaskId::from(1),
+            },
+        )]);
+
+        watch_tx.send_modify(|status| {
+            status.effective_mode = Some(RunMode::Leader);
+            status.last_observed_leader_epoch = Some(LeaderEpoch::from(3));
+        });
+
+        let snapshot =
+            PartitionProcessorManager::leadership_snapshot(&running_partition_processors);
+
+        assert_eq!(
+            snapshot.get(&PartitionId::MIN),
+            Some(&PartitionLeadership {
+                is_leader: true,
+                leader_epoch: Some(LeaderEpoch::from(3)),
+            })
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn only_assigned_partitions_are_run() -> anyhow::Result<()> {
+        let node_env = TestCoreEnvBuilder::new_with_mock_network()
+            .add_mock_nodes_config()
+            .with_partition_table(FixedPartitionTable::new(Version::MIN, 4))
+            .build()
+            .await;
+        let tc = node_env.tc.clone();
+
+        tc.run_in_scope_sync("db-manager-init", None, || {
+            RocksDbManager::init(Constant::new(CommonOptions::default()))
+        });
+        let partition_store_manager = PartitionStoreManager::create(
+            Constant::new(StorageOptions::default()),
+            Constant::new(RocksDbOptions::default()),
+            &[],
+        )
+        .await?;
+        let invoker = InvokerService::from_options(
+            &ServiceClientOptions::default(),
+            &InvokerOptions::default(),
+            MockEntryEnricher,
+            MockDeploymentMetadataRegistry::default(),
+        )?;
+        let (processors_alive, _processors_stopped) = mpsc::channel(1);
+
+        tc.run_in_scope("test", None, {
+            let tc = tc.clone();
+            async move {
+                let mut manager = PartitionProcessorManager::new(
+                    tc,
+                    Configuration::current().clone(),
+                    node_env.metadata.clone(),
+                    node_env.metadata_store_client.clone(),
+                    partition_store_manager,
+                    &mut MessageRouterBuilder::default(),
+                    Networking::default(),
+                    Bifrost::init().await,
+                    invoker.handle(),
+                    processors_alive,
+                );
+
+                // the cluster controller assigns only some partitions of the partition table
+                let partition_table = metadata()
+                    .partition_table()
+                    .expect("partition table is loaded");
+                let actions: Vec<_> = partition_table
+                    .partitioner()
+                    .filter(|(partition_id, _)| **partition_id % 2 == 1)
+                    .map(|(partition_id, key_range)| {
+                        Action::RunPartition(RunPartition {
+                            partition_id,
+                            key_range_inclusive: KeyRange {
+                                from: *key_range.start(),
+                                to: *key_range.end(),
+                            },
+                            mode: RunMode::Follower,
+                        })
+                    })
+                    .collect();
+                manager.apply_plan(&actions)?;
+
+                assert_eq!(
+                    manager
+                        .running_partition_processors
+                        .keys()
+                        .collect::<Vec<_>>(),
+                    vec![&PartitionId::from(1), &PartitionId::from(3)]
+                );
+                Ok::<_, anyhow::Error>(())
+            }
+        })
+        .await?;
+
+        tc.cancel_tasks(None, None).await;
+        Ok(())
+    }
 }
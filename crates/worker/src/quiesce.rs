@@ -0,0 +1,65 @@
+// Copyright (c) 2024 -  Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use metrics::gauge;
+use restate_ingress_dispatcher::IngressDispatcher;
+use restate_schema::UpdateableSchema;
+use restate_schema_api::subscription::SubscriptionResolver;
+use tracing::info;
+
+use crate::metric_definitions::WORKER_QUIESCED;
+use crate::{SubscriptionControllerHandle, WorkerHandleError};
+
+/// Allows to quiesce the worker. A quiesced worker stops accepting new invocations and stops
+/// consuming subscriptions, while the partition processors keep running so that in-flight
+/// invocations can complete and storage queries are still served.
+#[derive(Clone)]
+pub struct QuiesceHandle {
+    ingress_dispatcher: IngressDispatcher,
+    subscription_controller: SubscriptionControllerHandle,
+    schema_view: UpdateableSchema,
+}
+
+impl QuiesceHandle {
+    pub(crate) fn new(
+        ingress_dispatcher: IngressDispatcher,
+        subscription_controller: SubscriptionControllerHandle,
+        schema_view: UpdateableSchema,
+    ) -> Self {
+        Self {
+            ingress_dispatcher,
+            subscription_controller,
+            schema_view,
+        }
+    }
+
+    pub fn is_quiesced(&self) -> bool {
+        self.ingress_dispatcher.is_quiesced()
+    }
+
+    pub async fn quiesce(&self) -> Result<(), WorkerHandleError> {
+        // stop the subscriptions first so that consumers don't fail dispatching their events
+        self.subscription_controller
+            .pause(self.schema_view.list_subscriptions(&[]))
+            .await?;
+        self.ingress_dispatcher.set_quiesced(true);
+        gauge!(WORKER_QUIESCED).set(1.0);
+        info!("Worker is quiesced, no new invocations will be accepted");
+        Ok(())
+    }
+
+    pub async fn unquiesce(&self) -> Result<(), WorkerHandleError> {
+        self.ingress_dispatcher.set_quiesced(false);
+        gauge!(WORKER_QUIESCED).set(0.0);
+        self.subscription_controller.resume().await?;
+        info!("Worker is no longer quiesced");
+        Ok(())
+    }
+}
@@ -15,16 +15,69 @@ use restate_types::config::IngressOptions;
 use restate_types::identifiers::SubscriptionId;
 use std::ops::Deref;
 use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Default)]
+struct PauseState {
+    paused: bool,
+    // Latest set of subscriptions requested while being paused. Used to restore the
+    // subscriptions when resuming.
+    subscriptions: Option<Vec<Subscription>>,
+}
 
 #[derive(Debug, Clone)]
-pub struct SubscriptionControllerHandle(Arc<IngressOptions>, SubscriptionCommandSender);
+pub struct SubscriptionControllerHandle(
+    Arc<IngressOptions>,
+    SubscriptionCommandSender,
+    Arc<Mutex<PauseState>>,
+);
 
 impl SubscriptionControllerHandle {
     pub(crate) fn new(
         ingress_options: IngressOptions,
         commands_tx: SubscriptionCommandSender,
     ) -> Self {
-        Self(Arc::new(ingress_options), commands_tx)
+        Self(
+            Arc::new(ingress_options),
+            commands_tx,
+            Arc::new(Mutex::new(PauseState::default())),
+        )
+    }
+
+    /// Stops consuming all subscriptions until [`Self::resume`] is called. Subscription
+    /// updates received in the meantime are applied when resuming.
+    pub(crate) async fn pause(
+        &self,
+        current_subscriptions: Vec<Subscription>,
+    ) -> Result<(), WorkerHandleError> {
+        let mut state = self.2.lock().await;
+        if !state.paused {
+            state.paused = true;
+            state.subscriptions = Some(current_subscriptions);
+            self.send(restate_ingress_kafka::Command::UpdateSubscriptions(vec![]))
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn resume(&self) -> Result<(), WorkerHandleError> {
+        let mut state = self.2.lock().await;
+        if state.paused {
+            state.paused = false;
+            let subscriptions = state.subscriptions.take().unwrap_or_default();
+            self.send(restate_ingress_kafka::Command::UpdateSubscriptions(
+                subscriptions,
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn send(&self, command: restate_ingress_kafka::Command) -> Result<(), WorkerHandleError> {
+        self.1
+            .send(command)
+            .await
+            .map_err(|_| WorkerHandleError::Unreachable)
     }
 }
 
@@ -41,30 +94,106 @@ impl SubscriptionController for SubscriptionControllerHandle {
         &self,
         subscription: Subscription,
     ) -> Result<(), WorkerHandleError> {
-        self.1
-            .send(restate_ingress_kafka::Command::StartSubscription(
-                subscription,
-            ))
-            .await
-            .map_err(|_| WorkerHandleError::Unreachable)
+        let mut state = self.2.lock().await;
+        if state.paused {
+            state
+                .subscriptions
+                .get_or_insert_with(Vec::new)
+                .push(subscription);
+            return Ok(());
+        }
+        self.send(restate_ingress_kafka::Command::StartSubscription(
+            subscription,
+        ))
+        .await
     }
 
     async fn stop_subscription(&self, id: SubscriptionId) -> Result<(), WorkerHandleError> {
-        self.1
-            .send(restate_ingress_kafka::Command::StopSubscription(id))
+        let mut state = self.2.lock().await;
+        if state.paused {
+            if let Some(subscriptions) = state.subscriptions.as_mut() {
+                subscriptions.retain(|subscription| subscription.id() != id);
+            }
+            return Ok(());
+        }
+        self.send(restate_ingress_kafka::Command::StopSubscription(id))
             .await
-            .map_err(|_| WorkerHandleError::Unreachable)
     }
 
     async fn update_subscriptions(
         &self,
         subscriptions: Vec<Subscription>,
     ) -> Result<(), WorkerHandleError> {
-        self.1
-            .send(restate_ingress_kafka::Command::UpdateSubscriptions(
-                subscriptions,
-            ))
-            .await
-            .map_err(|_| WorkerHandleError::Unreachable)
+        let mut state = self.2.lock().await;
+        if state.paused {
+            state.subscriptions = Some(subscriptions);
+            return Ok(());
+        }
+        self.send(restate_ingress_kafka::Command::UpdateSubscriptions(
+            subscriptions,
+        ))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_ingress_kafka::Command;
+    use restate_schema_api::subscription::{EventReceiverServiceType, Sink, Source};
+    use restate_test_util::{assert, let_assert};
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    fn subscription(topic: &str) -> Subscription {
+        Subscription::new(
+            SubscriptionId::new(),
+            Source::Kafka {
+                cluster: "my-cluster".to_owned(),
+                topic: topic.to_owned(),
+            },
+            Sink::Service {
+                name: "Greeter".to_owned(),
+                handler: "greet".to_owned(),
+                ty: EventReceiverServiceType::Service,
+            },
+            HashMap::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn subscriptions_updated_while_paused_are_applied_on_resume() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let handle = SubscriptionControllerHandle::new(IngressOptions::default(), tx);
+        let orders = subscription("orders");
+        let payments = subscription("payments");
+
+        handle.pause(vec![orders.clone()]).await.unwrap();
+        let_assert!(Some(Command::UpdateSubscriptions(subscriptions)) = rx.recv().await);
+        assert!(subscriptions.is_empty());
+
+        // changes are only remembered while being paused
+        handle.start_subscription(payments.clone()).await.unwrap();
+        handle.stop_subscription(orders.id()).await.unwrap();
+        handle.pause(vec![]).await.unwrap();
+        assert!(rx.try_recv().is_err());
+
+        handle.resume().await.unwrap();
+        let_assert!(Some(Command::UpdateSubscriptions(subscriptions)) = rx.recv().await);
+        assert!(subscriptions == vec![payments.clone()]);
+
+        handle.stop_subscription(payments.id()).await.unwrap();
+        let_assert!(Some(Command::StopSubscription(id)) = rx.recv().await);
+        assert!(id == payments.id());
+    }
+
+    #[tokio::test]
+    async fn resuming_without_pausing_is_a_no_op() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let handle = SubscriptionControllerHandle::new(IngressOptions::default(), tx);
+
+        handle.resume().await.unwrap();
+        assert!(rx.try_recv().is_err());
     }
 }
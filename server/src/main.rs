@@ -206,14 +206,22 @@ fn main() {
                     .expect("Error when trying to wipe the configured storage path");
             }
 
-            let node = Node::create(Configuration::current().clone()).await;
+            let node = Node::create(
+                Configuration::current().clone(),
+                Some(tracing_guard.log_filter_handle()),
+            )
+            .await;
             if let Err(err) = node {
                 handle_error(err);
             }
             // We ignore errors since we will wait for shutdown below anyway.
             // This starts node roles and the rest of the system async under tasks managed by
             // the TaskCenter.
-            let _ = tc.spawn(TaskKind::SystemBoot, "init", None, node.unwrap().start());
+            let node = node.unwrap();
+            let _ = tc.spawn(TaskKind::SystemBoot, "init", None, async move {
+                node.start().await?;
+                Ok(())
+            });
 
             let task_center_watch = tc.shutdown_token();
             tokio::pin!(task_center_watch);
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use cling::prelude::*;
@@ -35,6 +36,11 @@ pub struct ConnectionInfo {
     /// Cluster Controller host:port (e.g. http://localhost:5122/)
     #[clap(long, value_hint= clap::ValueHint::Url, default_value_t = AdvertisedAddress::from_str("http://localhost:5122/").unwrap(), global = true)]
     pub cluster_controller: AdvertisedAddress,
+
+    /// File containing the bearer token which is sent to the cluster controller, required if
+    /// the node has authentication enabled
+    #[clap(long, env = "RESTATE_BEARER_TOKEN_FILE", value_hint = clap::ValueHint::FilePath, global = true)]
+    pub bearer_token_file: Option<PathBuf>,
 }
 
 #[derive(Run, Subcommand, Clone)]
@@ -28,7 +28,7 @@ use restate_types::{GenerationalNodeId, PlainNodeId};
 use tonic::codec::CompressionEncoding;
 
 use crate::app::ConnectionInfo;
-use crate::util::grpc_connect;
+use crate::util::{grpc_connect, grpc_interceptor};
 
 #[derive(Run, Parser, Collect, Clone, Debug)]
 #[clap(visible_alias = "cluster")]
@@ -52,8 +52,9 @@ async fn dump_cluster_state(
                 connection.cluster_controller
             )
         })?;
-    let mut client =
-        ClusterCtrlSvcClient::new(channel).accept_compressed(CompressionEncoding::Gzip);
+    let interceptor = grpc_interceptor(connection)?;
+    let mut client = ClusterCtrlSvcClient::with_interceptor(channel, interceptor)
+        .accept_compressed(CompressionEncoding::Gzip);
 
     let req = ClusterStateRequest::default();
     let state = client.get_cluster_state(req).await?.into_inner();
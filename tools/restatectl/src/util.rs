@@ -9,11 +9,15 @@
 // by the Apache License, Version 2.0.
 
 use restate_cli_util::CliContext;
+use restate_grpc_util::auth::BearerTokenInterceptor;
+use restate_types::config::AuthOptions;
 use restate_types::net::AdvertisedAddress;
 use tokio::net::UnixStream;
 use tonic::transport::{Channel, Endpoint, Uri};
 use tower::service_fn;
 
+use crate::app::ConnectionInfo;
+
 pub async fn grpc_connect(address: AdvertisedAddress) -> Result<Channel, tonic::transport::Error> {
     let ctx = CliContext::get();
     match address {
@@ -36,3 +40,12 @@ pub async fn grpc_connect(address: AdvertisedAddress) -> Result<Channel, tonic::
         }
     }
 }
+
+/// Attaches the bearer token of the connection, if any, to the requests.
+pub fn grpc_interceptor(connection: &ConnectionInfo) -> std::io::Result<BearerTokenInterceptor> {
+    let auth = connection
+        .bearer_token_file
+        .clone()
+        .map(|bearer_token_file| AuthOptions { bearer_token_file });
+    BearerTokenInterceptor::from_options(auth.as_ref())
+}
@@ -14,6 +14,7 @@ use restate_admin::service::AdminService;
 use restate_bifrost::Bifrost;
 use restate_core::TaskKind;
 use restate_core::TestCoreEnv;
+use restate_grpc_util::auth::BearerTokenInterceptor;
 use restate_node_services::node_svc::node_svc_client::NodeSvcClient;
 use restate_schema_api::subscription::Subscription;
 use restate_service_client::{AssumeRoleCacheMode, ServiceClient};
@@ -23,6 +24,7 @@ use restate_types::arc_util::Constant;
 use restate_types::config::Configuration;
 use restate_types::identifiers::SubscriptionId;
 use restate_types::invocation::InvocationTermination;
+use restate_types::net::BindAddress;
 use restate_types::retries::RetryPolicy;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_worker::SubscriptionController;
@@ -95,10 +97,10 @@ impl restate_schema_api::subscription::SubscriptionValidator for Mock {
 
 async fn generate_rest_api_doc() -> anyhow::Result<()> {
     let config = Configuration::default();
-    let openapi_address = format!(
-        "http://localhost:{}/openapi",
-        config.admin.bind_address.port()
-    );
+    let BindAddress::Socket(bind_address) = &config.admin.bind_address else {
+        bail!("The admin API must be bound to a socket address to generate the REST API doc");
+    };
+    let openapi_address = format!("http://localhost:{}/openapi", bind_address.port());
 
     // We start the Meta service, then download the openapi schema generated
     let node_env = TestCoreEnv::create_with_mock_nodes_config(1, 1).await;
@@ -124,7 +126,10 @@ async fn generate_rest_api_doc() -> anyhow::Result<()> {
         None,
         admin_service.run(
             Constant::new(config.admin),
-            NodeSvcClient::new(Channel::builder(Uri::default()).connect_lazy()),
+            NodeSvcClient::with_interceptor(
+                Channel::builder(Uri::default()).connect_lazy(),
+                BearerTokenInterceptor::default(),
+            ),
             bifrost,
         ),
     )?;